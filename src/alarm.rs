@@ -0,0 +1,275 @@
+/// Alarm subsystem for gauges in the danger zone
+/// Flashes the border of each alarming gauge and an optional banner along
+/// the bottom edge of the screen. Alarms latch until acknowledged; once an
+/// alarm stops sounding its border and the banner are painted over in the
+/// theme background on the next render.
+
+use crate::framebuffer::Framebuffer;
+use crate::colors::{GaugeStatus, Theme};
+use crate::ts_gauge::TSGauge;
+
+/// Maximum number of gauges that can be registered for alarms
+const MAX_ALARMS: usize = 16;
+
+/// Default on/off period of the alarm flash
+const DEFAULT_FLASH_PERIOD_MS: u32 = 250;
+
+/// Width of the flashing border drawn around a gauge
+const BORDER_WIDTH: u32 = 3;
+
+/// Height of the screen-edge banner
+const BANNER_HEIGHT: u32 = 16;
+
+#[derive(Copy, Clone)]
+struct Alarm {
+    /// Gauge name (`GaugeConfig::name`) this alarm watches
+    name: [u8; 64],
+    /// Gauge is currently in the danger zone
+    active: bool,
+    /// Danger was seen and not yet acknowledged
+    latched: bool,
+    /// Silenced until the gauge leaves and re-enters the danger zone
+    muted: bool,
+    /// Border is on screen and must be erased once the alarm stops sounding
+    drawn: bool,
+}
+
+impl Alarm {
+    fn name_str(&self) -> &str {
+        let len = self.name.iter().position(|&b| b == 0).unwrap_or(self.name.len());
+        core::str::from_utf8(&self.name[..len]).unwrap_or("")
+    }
+
+    fn is_sounding(&self) -> bool {
+        self.latched && !self.muted
+    }
+}
+
+pub struct AlarmManager {
+    alarms: [Option<Alarm>; MAX_ALARMS],
+    alarm_count: usize,
+    /// Time each flash state (on or off) is held
+    pub flash_period_ms: u32,
+    /// Also flash a banner along the bottom edge of the screen
+    pub show_banner: bool,
+    /// Border and banner use `danger`, and are erased with `background`
+    pub theme: Theme,
+    flash_timer_ms: u32,
+    flash_on: bool,
+    /// Banner is on screen and must be erased once every alarm stops
+    banner_drawn: bool,
+}
+
+impl AlarmManager {
+    pub const fn new() -> Self {
+        AlarmManager {
+            alarms: [None; MAX_ALARMS],
+            alarm_count: 0,
+            flash_period_ms: DEFAULT_FLASH_PERIOD_MS,
+            show_banner: true,
+            theme: Theme::DARK,
+            flash_timer_ms: 0,
+            flash_on: true,
+            banner_drawn: false,
+        }
+    }
+
+    /// Register a gauge (by `GaugeConfig` name) as alarm-worthy
+    pub fn watch(&mut self, gauge_name: &str) -> bool {
+        if self.alarm_count >= MAX_ALARMS {
+            return false;
+        }
+        let mut name = [0u8; 64];
+        let bytes = gauge_name.as_bytes();
+        let len = bytes.len().min(63);
+        name[..len].copy_from_slice(&bytes[..len]);
+
+        self.alarms[self.alarm_count] = Some(Alarm {
+            name,
+            active: false,
+            latched: false,
+            muted: false,
+            drawn: false,
+        });
+        self.alarm_count += 1;
+        true
+    }
+
+    fn find(&self, gauge: &TSGauge) -> Option<usize> {
+        let name = gauge.config.name_str();
+        (0..self.alarm_count).find(|&i| matches!(&self.alarms[i], Some(alarm) if alarm.name_str() == name))
+    }
+
+    /// Check gauge statuses and advance the flash timer by elapsed time
+    pub fn update<'a>(&mut self, gauges: impl IntoIterator<Item = &'a TSGauge>, dt_ms: u32) {
+        for gauge in gauges {
+            let index = match self.find(gauge) {
+                Some(index) => index,
+                None => continue,
+            };
+            if let Some(alarm) = self.alarms[index].as_mut() {
+                let danger = gauge.get_status() == GaugeStatus::Danger;
+                if danger && !alarm.active {
+                    // New danger event re-arms a muted alarm
+                    alarm.latched = true;
+                    alarm.muted = false;
+                }
+                alarm.active = danger;
+            }
+        }
+
+        if self.any_sounding() {
+            self.flash_timer_ms += dt_ms;
+            if self.flash_period_ms > 0 {
+                while self.flash_timer_ms >= self.flash_period_ms {
+                    self.flash_timer_ms -= self.flash_period_ms;
+                    self.flash_on = !self.flash_on;
+                }
+            }
+        } else {
+            self.flash_timer_ms = 0;
+            self.flash_on = true;
+        }
+    }
+
+    /// Silence all sounding alarms
+    /// Alarms still in danger stay muted until they clear and trip again;
+    /// latched alarms whose gauge has recovered are cleared
+    pub fn acknowledge(&mut self) {
+        for alarm in self.alarms.iter_mut().flatten() {
+            if alarm.active {
+                alarm.muted = true;
+            } else {
+                alarm.latched = false;
+                alarm.muted = false;
+            }
+        }
+    }
+
+    /// Check if any alarm is flashing
+    pub fn any_sounding(&self) -> bool {
+        self.alarms.iter().flatten().any(|alarm| alarm.is_sounding())
+    }
+
+    /// Draw flashing borders around alarming gauges and the banner, and erase
+    /// those left over from alarms that stopped sounding since the last call
+    /// Call after the gauges themselves have been rendered
+    pub fn render<'a>(&mut self, fb: &mut Framebuffer, gauges: impl IntoIterator<Item = &'a TSGauge>) {
+        let flash = if self.flash_on { self.theme.danger } else { self.theme.background };
+        let background = self.theme.background;
+
+        for gauge in gauges {
+            let alarm = match self.find(gauge) {
+                Some(index) => match self.alarms[index].as_mut() {
+                    Some(alarm) => alarm,
+                    None => continue,
+                },
+                None => continue,
+            };
+            if alarm.is_sounding() {
+                draw_border(fb, gauge, flash.to_u32());
+                alarm.drawn = true;
+            } else if alarm.drawn {
+                draw_border(fb, gauge, background.to_u32());
+                alarm.drawn = false;
+            }
+        }
+
+        let banner_y = fb.height().saturating_sub(BANNER_HEIGHT);
+        let width = fb.width();
+        if self.show_banner && self.any_sounding() {
+            fb.draw_filled_rect(0, banner_y, width, BANNER_HEIGHT, flash.to_u32());
+            self.banner_drawn = true;
+        } else if self.banner_drawn {
+            fb.draw_filled_rect(0, banner_y, width, BANNER_HEIGHT, background.to_u32());
+            self.banner_drawn = false;
+        }
+    }
+}
+
+/// Border `BORDER_WIDTH` pixels wide just outside the gauge's area
+fn draw_border(fb: &mut Framebuffer, gauge: &TSGauge, color: u32) {
+    let x = gauge.x.saturating_sub(BORDER_WIDTH);
+    let y = gauge.y.saturating_sub(BORDER_WIDTH);
+    for i in 0..BORDER_WIDTH {
+        fb.draw_rect(
+            x + i,
+            y + i,
+            (gauge.width + 2 * BORDER_WIDTH).saturating_sub(2 * i),
+            (gauge.height + 2 * BORDER_WIDTH).saturating_sub(2 * i),
+            color,
+        );
+    }
+}
+
+impl Default for AlarmManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::framebuffer::host_framebuffer;
+    use crate::ts_gauge::TSGaugeStyle;
+    use crate::ts_ini_parser::GaugeConfig;
+
+    fn coolant_gauge() -> TSGauge {
+        let mut config = GaugeConfig::new();
+        config.name[..7].copy_from_slice(b"coolant");
+        config.lo = 0.0;
+        config.hi = 100.0;
+        config.hi_warning = 80.0;
+        config.hi_danger = 90.0;
+        TSGauge::new(config, TSGaugeStyle::HorizontalBar, 50, 50, 100, 30)
+    }
+
+    #[test]
+    fn border_and_banner_erased_when_alarm_stops() {
+        let mut fb = host_framebuffer(320, 200);
+        let mut alarms = AlarmManager::new();
+        alarms.watch("coolant");
+        let mut gauges = [coolant_gauge()];
+        let danger = alarms.theme.danger.to_u32();
+        let background = alarms.theme.background.to_u32();
+        // Outermost border row, and the bottom screen row
+        let (border, banner) = ((47, 47), (10, 199));
+
+        gauges[0].set_value(95.0);
+        alarms.update(&gauges, 0);
+        alarms.render(&mut fb, &gauges);
+        assert_eq!(fb.get_pixel(border.0, border.1), Some(danger));
+        assert_eq!(fb.get_pixel(banner.0, banner.1), Some(danger));
+
+        // Recovered and acknowledged: one erase, then nothing more is drawn
+        gauges[0].set_value(50.0);
+        alarms.update(&gauges, 0);
+        alarms.acknowledge();
+        alarms.render(&mut fb, &gauges);
+        assert_eq!(fb.get_pixel(border.0, border.1), Some(background));
+        assert_eq!(fb.get_pixel(banner.0, banner.1), Some(background));
+
+        fb.draw_pixel(border.0, border.1, 0x123456);
+        alarms.render(&mut fb, &gauges);
+        assert_eq!(fb.get_pixel(border.0, border.1), Some(0x123456));
+    }
+
+    #[test]
+    fn flash_uses_theme_colors() {
+        let mut fb = host_framebuffer(320, 200);
+        let mut alarms = AlarmManager::new();
+        alarms.theme = Theme::HIGH_CONTRAST;
+        alarms.watch("coolant");
+        let mut gauges = [coolant_gauge()];
+        gauges[0].set_value(95.0);
+
+        alarms.update(&gauges, 0);
+        alarms.render(&mut fb, &gauges);
+        assert_eq!(fb.get_pixel(47, 47), Some(Theme::HIGH_CONTRAST.danger.to_u32()));
+
+        alarms.update(&gauges, DEFAULT_FLASH_PERIOD_MS);
+        alarms.render(&mut fb, &gauges);
+        assert_eq!(fb.get_pixel(47, 47), Some(Theme::HIGH_CONTRAST.background.to_u32()));
+    }
+}
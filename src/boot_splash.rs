@@ -0,0 +1,133 @@
+/// Startup splash screen with a progress bar that advances through init stages
+/// Shows where boot is if something hangs (e.g. stuck on the baud sweep)
+
+use crate::framebuffer::Framebuffer;
+use crate::colors::colors;
+use crate::digit_renderer::{draw_str, str_width};
+use crate::ts_gauge::TSGauge;
+use crate::timer;
+
+const LOGO_TEXT: &str = "LIBREDASH";
+
+/// Pixel scale of the logo text
+const LOGO_SCALE: u32 = 10;
+
+/// Pixel scale of the stage caption under the bar
+const CAPTION_SCALE: u32 = 3;
+
+const BAR_HEIGHT: u32 = 24;
+
+/// Frames in the gauge sweep self-test (up and back down)
+const SELFTEST_FRAMES: u32 = 60;
+
+/// Time between self-test frames (60 frames = about 1.5 s)
+const SELFTEST_FRAME_US: u32 = 25_000;
+
+/// Init stages in boot order; the config comes first because it names the
+/// display resolution and the ECU's serial port
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BootStage {
+    SdMount,
+    FramebufferReady,
+    ConfigParsed,
+    EcuSweep,
+    GaugesBuilt,
+}
+
+impl BootStage {
+    pub const COUNT: u32 = 5;
+
+    /// Position in boot order, starting at 0
+    pub fn index(&self) -> u32 {
+        *self as u32
+    }
+
+    /// Caption shown while the following stage runs
+    pub fn label(&self) -> &'static str {
+        match self {
+            BootStage::FramebufferReady => "DISPLAY READY",
+            BootStage::SdMount => "SD CARD CHECKED",
+            BootStage::ConfigParsed => "CONFIG LOADED",
+            BootStage::EcuSweep => "ECU SEARCH DONE",
+            BootStage::GaugesBuilt => "GAUGES READY",
+        }
+    }
+}
+
+pub struct BootProgress {
+    completed: u32,
+}
+
+impl BootProgress {
+    pub fn new() -> Self {
+        BootProgress { completed: 0 }
+    }
+
+    /// Clear the screen and draw the logo with an empty progress bar
+    pub fn draw(&self, fb: &mut Framebuffer) {
+        fb.clear(colors::BLACK.to_u32());
+
+        let logo_width = str_width(LOGO_TEXT, LOGO_SCALE);
+        let logo_x = fb.width().saturating_sub(logo_width) / 2;
+        let logo_y = (fb.height() / 2).saturating_sub(7 * LOGO_SCALE + BAR_HEIGHT * 2);
+        draw_str(fb, LOGO_TEXT, logo_x, logo_y, LOGO_SCALE, colors::WHITE);
+
+        let (x, y, width) = Self::bar_rect(fb);
+        fb.draw_rect(x, y, width, BAR_HEIGHT, colors::LIGHT_GRAY.to_u32());
+        self.draw_fill(fb);
+    }
+
+    /// Mark a stage done and redraw the bar
+    /// Stages completed out of order never move the bar backwards
+    pub fn stage_complete(&mut self, fb: &mut Framebuffer, stage: BootStage) {
+        self.completed = self.completed.max(stage.index() + 1);
+        self.draw_fill(fb);
+
+        // Replace the caption
+        let (x, y, width) = Self::bar_rect(fb);
+        let caption_y = y + BAR_HEIGHT + BAR_HEIGHT / 2;
+        fb.draw_filled_rect(x, caption_y, width, 7 * CAPTION_SCALE, colors::BLACK.to_u32());
+        let caption_x = x + width.saturating_sub(str_width(stage.label(), CAPTION_SCALE)) / 2;
+        draw_str(fb, stage.label(), caption_x, caption_y, CAPTION_SCALE, colors::LIGHT_GRAY);
+    }
+
+    /// Check if every stage has completed
+    pub fn is_complete(&self) -> bool {
+        self.completed >= BootStage::COUNT
+    }
+
+    /// Progress bar position: centered, half the screen wide
+    fn bar_rect(fb: &Framebuffer) -> (u32, u32, u32) {
+        let width = fb.width() / 2;
+        (fb.width() / 4, fb.height() / 2, width)
+    }
+
+    fn draw_fill(&self, fb: &mut Framebuffer) {
+        let (x, y, width) = Self::bar_rect(fb);
+        let inner = width.saturating_sub(4);
+        let filled = inner * self.completed / BootStage::COUNT;
+        fb.draw_filled_rect(x + 2, y + 2, filled, BAR_HEIGHT - 4, colors::GREEN.to_u32());
+    }
+}
+
+impl Default for BootProgress {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Sweep every gauge from min to max and back, like a cluster at key-on
+/// Run after the gauges are built and before the main loop starts
+pub fn gauge_selftest(fb: &mut Framebuffer, gauges: &mut [TSGauge]) {
+    // Gauges share the dashboard theme, so the first one's background is it
+    let background = gauges.first().map_or(colors::BLACK, |gauge| gauge.theme.background);
+    fb.clear(background.to_u32());
+    for frame in 0..=SELFTEST_FRAMES {
+        let progress = frame as f32 / SELFTEST_FRAMES as f32;
+        for gauge in gauges.iter_mut() {
+            gauge.run_selftest(progress);
+            gauge.render(fb);
+        }
+        timer::delay_us(SELFTEST_FRAME_US);
+    }
+}
@@ -28,6 +28,43 @@ impl Color {
             b: (color & 0xFF) as u8,
         }
     }
+
+    /// Create color from hue (degrees, wrapped into [0, 360)), saturation
+    /// and value (both 0.0-1.0)
+    pub fn from_hsv(h: f32, s: f32, v: f32) -> Self {
+        let s = s.clamp(0.0, 1.0);
+        let v = v.clamp(0.0, 1.0);
+        let mut h = h % 360.0;
+        if h < 0.0 {
+            h += 360.0;
+        }
+
+        let chroma = v * s;
+        let sector = h / 60.0;
+        let x = chroma * (1.0 - ((sector % 2.0) - 1.0).abs());
+        let m = v - chroma;
+
+        let (r, g, b) = match sector as u32 {
+            0 => (chroma, x, 0.0),
+            1 => (x, chroma, 0.0),
+            2 => (0.0, chroma, x),
+            3 => (0.0, x, chroma),
+            4 => (x, 0.0, chroma),
+            _ => (chroma, 0.0, x),
+        };
+
+        Color {
+            r: ((r + m) * 255.0 + 0.5) as u8,
+            g: ((g + m) * 255.0 + 0.5) as u8,
+            b: ((b + m) * 255.0 + 0.5) as u8,
+        }
+    }
+
+    /// Thermal-style color for a normalized value: blue (0.0) through to red (1.0)
+    pub fn from_heat(normalized: f32) -> Self {
+        let t = normalized.max(0.0).min(1.0);
+        Self::from_hsv(240.0 * (1.0 - t), 1.0, 1.0)
+    }
 }
 
 pub mod colors {
@@ -55,6 +92,81 @@ pub mod colors {
     pub const ORANGE: Color = Color { r: 255, g: 165, b: 0 };
 }
 
+/// Palette the gauges and dashboards draw with
+#[derive(Clone, Copy, Debug)]
+pub struct Theme {
+    pub background: Color,
+    /// Unfilled tracks, dial faces and other inactive areas
+    pub track: Color,
+    pub normal: Color,
+    pub warning: Color,
+    pub danger: Color,
+    /// Below operating temperature, on gauges with the cold band enabled
+    pub cold: Color,
+    pub text: Color,
+    /// Highlights such as the peak-hold marker
+    pub accent: Color,
+}
+
+impl Theme {
+    /// Default night-friendly theme: bright colors on black
+    pub const DARK: Theme = Theme {
+        background: colors::BLACK,
+        track: colors::DARK_GRAY,
+        normal: colors::GREEN,
+        warning: colors::YELLOW,
+        danger: colors::RED,
+        cold: Color { r: 64, g: 128, b: 255 },
+        text: colors::WHITE,
+        accent: colors::CYAN,
+    };
+
+    /// Dark colors on white for direct sunlight
+    pub const HIGH_CONTRAST: Theme = Theme {
+        background: colors::WHITE,
+        track: Color { r: 160, g: 160, b: 160 },
+        normal: Color { r: 0, g: 150, b: 0 },
+        warning: Color { r: 230, g: 120, b: 0 },
+        danger: Color { r: 210, g: 0, b: 0 },
+        cold: Color { r: 0, g: 70, b: 200 },
+        text: colors::BLACK,
+        accent: colors::BLUE,
+    };
+
+    /// Look up a built-in theme by config name ("dark", "high_contrast")
+    pub fn from_name(name: &str) -> Option<Self> {
+        let name = name.trim();
+        if name.eq_ignore_ascii_case("dark") {
+            Some(Theme::DARK)
+        } else if name.eq_ignore_ascii_case("high_contrast") || name.eq_ignore_ascii_case("contrast") {
+            Some(Theme::HIGH_CONTRAST)
+        } else {
+            None
+        }
+    }
+
+    /// Zone color for a gauge status
+    pub fn status_color(&self, status: GaugeStatus) -> Color {
+        match status {
+            GaugeStatus::Danger => self.danger,
+            GaugeStatus::Warning => self.warning,
+            GaugeStatus::Normal => self.normal,
+            GaugeStatus::Cold => self.cold,
+        }
+    }
+
+    /// Secondary text such as minor ticks: text faded a quarter toward the background
+    pub fn muted_text(&self) -> Color {
+        interpolate_color(self.text, self.background, 0.25)
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Theme::DARK
+    }
+}
+
 /// Gauge status based on current value relative to thresholds
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub enum GaugeStatus {
@@ -64,23 +176,92 @@ pub enum GaugeStatus {
     Warning,
     /// Value in normal operating range
     Normal,
+    /// Below operating range but not dangerously low (e.g. engine warming
+    /// up); only reported by gauges with the cold band enabled
+    Cold,
 }
 
-/// Calculate gauge color based on value and thresholds
+/// Three-zone status with the low warning zone reported as `Cold` instead
+/// Values at or below `lo_danger` stay `Danger`
+pub fn cold_band_status(status: GaugeStatus, current_value: f32, lo_warning: f32, hi_warning: f32) -> GaugeStatus {
+    match status {
+        GaugeStatus::Warning | GaugeStatus::Cold => {
+            // Which side of the normal band the value is on
+            if current_value < (lo_warning + hi_warning) / 2.0 {
+                GaugeStatus::Cold
+            } else {
+                GaugeStatus::Warning
+            }
+        }
+        other => other,
+    }
+}
+
+/// Calculate gauge color based on value and thresholds, from the theme's zone colors
 pub fn get_gauge_color(
     current_value: f32,
     lo_danger: f32,
     lo_warning: f32,
     hi_warning: f32,
     hi_danger: f32,
+    theme: &Theme,
 ) -> Color {
     if current_value <= lo_danger || current_value >= hi_danger {
-        colors::RED
+        theme.danger
     } else if current_value <= lo_warning || current_value >= hi_warning {
-        colors::YELLOW
+        theme.warning
     } else {
-        colors::GREEN
+        theme.normal
+    }
+}
+
+/// Calculate gauge color with smooth transitions between zones
+/// Fades green->yellow approaching each warning threshold and yellow->red
+/// between warning and danger. The fade-in band below a warning threshold is
+/// as wide as the warning band itself. A side whose warning and danger
+/// thresholds coincide falls back to the discrete colors.
+pub fn get_gauge_color_smooth(
+    current_value: f32,
+    lo_danger: f32,
+    lo_warning: f32,
+    hi_warning: f32,
+    hi_danger: f32,
+    theme: &Theme,
+) -> Color {
+    let hi_band = hi_danger - hi_warning;
+    let lo_band = lo_warning - lo_danger;
+
+    if current_value <= lo_danger || current_value >= hi_danger {
+        return theme.danger;
+    }
+
+    // High side
+    if hi_band > 0.0 {
+        if current_value >= hi_warning {
+            return interpolate_color(theme.warning, theme.danger, (current_value - hi_warning) / hi_band);
+        }
+        let fade_start = hi_warning - hi_band;
+        if current_value > fade_start {
+            return interpolate_color(theme.normal, theme.warning, (current_value - fade_start) / hi_band);
+        }
+    } else if current_value >= hi_warning {
+        return theme.warning;
+    }
+
+    // Low side
+    if lo_band > 0.0 {
+        if current_value <= lo_warning {
+            return interpolate_color(theme.warning, theme.danger, (lo_warning - current_value) / lo_band);
+        }
+        let fade_start = lo_warning + lo_band;
+        if current_value < fade_start {
+            return interpolate_color(theme.normal, theme.warning, (fade_start - current_value) / lo_band);
+        }
+    } else if current_value <= lo_warning {
+        return theme.warning;
     }
+
+    theme.normal
 }
 
 /// Determine gauge status
@@ -100,9 +281,43 @@ pub fn get_gauge_status(
     }
 }
 
+/// Determine gauge status, holding the previous zone until the value has
+/// moved back past its threshold by `margin` (in value units)
+/// Entering a more severe zone is never delayed
+pub fn get_gauge_status_hysteresis(
+    current_value: f32,
+    lo_danger: f32,
+    lo_warning: f32,
+    hi_warning: f32,
+    hi_danger: f32,
+    previous: GaugeStatus,
+    margin: f32,
+) -> GaugeStatus {
+    let raw = get_gauge_status(current_value, lo_danger, lo_warning, hi_warning, hi_danger);
+    // Zones widened by the margin: the status we'd keep while still close to a threshold
+    let held = get_gauge_status(
+        current_value,
+        lo_danger + margin,
+        lo_warning + margin,
+        hi_warning - margin,
+        hi_danger - margin,
+    );
+
+    let sticky = if severity(held) < severity(previous) { held } else { previous };
+    if severity(raw) > severity(sticky) { raw } else { sticky }
+}
+
+fn severity(status: GaugeStatus) -> u8 {
+    match status {
+        GaugeStatus::Normal => 0,
+        GaugeStatus::Warning | GaugeStatus::Cold => 1,
+        GaugeStatus::Danger => 2,
+    }
+}
+
 /// Interpolate between two colors based on progress (0.0 to 1.0)
 pub fn interpolate_color(color1: Color, color2: Color, progress: f32) -> Color {
-    let p = if progress < 0.0 { 0.0 } else if progress > 1.0 { 1.0 } else { progress };
+    let p = progress.clamp(0.0, 1.0);
     
     Color {
         r: (color1.r as f32 * (1.0 - p) + color2.r as f32 * p) as u8,
@@ -110,3 +325,58 @@ pub fn interpolate_color(color1: Color, color2: Color, progress: f32) -> Color {
         b: (color1.b as f32 * (1.0 - p) + color2.b as f32 * p) as u8,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rgb(c: Color) -> u32 {
+        c.to_u32()
+    }
+
+    #[test]
+    fn hsv_sector_boundaries_hit_primaries() {
+        assert_eq!(rgb(Color::from_hsv(0.0, 1.0, 1.0)), 0xFF0000);
+        assert_eq!(rgb(Color::from_hsv(60.0, 1.0, 1.0)), 0xFFFF00);
+        assert_eq!(rgb(Color::from_hsv(120.0, 1.0, 1.0)), 0x00FF00);
+        assert_eq!(rgb(Color::from_hsv(180.0, 1.0, 1.0)), 0x00FFFF);
+        assert_eq!(rgb(Color::from_hsv(240.0, 1.0, 1.0)), 0x0000FF);
+        assert_eq!(rgb(Color::from_hsv(300.0, 1.0, 1.0)), 0xFF00FF);
+    }
+
+    #[test]
+    fn hsv_mid_sectors_blend_neighbours() {
+        assert_eq!(rgb(Color::from_hsv(30.0, 1.0, 1.0)), 0xFF8000);
+        assert_eq!(rgb(Color::from_hsv(90.0, 1.0, 1.0)), 0x80FF00);
+        assert_eq!(rgb(Color::from_hsv(150.0, 1.0, 1.0)), 0x00FF80);
+        assert_eq!(rgb(Color::from_hsv(210.0, 1.0, 1.0)), 0x0080FF);
+        assert_eq!(rgb(Color::from_hsv(270.0, 1.0, 1.0)), 0x8000FF);
+        assert_eq!(rgb(Color::from_hsv(330.0, 1.0, 1.0)), 0xFF0080);
+    }
+
+    #[test]
+    fn hsv_zero_saturation_is_grayscale() {
+        for hue in [0.0, 75.0, 200.0, 359.0] {
+            assert_eq!(rgb(Color::from_hsv(hue, 0.0, 0.0)), 0x000000);
+            assert_eq!(rgb(Color::from_hsv(hue, 0.0, 0.5)), 0x808080);
+            assert_eq!(rgb(Color::from_hsv(hue, 0.0, 1.0)), 0xFFFFFF);
+        }
+    }
+
+    #[test]
+    fn hsv_hue_wraps_and_inputs_clamp() {
+        assert_eq!(rgb(Color::from_hsv(360.0, 1.0, 1.0)), 0xFF0000);
+        assert_eq!(rgb(Color::from_hsv(-120.0, 1.0, 1.0)), 0x0000FF);
+        assert_eq!(rgb(Color::from_hsv(480.0, 1.0, 1.0)), 0x00FF00);
+        assert_eq!(rgb(Color::from_hsv(0.0, 2.0, 2.0)), 0xFF0000);
+        assert_eq!(rgb(Color::from_hsv(0.0, 1.0, -1.0)), 0x000000);
+    }
+
+    #[test]
+    fn heat_runs_blue_to_red() {
+        assert_eq!(rgb(Color::from_heat(0.0)), 0x0000FF);
+        assert_eq!(rgb(Color::from_heat(0.5)), 0x00FF00);
+        assert_eq!(rgb(Color::from_heat(1.0)), 0xFF0000);
+        assert_eq!(rgb(Color::from_heat(-1.0)), 0x0000FF);
+    }
+}
@@ -8,23 +8,146 @@
 /// 3. Fall back to embedded default dashboard if SD fails
 /// 4. Load mock ECU data or connect to real MegaSquirt
 
-use crate::ts_ini_parser::GaugeConfig;
-use crate::ts_gauge::TSGaugeStyle;
+use core::ptr::addr_of_mut;
+use crate::ts_ini_parser::{GaugeConfig, GaugeLayout, IniParser, IniSection};
+use crate::ts_gauge::{BarStyle, LayoutError, TSGauge, TSGaugeStyle, TitleAlign, TitlePosition};
+use crate::framebuffer::{Framebuffer, Rotation};
+use crate::framebuffer_config::parse_resolution;
+use crate::digit_renderer::draw_str;
+use crate::fatfs::SDCard;
+use crate::colors::Theme;
+use crate::units::{stoich_for_fuel, DistanceUnit, MixtureUnit, PressureUnit, TempUnit, UnitConversion, STANDARD_ATMOSPHERE_KPA, STOICH_GASOLINE};
+use crate::math::parse_float;
+use crate::uart::UartPort;
+use crate::value_expr::{ExpressionSet, ValueExpr};
+use crate::data_source::SnapshotChannel;
+use crate::megasquirt::ChecksumMode;
+use crate::page_button::PageButton;
+
+/// Config file in the root of the boot partition (/boot/config.ini)
+const CONFIG_FILE_NAME: &str = "CONFIG.INI";
+
+/// Largest config file we will read
+const MAX_CONFIG_SIZE: usize = 16 * 1024;
+
+/// File contents and parse state for `load_from_sd_card`
+/// Statics rather than locals: together they are larger than the boot stack
+static mut CONFIG_BUFFER: [u8; MAX_CONFIG_SIZE] = [0; MAX_CONFIG_SIZE];
+static mut CONFIG_PARSER: IniParser = IniParser::new();
+
+/// TunerStudio INI for the ECU's firmware, next to config.ini
+const ECU_INI_FILE_NAME: &str = "ECU.INI";
+
+/// Largest ECU INI we will read; MS3 firmware INIs run to a few hundred KB
+const MAX_ECU_INI_SIZE: usize = 512 * 1024;
+
+/// File contents and parse state for `load_ecu_ini`
+static mut ECU_INI_BUFFER: [u8; MAX_ECU_INI_SIZE] = [0; MAX_ECU_INI_SIZE];
+static mut ECU_INI_PARSER: IniParser = IniParser::new();
+
+/// Maximum number of gauges in a dashboard
+pub const MAX_DASHBOARD_GAUGES: usize = 16;
+
+/// Screen size `DEFAULT_LAYOUT` is drawn up for
+const DEFAULT_LAYOUT_SIZE: (u32, u32) = (1280, 720);
+
+/// Fallback placement (x, y, width, height, style) when config.ini has no
+/// [Layout] entry for a gauge: two rows of three on a 1280x720 screen,
+/// scaled to the actual screen by `build_gauge`
+const DEFAULT_LAYOUT: [(u32, u32, u32, u32, TSGaugeStyle); 6] = [
+    (100, 100, 300, 300, TSGaugeStyle::Circular),
+    (550, 100, 300, 300, TSGaugeStyle::HorizontalBar),
+    (1000, 100, 240, 300, TSGaugeStyle::VerticalBar),
+    (100, 460, 300, 200, TSGaugeStyle::Digital),
+    (550, 460, 300, 200, TSGaugeStyle::Digital),
+    (1000, 460, 240, 200, TSGaugeStyle::Digital),
+];
 
 pub struct DashboardConfig {
-    pub gauges: [GaugeConfig; 16],
+    pub gauges: [GaugeConfig; MAX_DASHBOARD_GAUGES],
     pub gauge_count: usize,
     pub use_mock_ecu: bool,
     pub mock_enabled: bool,
+    /// Unit the boost gauge is displayed in; its [GaugeConfigurations]
+    /// scale and thresholds are written in this unit too
+    pub boost_unit: PressureUnit,
+    /// Atmospheric pressure subtracted from MAP for boost
+    pub atmospheric_kpa: f32,
+    /// Unit the trip meter counts in
+    pub distance_unit: DistanceUnit,
+    /// Whether the ECU's mixture channel reports AFR or lambda, when its INI
+    /// doesn't say
+    pub afr_source: MixtureUnit,
+    /// Unit the AFR gauge is displayed in; its [GaugeConfigurations] scale
+    /// and thresholds are written in this unit too
+    pub afr_unit: MixtureUnit,
+    /// Stoichiometric AFR of the fuel in use
+    pub stoich: f32,
+    /// Gauge placement from config.ini [Layout]
+    pub layout: GaugeLayout,
+    /// Palette selected by [General] theme
+    pub theme: Theme,
+    /// Fill style for bar gauges
+    pub bar_style: BarStyle,
+    /// Block count for segmented bars
+    pub bar_segments: u8,
+    /// Rounded corners on bar and digital gauge frames
+    pub rounded_frames: bool,
+    /// Blue "cold" band below operating range on temperature gauges
+    pub cold_band: bool,
+    /// Title placement for every gauge
+    pub title_align: TitleAlign,
+    pub title_position: TitlePosition,
+    /// Serial port the ECU is wired to
+    pub ecu_port: UartPort,
+    /// Reply checking for the ECU link: none, sum8, or crc32 (MS3 newserial framing)
+    pub ecu_checksum: ChecksumMode,
+    /// Debug console port from [General] console_port; None = no console
+    pub console_port: Option<UartPort>,
+    /// Display resolution from [General] resolution; None = GPU preferred mode
+    pub resolution: Option<(u32, u32)>,
+    /// How the display is mounted; applied with `Framebuffer::set_rotation`
+    pub rotation: Rotation,
+    /// BCM GPIO pin of the page button from [General] page_button; None = no button
+    pub page_button: Option<u32>,
+    /// Derived channels from config.ini [Expressions]
+    pub expressions: ExpressionSet,
+    /// Set when a configured layout was rejected and the defaults used instead
+    pub layout_error: Option<LayoutError>,
+    /// Screen the default grid is scaled to; set by `check_layout`
+    screen_size: (u32, u32),
 }
 
 impl DashboardConfig {
-    pub fn new() -> Self {
+    pub const fn new() -> Self {
         DashboardConfig {
-            gauges: [GaugeConfig::new(); 16],
+            gauges: [GaugeConfig::new(); MAX_DASHBOARD_GAUGES],
             gauge_count: 0,
             use_mock_ecu: true,
             mock_enabled: true,
+            boost_unit: PressureUnit::Psi,
+            atmospheric_kpa: STANDARD_ATMOSPHERE_KPA,
+            distance_unit: DistanceUnit::Miles,
+            afr_source: MixtureUnit::Afr,
+            afr_unit: MixtureUnit::Afr,
+            stoich: STOICH_GASOLINE,
+            layout: GaugeLayout::new(),
+            theme: Theme::DARK,
+            bar_style: BarStyle::Solid,
+            bar_segments: 20,
+            rounded_frames: false,
+            cold_band: false,
+            title_align: TitleAlign::Center,
+            title_position: TitlePosition::Below,
+            ecu_port: UartPort::PL011,
+            ecu_checksum: ChecksumMode::None,
+            console_port: Some(UartPort::PL011),
+            resolution: None,
+            rotation: Rotation::None,
+            page_button: None,
+            expressions: ExpressionSet::new(),
+            layout_error: None,
+            screen_size: DEFAULT_LAYOUT_SIZE,
         }
     }
 
@@ -62,7 +185,7 @@ impl DashboardConfig {
         coolant.name[0..7].copy_from_slice(b"coolant");
         coolant.var[0..11].copy_from_slice(b"coolantTemp");
         coolant.title[0..7].copy_from_slice(b"Coolant");
-        coolant.units[0..3].copy_from_slice(b"degF");  // Using "degF" instead of degree symbol
+        coolant.units[0..4].copy_from_slice(b"degF");  // Using "degF" instead of degree symbol
         coolant.lo = 50.0;
         coolant.hi = 250.0;
         coolant.lo_warning = 80.0;
@@ -75,11 +198,11 @@ impl DashboardConfig {
     pub fn load_extended_dashboard(&mut self) {
         self.load_default_dashboard();
         
-        if self.gauge_count < 16 {
+        if self.gauge_count < MAX_DASHBOARD_GAUGES {
             // Add more gauges...
             // Gauge 4: Oil Pressure
             let mut oil = GaugeConfig::new();
-            oil.name[0..11].copy_from_slice(b"oil_pressure");
+            oil.name[0..12].copy_from_slice(b"oil_pressure");
             oil.var[0..11].copy_from_slice(b"oilPressure");
             oil.title[0..3].copy_from_slice(b"Oil");
             oil.units[0..3].copy_from_slice(b"PSI");
@@ -96,16 +219,304 @@ impl DashboardConfig {
 
     /// Try to load configuration from SD card
     /// Returns true if SD config loaded successfully, false if using defaults
+    /// Any failure (no card, no file, no gauges) leaves the current
+    /// configuration untouched
     pub fn load_from_sd_card(&mut self) -> bool {
-        // Placeholder for actual SD card loading
-        // Would:
-        // 1. Read /boot/config.ini from SD
-        // 2. Parse INI format for gauge definitions
-        // 3. Load referenced .dash files if available
-        // 4. Set up variable mappings from mainController.ini
-        //
-        // For now, always falls back to default
-        false
+        // Safety: only the boot core runs and nothing else uses these
+        // statics, so these are the only references to them
+        let buffer = unsafe { &mut *addr_of_mut!(CONFIG_BUFFER) };
+        let parser = unsafe { &mut *addr_of_mut!(CONFIG_PARSER) };
+
+        read_ini_file(CONFIG_FILE_NAME, buffer, parser) && self.apply_ini(parser)
+    }
+
+    /// Take gauge definitions and [General] options from a parsed INI
+    /// Returns false without changing anything if it defines no gauges
+    pub fn apply_ini(&mut self, parser: &IniParser) -> bool {
+        let gauges = parser.gauges();
+        if gauges.is_empty() {
+            return false;
+        }
+
+        let count = gauges.len().min(MAX_DASHBOARD_GAUGES);
+        for i in 0..count {
+            if let Some(config) = gauges.get(i) {
+                self.gauges[i] = *config;
+            }
+        }
+        self.gauge_count = count;
+
+        self.layout = *parser.layout();
+
+        if let Some(value) = parser.setting(IniSection::General, "use_mock_ecu") {
+            self.use_mock_ecu = parse_bool(value);
+        }
+        if let Some(value) = parser.setting(IniSection::General, "mock_enabled") {
+            self.mock_enabled = parse_bool(value);
+        }
+        if let Some(value) = parser.setting(IniSection::General, "boost_unit") {
+            if let Some(unit) = PressureUnit::from_str(value) {
+                self.boost_unit = unit;
+            }
+        }
+        if let Some(value) = parser.setting(IniSection::General, "distance_unit") {
+            if let Some(unit) = DistanceUnit::from_str(value) {
+                self.distance_unit = unit;
+            }
+        }
+        if let Some(value) = parser.setting(IniSection::General, "atmospheric_kpa") {
+            let kpa = parse_float(value.trim());
+            if kpa > 0.0 {
+                self.atmospheric_kpa = kpa;
+            }
+        }
+        if let Some(value) = parser.setting(IniSection::General, "afr_source") {
+            if let Some(unit) = MixtureUnit::from_str(value) {
+                self.afr_source = unit;
+            }
+        }
+        if let Some(value) = parser.setting(IniSection::General, "afr_unit") {
+            if let Some(unit) = MixtureUnit::from_str(value) {
+                self.afr_unit = unit;
+            }
+        }
+        if let Some(value) = parser.setting(IniSection::General, "fuel") {
+            if let Some(stoich) = stoich_for_fuel(value) {
+                self.stoich = stoich;
+            }
+        }
+        if let Some(value) = parser.setting(IniSection::General, "theme") {
+            if let Some(theme) = Theme::from_name(value) {
+                self.theme = theme;
+            }
+        }
+        if let Some(value) = parser.setting(IniSection::General, "bar_style") {
+            if let Some(style) = BarStyle::from_str(value) {
+                self.bar_style = style;
+            }
+        }
+        if let Some(value) = parser.setting(IniSection::General, "bar_segments") {
+            let segments = parse_float(value.trim());
+            if segments >= 1.0 {
+                self.bar_segments = segments.min(u8::MAX as f32) as u8;
+            }
+        }
+        if let Some(value) = parser.setting(IniSection::General, "rounded_frames") {
+            self.rounded_frames = parse_bool(value);
+        }
+        if let Some(value) = parser.setting(IniSection::General, "cold_band") {
+            self.cold_band = parse_bool(value);
+        }
+        if let Some(value) = parser.setting(IniSection::General, "title_align") {
+            if let Some(align) = TitleAlign::from_str(value) {
+                self.title_align = align;
+            }
+        }
+        if let Some(value) = parser.setting(IniSection::General, "title_position") {
+            if let Some(position) = TitlePosition::from_str(value) {
+                self.title_position = position;
+            }
+        }
+        if let Some(value) = parser.setting(IniSection::General, "ecu_port") {
+            if let Some(port) = UartPort::from_name(value) {
+                self.ecu_port = port;
+            }
+        }
+        if let Some(value) = parser.setting(IniSection::General, "ecu_checksum") {
+            if let Some(mode) = ChecksumMode::from_str(value) {
+                self.ecu_checksum = mode;
+            }
+        }
+        if let Some(value) = parser.setting(IniSection::General, "console_port") {
+            if value.trim().eq_ignore_ascii_case("none") {
+                self.console_port = None;
+            } else if let Some(port) = UartPort::from_name(value) {
+                self.console_port = Some(port);
+            }
+        }
+        if let Some(value) = parser.setting(IniSection::General, "resolution") {
+            if let Some(resolution) = parse_resolution(value) {
+                self.resolution = Some(resolution);
+            }
+        }
+        if let Some(value) = parser.setting(IniSection::General, "rotation") {
+            if let Some(rotation) = Rotation::from_str(value) {
+                self.rotation = rotation;
+            }
+        }
+        if let Some(value) = parser.setting(IniSection::General, "page_button") {
+            let value = value.trim();
+            if value.eq_ignore_ascii_case("none") {
+                self.page_button = None;
+            } else if let Ok(pin) = value.parse::<u32>() {
+                if PageButton::usable_pin(pin) {
+                    self.page_button = Some(pin);
+                }
+            }
+        }
+        // Lines that don't parse are skipped rather than failing the config
+        self.expressions = ExpressionSet::new();
+        for setting in parser.settings_in(IniSection::Expressions) {
+            if let Some(expr) = ValueExpr::parse(setting.value_str()) {
+                self.expressions.add(setting.key_str(), expr);
+            }
+        }
+        // An explicit stoich overrides the fuel preset
+        if let Some(value) = parser.setting(IniSection::General, "stoich") {
+            let stoich = parse_float(value.trim());
+            if stoich > 0.0 {
+                self.stoich = stoich;
+            }
+        }
+
+        true
+    }
+
+    /// Build the renderable gauge for a configured gauge index
+    /// Uses its [Layout] entry if present, otherwise the default 6-gauge grid
+    /// slot scaled to the screen; gauges beyond the grid without a layout
+    /// entry return None
+    pub fn build_gauge(&self, index: usize) -> Option<TSGauge> {
+        if index >= self.gauge_count {
+            return None;
+        }
+        let mut config = self.gauges[index];
+        let display_unit = self.display_unit_for(&config);
+        if let Some(conversion) = display_unit {
+            // The scale is written in the display unit; take it back to the
+            // source unit so `set_display_unit` converts it with the values
+            config.convert_scale(&conversion.inverse());
+        }
+
+        let mut gauge = if let Some(entry) = self.layout.get_by_name(config.name_str()) {
+            let style = TSGaugeStyle::from_str(entry.style_str()).unwrap_or(TSGaugeStyle::Digital);
+            let mut gauge = TSGauge::new(config, style, entry.x, entry.y, entry.width, entry.height);
+            gauge.refresh_divider = entry.refresh_divider;
+            gauge
+        } else {
+            let (x, y, width, height, style) = self.default_slot(index)?;
+            TSGauge::new(config, style, x, y, width, height)
+        };
+        gauge.theme = self.theme;
+        gauge.bar_style = self.bar_style;
+        gauge.bar_segments = self.bar_segments;
+        gauge.rounded_frame = self.rounded_frames;
+        // Only temperatures have an operating range to warm up into
+        gauge.cold_band = self.cold_band && TempUnit::from_str(config.units_str()).is_some();
+        gauge.title_align = self.title_align;
+        gauge.title_position = self.title_position;
+        if let Some(conversion) = display_unit {
+            gauge.set_display_unit(conversion);
+        }
+        Some(gauge)
+    }
+
+    /// `ChannelMap` name of the channel a gauge variable reads: the canonical
+    /// name for snapshot channels and their aliases, else the variable itself
+    pub fn channel_for<'a>(&self, var: &'a str) -> &'a str {
+        if self.expressions.defines(var) {
+            return var;
+        }
+        SnapshotChannel::from_var(var).map_or(var, |channel| channel.name())
+    }
+
+    /// Conversion for gauges on a channel whose display unit is configured
+    /// An [Expressions] channel is shown as computed, whatever its name
+    fn display_unit_for(&self, config: &GaugeConfig) -> Option<UnitConversion> {
+        if self.expressions.defines(config.var_str()) {
+            return None;
+        }
+        match SnapshotChannel::from_var(config.var_str())? {
+            SnapshotChannel::Boost => Some(self.boost_display_unit()),
+            SnapshotChannel::Afr => Some(self.afr_display_unit()),
+            _ => None,
+        }
+    }
+
+    /// Default grid slot `index`, scaled from `DEFAULT_LAYOUT_SIZE` to the screen
+    fn default_slot(&self, index: usize) -> Option<(u32, u32, u32, u32, TSGaugeStyle)> {
+        let (x, y, width, height, style) = *DEFAULT_LAYOUT.get(index)?;
+        let (screen_w, screen_h) = self.screen_size;
+        let scale_x = |v: u32| (v as u64 * screen_w as u64 / DEFAULT_LAYOUT_SIZE.0 as u64) as u32;
+        let scale_y = |v: u32| (v as u64 * screen_h as u64 / DEFAULT_LAYOUT_SIZE.1 as u64) as u32;
+        Some((scale_x(x), scale_y(y), scale_x(width), scale_y(height), style))
+    }
+
+    /// Check every buildable gauge is on screen and no two overlap
+    pub fn validate_layout(&self, fb_width: u32, fb_height: u32) -> Result<(), LayoutError> {
+        for i in 0..self.gauge_count {
+            let gauge = match self.build_gauge(i) {
+                Some(gauge) => gauge,
+                None => continue,
+            };
+            gauge.validate(fb_width, fb_height)?;
+
+            for j in 0..i {
+                if let Some(other) = self.build_gauge(j) {
+                    if gauge.overlaps(&other) {
+                        return Err(LayoutError::Overlap { first: j, second: i });
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Validate the layout for the actual screen; if it is bad, drop the
+    /// [Layout] entries so gauges use the default grid, and remember the
+    /// error for `render_layout_error`. Returns false if the layout was rejected
+    /// The default grid is scaled to the screen and checked too; if even that
+    /// doesn't fit (e.g. titles on a tiny screen) its error is reported instead
+    pub fn check_layout(&mut self, fb_width: u32, fb_height: u32) -> bool {
+        self.screen_size = (fb_width, fb_height);
+        match self.validate_layout(fb_width, fb_height) {
+            Ok(()) => {
+                self.layout_error = None;
+                true
+            }
+            Err(error) => {
+                self.layout = GaugeLayout::new();
+                let fallback = self.validate_layout(fb_width, fb_height);
+                self.layout_error = Some(fallback.err().unwrap_or(error));
+                false
+            }
+        }
+    }
+
+    /// Show a rejected layout's error along the bottom of the screen
+    pub fn render_layout_error(&self, fb: &mut Framebuffer) {
+        if let Some(error) = self.layout_error {
+            let scale = 2;
+            let y = fb.height().saturating_sub(scale * 7 + 4);
+            let width = draw_str(fb, "LAYOUT ERROR: ", 4, y, scale, self.theme.danger);
+            draw_str(fb, error.message(), 4 + width, y, scale, self.theme.danger);
+        }
+    }
+
+    /// Console port to use once the ECU source is known: None while a real
+    /// ECU needs the console's peripheral or pins, since both can't share them
+    pub fn active_console_port(&self) -> Option<UartPort> {
+        match self.console_port {
+            Some(port) if !self.use_mock_ecu && port.conflicts_with(&self.ecu_port) => None,
+            port => port,
+        }
+    }
+
+    /// Conversion for gauges showing `ECUData::boost` (which is in kPa)
+    pub fn boost_display_unit(&self) -> UnitConversion {
+        UnitConversion::Pressure {
+            from: PressureUnit::Kpa,
+            to: self.boost_unit,
+        }
+    }
+
+    /// Conversion for gauges showing `EcuSnapshot::afr` (which is an AFR)
+    pub fn afr_display_unit(&self) -> UnitConversion {
+        UnitConversion::Mixture {
+            from: MixtureUnit::Afr,
+            to: self.afr_unit,
+            stoich: self.stoich,
+        }
     }
 
     /// Get gauge by name
@@ -118,70 +529,158 @@ impl DashboardConfig {
         }
         None
     }
+}
 
-    /// Map ECU variable name to gauge value
-    /// Translates from MegaSquirt OutputChannels names to gauge parameters
-    pub fn get_ecu_variable_value(
-        &self,
-        var_name: &str,
-        ecu_data: &crate::mock_ecu::MockECUData,
-    ) -> f32 {
-        match var_name {
-            "rpm" => ecu_data.rpm,
-            "map" | "mapPressure" => ecu_data.map_pressure,
-            "coolantTemp" | "coolant" => ecu_data.coolant_temp,
-            "intakeTemp" | "intake" => ecu_data.intake_temp,
-            "airFuelRatio" | "afr" => ecu_data.air_fuel_ratio,
-            "oilPressure" | "oil" => ecu_data.oil_pressure,
-            "fuelPressure" | "fuel" => ecu_data.fuel_pressure,
-            "batteryVoltage" | "battery" => ecu_data.battery_voltage,
-            "throttlePosition" | "tps" => ecu_data.throttle_position,
-            "boostPressure" | "boost" => ecu_data.boost_pressure,
-            "ignitionAdvance" | "timing" => ecu_data.ignition_advance,
-            "injectorDuty" | "injectorPw" => ecu_data.injector_duty,
-            "vehicleSpeed" | "speed" => ecu_data.vehicle_speed,
-            _ => 0.0,
-        }
+/// Read and parse the ECU INI (channel layout and realtime block size) from
+/// the SD card. None if there is no card or no ECU.INI on it
+/// Boot-time only: a second call reuses the parser the first one returned
+pub fn load_ecu_ini() -> Option<&'static IniParser> {
+    // Safety: only the boot core runs and nothing else uses these statics,
+    // and this is called once at boot, so these are the only references to them
+    let buffer = unsafe { &mut *addr_of_mut!(ECU_INI_BUFFER) };
+    let parser = unsafe { &mut *addr_of_mut!(ECU_INI_PARSER) };
+
+    if read_ini_file(ECU_INI_FILE_NAME, buffer, parser) {
+        Some(parser)
+    } else {
+        None
     }
 }
 
-/// Create default gauge objects for rendering
-pub fn create_default_gauges() -> (
-    crate::ts_gauge::TSGauge,
-    crate::ts_gauge::TSGauge,
-    crate::ts_gauge::TSGauge,
-) {
-    let config = DashboardConfig::new();
-
-    // RPM gauge - circular analog
-    let rpm_gauge = crate::ts_gauge::TSGauge::new(
-        config.gauges[0],
-        TSGaugeStyle::Circular,
-        100,
-        100,
-        300,
-        300,
-    );
-
-    // MAP gauge - horizontal bar
-    let map_gauge = crate::ts_gauge::TSGauge::new(
-        config.gauges[1],
-        TSGaugeStyle::HorizontalBar,
-        550,
-        100,
-        300,
-        300,
-    );
-
-    // Coolant gauge - vertical bar
-    let coolant_gauge = crate::ts_gauge::TSGauge::new(
-        config.gauges[2],
-        TSGaugeStyle::VerticalBar,
-        1000,
-        100,
-        240,
-        300,
-    );
-
-    (rpm_gauge, map_gauge, coolant_gauge)
+/// Read `name` from the SD card into `buffer` and parse it with `parser`
+/// Returns false if there is no card, no such file or it is empty
+fn read_ini_file(name: &str, buffer: &mut [u8], parser: &mut IniParser) -> bool {
+    let mut card = match SDCard::init() {
+        Some(card) => card,
+        None => return false,
+    };
+    let entry = match card.find_file(name) {
+        Some(entry) => entry,
+        None => return false,
+    };
+
+    let len = card.read_file(&entry, buffer);
+    if len == 0 {
+        return false;
+    }
+
+    // Tolerate a truncated multi-byte character at the end of the buffer
+    let text = match core::str::from_utf8(&buffer[..len]) {
+        Ok(text) => text,
+        Err(e) => core::str::from_utf8(&buffer[..e.valid_up_to()]).unwrap_or(""),
+    };
+
+    parser.reset();
+    parser.feed(text);
+    true
+}
+
+/// Parse an INI boolean ("true"/"yes"/"on"/"1")
+fn parse_bool(value: &str) -> bool {
+    matches!(value.trim(), "true" | "TRUE" | "True" | "yes" | "on" | "1")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ts_ini_parser::{parse_gauge_line, parse_layout_line};
+    use crate::units::convert_pressure;
+
+    fn six_gauges() -> DashboardConfig {
+        let mut config = DashboardConfig::new();
+        config.load_extended_dashboard();
+        for i in config.gauge_count..DEFAULT_LAYOUT.len() {
+            config.gauges[i] = config.gauges[0];
+            config.gauges[i].name = [0; 64];
+            config.gauges[i].name[0] = b'a' + i as u8;
+        }
+        config.gauge_count = DEFAULT_LAYOUT.len();
+        config
+    }
+
+    #[test]
+    fn default_grid_scales_to_small_screens() {
+        for (width, height) in [(1280, 720), (800, 480), (480, 320)] {
+            let mut config = six_gauges();
+            assert!(config.check_layout(width, height), "{}x{}", width, height);
+            let gauge = config.build_gauge(5).unwrap();
+            let (x, y, w, h) = gauge.bounds();
+            assert!(x + w <= width && y + h <= height);
+        }
+    }
+
+    #[test]
+    fn rejected_layout_falls_back_to_a_fitting_grid() {
+        let mut config = six_gauges();
+        let mut layout = GaugeLayout::new();
+        layout.add(parse_layout_line("tachometer = 700, 0, 300, 300, circular").unwrap());
+        config.layout = layout;
+
+        assert!(!config.check_layout(800, 480));
+        assert_eq!(config.layout_error, Some(LayoutError::OutOfBounds));
+        assert!(config.layout.is_empty());
+        assert_eq!(config.validate_layout(800, 480), Ok(()));
+    }
+
+    /// Default dashboard plus one gauge from a [GaugeConfigurations] line
+    fn with_gauge(line: &str) -> DashboardConfig {
+        let mut config = DashboardConfig::new();
+        config.load_default_dashboard();
+        config.gauges[config.gauge_count] = parse_gauge_line(line).unwrap();
+        config.gauge_count += 1;
+        config
+    }
+
+    #[test]
+    fn boost_gauge_shows_the_configured_unit() {
+        let line = r#"boostGauge = boost, "Boost", "PSI", 0, 30, 0, 0, 20, 25, 1, 0"#;
+        let mut config = with_gauge(line);
+        let mut gauge = config.build_gauge(3).unwrap();
+        assert!((gauge.config.hi - 30.0).abs() < 1e-3);
+        assert!((gauge.config.hi_danger - 25.0).abs() < 1e-3);
+        assert_eq!(gauge.config.units_str(), "PSI");
+
+        // Snapshot boost is in kPa
+        gauge.set_value(convert_pressure(10.0, PressureUnit::Psi, PressureUnit::Kpa));
+        assert!((gauge.current_value - 10.0).abs() < 1e-3);
+
+        config.boost_unit = PressureUnit::Bar;
+        let mut gauge = config.build_gauge(3).unwrap();
+        assert!((gauge.config.hi - 30.0).abs() < 1e-3);
+        gauge.set_value(150.0);
+        assert!((gauge.current_value - 1.5).abs() < 1e-3);
+        assert_eq!(gauge.config.units_str(), "bar");
+    }
+
+    #[test]
+    fn afr_gauge_shows_lambda_for_the_configured_fuel() {
+        let line = r#"lambdaGauge = afr, "Lambda", "lambda", 0.5, 1.5, 0.7, 0.8, 1.1, 1.2, 2, 1"#;
+        let mut config = with_gauge(line);
+        config.afr_unit = MixtureUnit::Lambda;
+        let mut gauge = config.build_gauge(3).unwrap();
+        assert!((gauge.config.hi - 1.5).abs() < 1e-5);
+        gauge.set_value(14.7);
+        assert!((gauge.current_value - 1.0).abs() < 1e-5);
+
+        config.stoich = crate::units::STOICH_E85;
+        let mut gauge = config.build_gauge(3).unwrap();
+        gauge.set_value(9.0);
+        assert!((gauge.current_value - 1.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn console_gives_way_to_an_ecu_on_its_port() {
+        let mut config = DashboardConfig::new();
+        assert_eq!(config.active_console_port(), Some(UartPort::PL011));
+
+        config.use_mock_ecu = false;
+        assert_eq!(config.active_console_port(), None);
+
+        config.ecu_port = UartPort::MINI;
+        assert_eq!(config.active_console_port(), None, "mini-UART is on the same pins");
+
+        config.console_port = None;
+        config.use_mock_ecu = true;
+        assert_eq!(config.active_console_port(), None);
+    }
 }
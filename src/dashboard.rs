@@ -1,7 +1,27 @@
-use crate::framebuffer::{Framebuffer, COLOR_BLACK, COLOR_RED, COLOR_GREEN, COLOR_YELLOW, COLOR_GRAY};
+use crate::colors::{Color, Theme};
+use crate::digit_renderer::{char_advance, draw_str, format_u32};
+use crate::megasquirt::{ChannelMap, LinkStats};
+use crate::framebuffer::{Framebuffer, COLOR_GREEN};
+use crate::ts_gauge::{TSGauge, TSGaugeStyle};
+use crate::ts_ini_parser::GaugeConfig;
+use crate::units::UnitConversion;
 
 const MAX_DASHBOARD_ELEMENTS: usize = 32;
 
+/// Samples kept by each graph element
+const GRAPH_HISTORY_LEN: usize = 128;
+
+/// Time span a graph made by `DashElement::graph_of` covers
+const GRAPH_WINDOW_MS: u32 = 60_000;
+
+/// Pixel scale of graph titles
+const GRAPH_LABEL_SCALE: u32 = 2;
+
+/// Maximum number of dashboard pages held by `DashboardManager`
+/// Each page holds its elements inline, so keep the manager in a static
+/// rather than on the boot stack
+const MAX_DASHBOARD_PAGES: usize = 8;
+
 #[derive(Copy, Clone, PartialEq)]
 pub enum DashElementType {
     Gauge,
@@ -10,7 +30,6 @@ pub enum DashElementType {
     Value,
 }
 
-#[derive(Copy, Clone)]
 pub struct DashElement {
     pub element_type: DashElementType,
     pub x: u32,
@@ -19,112 +38,404 @@ pub struct DashElement {
     pub height: u32,
     pub color: u32,
     pub label: [u8; 64],
+    /// Channel the element shows, looked up by `Dashboard::update_channels`;
+    /// empty for elements fed through `update_value`
+    channel: [u8; 32],
+    /// Conversion applied to graph samples, matching the gauge the graph follows
+    pub display_unit: Option<UnitConversion>,
     pub value: f32,
     pub min_value: f32,
     pub max_value: f32,
+    /// TSGauge a `Gauge` element renders through, kept between frames so
+    /// animation, peak hold and zone hysteresis carry over
+    pub gauge: Option<TSGauge>,
+    /// Time between recorded graph samples; 0 records every update
+    /// Updates in between are averaged into the next sample
+    pub sample_interval_ms: u32,
+    /// Recent values for graph elements, oldest overwritten first
+    history: [f32; GRAPH_HISTORY_LEN],
+    /// Index the next sample is written to
+    history_head: usize,
+    history_len: usize,
+    /// Sum and count of updates since the last recorded sample
+    pending_sum: f32,
+    pending_count: u32,
+    since_sample_ms: u32,
+    /// A graph sample was recorded since the graph was last drawn
+    graph_dirty: bool,
 }
 
+impl DashElement {
+    pub fn new(element_type: DashElementType, x: u32, y: u32, width: u32, height: u32, color: u32) -> Self {
+        DashElement {
+            element_type,
+            x,
+            y,
+            width,
+            height,
+            color,
+            label: [0; 64],
+            channel: [0; 32],
+            display_unit: None,
+            value: 0.0,
+            min_value: 0.0,
+            max_value: 100.0,
+            gauge: None,
+            history: [0.0; GRAPH_HISTORY_LEN],
+            history_head: 0,
+            history_len: 0,
+            sample_interval_ms: 0,
+            pending_sum: 0.0,
+            pending_count: 0,
+            since_sample_ms: 0,
+            graph_dirty: true,
+        }
+    }
+
+    /// Gauge element around an already configured `TSGauge`, reading `channel`
+    pub fn from_gauge(gauge: TSGauge, channel: &str) -> Self {
+        let mut elem = Self::new(DashElementType::Gauge, gauge.x, gauge.y, gauge.width, gauge.height, COLOR_GREEN);
+        elem.min_value = gauge.config.lo;
+        elem.max_value = gauge.config.hi;
+        elem.value = gauge.config.lo;
+        elem.set_channel(channel);
+        elem.gauge = Some(gauge);
+        elem
+    }
+
+    /// Graph of the last `GRAPH_WINDOW_MS` of `channel`, with the scale,
+    /// unit and title of `gauge`
+    pub fn graph_of(gauge: &TSGauge, channel: &str, x: u32, y: u32, width: u32, height: u32) -> Self {
+        let mut elem = Self::new(DashElementType::Graph, x, y, width, height, gauge.theme.normal.to_u32());
+        elem.min_value = gauge.config.lo;
+        elem.max_value = gauge.config.hi;
+        elem.value = gauge.config.lo;
+        elem.display_unit = gauge.display_unit;
+        elem.sample_interval_ms = GRAPH_WINDOW_MS / GRAPH_HISTORY_LEN as u32;
+        elem.set_channel(channel);
+        let title = gauge.config.title_str().as_bytes();
+        let len = title.len().min(elem.label.len() - 1);
+        elem.label[..len].copy_from_slice(&title[..len]);
+        elem
+    }
+
+    /// Bind the element to a `ChannelMap` channel; names over 32 bytes are cut
+    pub fn set_channel(&mut self, channel: &str) {
+        let bytes = channel.as_bytes();
+        let len = bytes.len().min(self.channel.len());
+        self.channel = [0; 32];
+        self.channel[..len].copy_from_slice(&bytes[..len]);
+    }
+
+    pub fn channel_str(&self) -> &str {
+        let len = self.channel.iter().position(|&b| b == 0).unwrap_or(self.channel.len());
+        core::str::from_utf8(&self.channel[..len]).unwrap_or("")
+    }
+
+    fn label_str(&self) -> &str {
+        let len = self.label.iter().position(|&b| b == 0).unwrap_or(self.label.len());
+        core::str::from_utf8(&self.label[..len]).unwrap_or("")
+    }
+
+    /// Take a new reading: graphs sample it (in their display unit), gauges show it
+    fn set_value(&mut self, value: f32) {
+        self.value = value;
+        if self.element_type == DashElementType::Graph {
+            let value = self.display_unit.map_or(value, |conversion| conversion.apply(value));
+            self.value = value;
+            self.add_sample(value);
+        }
+        if let Some(ref mut gauge) = self.gauge {
+            gauge.set_value(value);
+        }
+    }
+
+    /// Gauge element rendered by `TSGauge` with full threshold coloring
+    pub fn gauge(config: GaugeConfig, style: TSGaugeStyle, x: u32, y: u32, width: u32, height: u32) -> Self {
+        let mut elem = Self::new(DashElementType::Gauge, x, y, width, height, COLOR_GREEN);
+        elem.min_value = config.lo;
+        elem.max_value = config.hi;
+        elem.value = config.lo;
+        elem.gauge = Some(TSGauge::new(config, style, x, y, width, height));
+        elem
+    }
+
+    /// Record a sample in the graph history
+    fn push_history(&mut self, value: f32) {
+        self.graph_dirty = true;
+        self.history[self.history_head] = value;
+        self.history_head = (self.history_head + 1) % GRAPH_HISTORY_LEN;
+        if self.history_len < GRAPH_HISTORY_LEN {
+            self.history_len += 1;
+        }
+    }
+
+    /// Take a new value into the graph: recorded at once without decimation,
+    /// otherwise held for averaging until the interval elapses
+    fn add_sample(&mut self, value: f32) {
+        if self.sample_interval_ms == 0 {
+            self.push_history(value);
+        } else {
+            self.pending_sum += value;
+            self.pending_count += 1;
+        }
+    }
+
+    /// Advance the sampling clock, recording one averaged sample per elapsed interval
+    /// Intervals with no updates repeat the current value so the time axis stays true
+    fn advance_sampling(&mut self, dt_ms: u32) {
+        if self.sample_interval_ms == 0 {
+            return;
+        }
+        self.since_sample_ms = self.since_sample_ms.saturating_add(dt_ms);
+
+        let mut recorded = 0;
+        while self.since_sample_ms >= self.sample_interval_ms {
+            self.since_sample_ms -= self.sample_interval_ms;
+            let sample = if self.pending_count > 0 {
+                self.pending_sum / self.pending_count as f32
+            } else {
+                self.value
+            };
+            self.pending_sum = 0.0;
+            self.pending_count = 0;
+            self.push_history(sample);
+
+            // After a long stall the whole history is one value anyway
+            recorded += 1;
+            if recorded >= GRAPH_HISTORY_LEN {
+                self.since_sample_ms = 0;
+            }
+        }
+    }
+
+    /// Time span the full graph history covers, e.g. for a "LAST 30S" label
+    /// None without decimation, where it depends on the update rate
+    pub fn history_window_ms(&self) -> Option<u32> {
+        if self.sample_interval_ms == 0 {
+            None
+        } else {
+            Some(self.sample_interval_ms.saturating_mul(GRAPH_HISTORY_LEN as u32))
+        }
+    }
+
+    /// History sample by age order (0 = oldest retained)
+    fn history_sample(&self, index: usize) -> f32 {
+        let oldest = (self.history_head + GRAPH_HISTORY_LEN - self.history_len) % GRAPH_HISTORY_LEN;
+        self.history[(oldest + index) % GRAPH_HISTORY_LEN]
+    }
+}
+
+/// Placeholder filling unused `DashboardManager` page slots
+const EMPTY_PAGE: Dashboard = Dashboard::empty();
+
 pub struct Dashboard {
     name: [u8; 128],
     elements: [Option<DashElement>; MAX_DASHBOARD_ELEMENTS],
     element_count: usize,
+    theme: Theme,
+    /// Set when the whole page must be repainted (first frame, theme or page change)
+    full_redraw: bool,
+    /// Frames rendered, for gauge refresh dividers
+    frame: u32,
 }
 
 impl Dashboard {
     pub fn new(name: &str) -> Self {
-        let mut name_bytes = [0u8; 128];
-        let bytes = name.as_bytes();
-        let len = bytes.len().min(127);
-        name_bytes[..len].copy_from_slice(&bytes[..len]);
-        
+        let mut dashboard = Self::empty();
+        dashboard.reset(name);
+        dashboard
+    }
+
+    /// Unnamed page with no elements; const so pages can live in a static
+    pub const fn empty() -> Self {
+        const EMPTY: Option<DashElement> = None;
         Dashboard {
-            name: name_bytes,
-            elements: [None; MAX_DASHBOARD_ELEMENTS],
+            name: [0; 128],
+            elements: [EMPTY; MAX_DASHBOARD_ELEMENTS],
             element_count: 0,
+            theme: Theme::DARK,
+            full_redraw: true,
+            frame: 0,
         }
     }
-    
-    pub fn add_element(&mut self, element: DashElement) {
+
+    /// Remove every element and rename, in place (a page is too big to build
+    /// on the boot stack)
+    pub fn reset(&mut self, name: &str) {
+        self.name = [0; 128];
+        let bytes = name.as_bytes();
+        let len = bytes.len().min(127);
+        self.name[..len].copy_from_slice(&bytes[..len]);
+        for elem in self.elements.iter_mut() {
+            *elem = None;
+        }
+        self.element_count = 0;
+        self.frame = 0;
+        self.invalidate();
+    }
+
+    pub fn name_str(&self) -> &str {
+        let len = self.name.iter().position(|&b| b == 0).unwrap_or(self.name.len());
+        core::str::from_utf8(&self.name[..len]).unwrap_or("")
+    }
+
+    pub fn set_theme(&mut self, theme: Theme) {
+        self.theme = theme;
+        for gauge in self.elements[..self.element_count].iter_mut().flatten().filter_map(|elem| elem.gauge.as_mut()) {
+            gauge.theme = theme;
+        }
+        self.invalidate();
+    }
+
+    /// Repaint the whole page on the next `render`, e.g. after switching to it
+    pub fn invalidate(&mut self) {
+        self.full_redraw = true;
+    }
+
+    pub fn theme(&self) -> &Theme {
+        &self.theme
+    }
+
+    pub fn add_element(&mut self, mut element: DashElement) {
         if self.element_count < MAX_DASHBOARD_ELEMENTS {
+            if let Some(ref mut gauge) = element.gauge {
+                gauge.theme = self.theme;
+            }
             self.elements[self.element_count] = Some(element);
             self.element_count += 1;
+            self.invalidate();
         }
     }
     
     pub fn update_value(&mut self, element_id: usize, value: f32) {
         if element_id < self.element_count {
             if let Some(ref mut elem) = self.elements[element_id] {
-                elem.value = value;
+                elem.set_value(value);
             }
         }
     }
-    
-    pub fn render(&self, fb: &mut Framebuffer) {
-        // Clear background
-        fb.clear(COLOR_BLACK);
-        
-        // Render each element
-        for i in 0..self.element_count {
-            if let Some(elem) = self.elements[i] {
-                match elem.element_type {
-                    DashElementType::Gauge => self.render_gauge(&elem, fb),
-                    DashElementType::Label => self.render_label(&elem, fb),
-                    DashElementType::Value => self.render_value(&elem, fb),
-                    DashElementType::Graph => self.render_graph(&elem, fb),
-                }
+
+    /// Give every element bound to a channel `changed` accepts its latest value
+    /// Channels missing from the map leave their elements unchanged
+    pub fn update_channels(&mut self, channels: &ChannelMap, changed: impl Fn(&str) -> bool) {
+        for elem in self.elements[..self.element_count].iter_mut().flatten() {
+            let channel = elem.channel_str();
+            if !changed(channel) {
+                continue;
+            }
+            if let Some(value) = channels.get(channel) {
+                elem.set_value(value);
             }
         }
     }
-    
-    fn render_gauge(&self, elem: &DashElement, fb: &mut Framebuffer) {
-        // Draw gauge background
-        fb.draw_filled_rect(elem.x, elem.y, elem.width, elem.height, COLOR_BLACK);
-        fb.draw_rect(elem.x, elem.y, elem.width, elem.height, elem.color);
-        
-        // Calculate fill based on value
-        let mut percentage = (elem.value - elem.min_value) / (elem.max_value - elem.min_value);
-        if percentage < 0.0 {
-            percentage = 0.0;
+
+    /// Gauges on the page, e.g. for alarms
+    pub fn gauges(&self) -> impl Iterator<Item = &TSGauge> {
+        self.elements[..self.element_count].iter().flatten().filter_map(|elem| elem.gauge.as_ref())
+    }
+
+    /// Advance time-based element state (graph sampling, gauge animation) by the frame time
+    pub fn update(&mut self, dt_ms: u32) {
+        for elem in self.elements[..self.element_count].iter_mut().flatten() {
+            if elem.element_type == DashElementType::Graph {
+                elem.advance_sampling(dt_ms);
+            }
+            if let Some(ref mut gauge) = elem.gauge {
+                gauge.update_animation(dt_ms);
+            }
         }
-        if percentage > 1.0 {
-            percentage = 1.0;
+    }
+
+    /// Draw the page. The background is cleared only on a full redraw;
+    /// otherwise gauges repaint their own area when they changed and are due
+    /// on their refresh divider, and graphs when they took a sample
+    pub fn render(&mut self, fb: &mut Framebuffer) -> bool {
+        self.render_with(fb, || {})
+    }
+
+    /// `render`, calling `between` after each element, e.g. to drain a UART
+    /// FIFO during a long redraw. Returns true if the page was cleared, so
+    /// overlays drawn on top know to redraw too
+    pub fn render_with(&mut self, fb: &mut Framebuffer, mut between: impl FnMut()) -> bool {
+        let full_redraw = self.full_redraw;
+        if full_redraw {
+            fb.clear(self.theme.background.to_u32());
+            self.full_redraw = false;
         }
-        
-        let fill_width = (percentage * (elem.width - 4) as f32) as u32;
-        if fill_width > 0 && elem.height > 4 {
-            fb.draw_filled_rect(elem.x + 2, elem.y + 2, fill_width, elem.height - 4, elem.color);
+        let frame = self.frame;
+        self.frame = self.frame.wrapping_add(1);
+
+        let theme = self.theme;
+        for elem in self.elements[..self.element_count].iter_mut().flatten() {
+            match elem.element_type {
+                DashElementType::Gauge => Self::render_gauge(elem, full_redraw, frame, fb),
+                DashElementType::Label => Self::render_label(&theme, elem, fb),
+                DashElementType::Value => Self::render_value(&theme, elem, fb),
+                DashElementType::Graph => {
+                    if full_redraw || elem.graph_dirty {
+                        Self::render_graph(&theme, elem, fb);
+                        elem.graph_dirty = false;
+                    }
+                }
+            }
+            between();
+        }
+        full_redraw
+    }
+    
+    /// Index of the topmost gauge element under a screen point (title strip
+    /// included), for mapping taps to gauges
+    pub fn gauge_at(&self, x: u32, y: u32) -> Option<usize> {
+        // Later elements draw over earlier ones, so search from the top
+        (0..self.element_count).rev().find(|&i| match self.elements[i] {
+            Some(ref elem) => elem.gauge.as_ref().is_some_and(|gauge| gauge.contains(x, y)),
+            None => false,
+        })
+    }
+
+    fn render_gauge(elem: &mut DashElement, full_redraw: bool, frame: u32, fb: &mut Framebuffer) {
+        // Delegate to TSGauge so dashboards get thresholds, needles and arcs
+        if let Some(ref mut gauge) = elem.gauge {
+            if full_redraw {
+                gauge.dirty = true;
+                gauge.render(fb);
+            } else {
+                gauge.render_on_frame(fb, frame);
+            }
         }
     }
     
-    fn render_label(&self, elem: &DashElement, fb: &mut Framebuffer) {
+    fn render_label(theme: &Theme, elem: &DashElement, fb: &mut Framebuffer) {
         // Draw label box
-        fb.draw_filled_rect(elem.x, elem.y, elem.width, elem.height, COLOR_BLACK);
+        fb.draw_filled_rect(elem.x, elem.y, elem.width, elem.height, theme.background.to_u32());
         fb.draw_rect(elem.x, elem.y, elem.width, elem.height, elem.color);
     }
     
-    fn render_value(&self, elem: &DashElement, fb: &mut Framebuffer) {
+    fn render_value(theme: &Theme, elem: &DashElement, fb: &mut Framebuffer) {
         // Draw value display
-        fb.draw_filled_rect(elem.x, elem.y, elem.width, elem.height, COLOR_BLACK);
+        fb.draw_filled_rect(elem.x, elem.y, elem.width, elem.height, theme.background.to_u32());
         fb.draw_rect(elem.x, elem.y, elem.width, elem.height, elem.color);
         
         // Draw colored indicator based on value range
         let percentage = (elem.value - elem.min_value) / (elem.max_value - elem.min_value);
         let indicator_color = if percentage > 0.8 {
-            COLOR_RED
+            theme.danger
         } else if percentage > 0.6 {
-            COLOR_YELLOW
+            theme.warning
         } else {
-            COLOR_GREEN
-        };
+            theme.normal
+        }
+        .to_u32();
         
         if elem.width > 10 && elem.height > 10 {
             fb.draw_filled_rect(elem.x + 5, elem.y + 5, 20, elem.height - 10, indicator_color);
         }
     }
     
-    fn render_graph(&self, elem: &DashElement, fb: &mut Framebuffer) {
+    fn render_graph(theme: &Theme, elem: &DashElement, fb: &mut Framebuffer) {
         // Draw graph background
-        fb.draw_filled_rect(elem.x, elem.y, elem.width, elem.height, COLOR_BLACK);
+        fb.draw_filled_rect(elem.x, elem.y, elem.width, elem.height, theme.background.to_u32());
         fb.draw_rect(elem.x, elem.y, elem.width, elem.height, elem.color);
         
         // Draw grid lines
@@ -132,10 +443,207 @@ impl Dashboard {
             let y_pos = elem.y + (elem.height * i) / 4;
             let mut x = elem.x + 2;
             while x < elem.x + elem.width - 2 {
-                fb.draw_pixel(x, y_pos, COLOR_GRAY);
+                fb.draw_pixel(x, y_pos, theme.track.to_u32());
                 x += 4;
             }
         }
+
+        let label = elem.label_str();
+        if !label.is_empty() {
+            draw_str(fb, label, elem.x + 6, elem.y + 6, GRAPH_LABEL_SCALE, theme.text);
+        }
+
+        // Plot history oldest (left) to newest (right)
+        if elem.history_len < 2 || elem.width <= 4 || elem.height <= 4 {
+            return;
+        }
+        let plot_width = elem.width - 4;
+        let plot_height = elem.height - 4;
+        let range = elem.max_value - elem.min_value;
+        let sample_y = |value: f32| -> u32 {
+            let mut normalized = if range > 0.0 { (value - elem.min_value) / range } else { 0.0 };
+            if normalized < 0.0 {
+                normalized = 0.0;
+            }
+            if normalized > 1.0 {
+                normalized = 1.0;
+            }
+            elem.y + 2 + ((1.0 - normalized) * (plot_height - 1) as f32) as u32
+        };
+
+        let mut prev_x = elem.x + 2;
+        let mut prev_y = sample_y(elem.history_sample(0));
+        for i in 1..elem.history_len {
+            let x = elem.x + 2 + (i as u32 * (plot_width - 1)) / (GRAPH_HISTORY_LEN as u32 - 1);
+            let y = sample_y(elem.history_sample(i));
+
+            // Step trace: hold the previous level, then a vertical span to the new one
+            // so steep changes stay continuous
+            fb.draw_filled_rect(prev_x, prev_y, (x - prev_x).max(1), 1, elem.color);
+            let (top, bottom) = if prev_y < y { (prev_y, y) } else { (y, prev_y) };
+            fb.draw_filled_rect(x, top, 1, bottom - top + 1, elem.color);
+
+            prev_x = x;
+            prev_y = y;
+        }
+    }
+}
+
+/// Set of dashboard pages (e.g. "Street", "Track", "Diagnostics")
+/// Only the active page is rendered
+pub struct DashboardManager {
+    pages: [Dashboard; MAX_DASHBOARD_PAGES],
+    page_count: usize,
+    current: usize,
+}
+
+impl DashboardManager {
+    pub const fn new() -> Self {
+        DashboardManager {
+            pages: [EMPTY_PAGE; MAX_DASHBOARD_PAGES],
+            page_count: 0,
+            current: 0,
+        }
+    }
+
+    /// Append an empty page, set up in place, and return it for adding
+    /// elements; None if all page slots are in use
+    pub fn add_page(&mut self, name: &str) -> Option<&mut Dashboard> {
+        if self.page_count >= MAX_DASHBOARD_PAGES {
+            return None;
+        }
+        let page = &mut self.pages[self.page_count];
+        self.page_count += 1;
+        page.reset(name);
+        Some(page)
+    }
+
+    /// Page by index, active or not
+    pub fn page(&self, index: usize) -> Option<&Dashboard> {
+        self.pages[..self.page_count].get(index)
+    }
+
+    pub fn page_mut(&mut self, index: usize) -> Option<&mut Dashboard> {
+        self.pages[..self.page_count].get_mut(index)
+    }
+
+    pub fn page_count(&self) -> usize {
+        self.page_count
+    }
+
+    /// Index of the active page
+    pub fn current_index(&self) -> usize {
+        self.current
+    }
+
+    pub fn current(&self) -> Option<&Dashboard> {
+        self.pages[..self.page_count].get(self.current)
+    }
+
+    pub fn current_mut(&mut self) -> Option<&mut Dashboard> {
+        self.pages[..self.page_count].get_mut(self.current)
+    }
+
+    /// Switch to the next page, wrapping around after the last
+    pub fn next_page(&mut self) {
+        if self.page_count > 0 {
+            self.switch_to((self.current + 1) % self.page_count);
+        }
+    }
+
+    /// Switch to the previous page, wrapping around before the first
+    pub fn prev_page(&mut self) {
+        if self.page_count > 0 {
+            self.switch_to((self.current + self.page_count - 1) % self.page_count);
+        }
+    }
+
+    /// Jump directly to a page; returns false if the index is out of range
+    pub fn set_page(&mut self, index: usize) -> bool {
+        if index >= self.page_count {
+            return false;
+        }
+        self.switch_to(index);
+        true
+    }
+
+    /// Make `index` the active page and repaint it in full on the next render
+    fn switch_to(&mut self, index: usize) {
+        self.current = index;
+        if let Some(page) = self.current_mut() {
+            page.invalidate();
+        }
+    }
+
+    /// Apply a theme to every page
+    pub fn set_theme(&mut self, theme: Theme) {
+        for page in self.pages[..self.page_count].iter_mut() {
+            page.set_theme(theme);
+        }
+    }
+
+    /// Feed every page, so graphs on hidden pages keep recording
+    pub fn update_channels(&mut self, channels: &ChannelMap, changed: impl Fn(&str) -> bool) {
+        for page in self.pages[..self.page_count].iter_mut() {
+            page.update_channels(channels, &changed);
+        }
+    }
+
+    /// Gauge element under a screen point on the active page
+    pub fn gauge_at(&self, x: u32, y: u32) -> Option<usize> {
+        self.current()?.gauge_at(x, y)
+    }
+
+    /// Advance every page, so background graphs keep recording
+    pub fn update(&mut self, dt_ms: u32) {
+        for page in self.pages[..self.page_count].iter_mut() {
+            page.update(dt_ms);
+        }
+    }
+
+    /// Render the active page only
+    pub fn render_current(&mut self, fb: &mut Framebuffer) -> bool {
+        self.render_current_with(fb, || {})
+    }
+
+    /// Render the active page with `Dashboard::render_with`
+    /// Returns true if the page was cleared
+    pub fn render_current_with(&mut self, fb: &mut Framebuffer, between: impl FnMut()) -> bool {
+        match self.current_mut() {
+            Some(dashboard) => dashboard.render_with(fb, between),
+            None => false,
+        }
+    }
+}
+
+impl Default for DashboardManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Characters before the value column: the longest label plus a gap
+const LINK_STATS_LABEL_CHARS: u32 = 15;
+/// Widest value, a full u32
+const LINK_STATS_VALUE_CHARS: u32 = 10;
+
+/// Width and height `render_link_stats` draws over at `scale`
+pub fn link_stats_size(scale: u32) -> (u32, u32) {
+    let rows = LinkStats::default().rows().len() as u32;
+    ((LINK_STATS_LABEL_CHARS + LINK_STATS_VALUE_CHARS) * char_advance(scale), rows * 10 * scale)
+}
+
+/// Draw ECU link counters as a label/value table for a diagnostics page
+pub fn render_link_stats(fb: &mut Framebuffer, stats: &LinkStats, x: u32, y: u32, scale: u32, color: Color) {
+    let value_x = x + LINK_STATS_LABEL_CHARS * char_advance(scale);
+    let line_height = 10 * scale;
+
+    for (i, (label, value)) in stats.rows().iter().enumerate() {
+        let row_y = y + i as u32 * line_height;
+        draw_str(fb, label, x, row_y, scale, color);
+
+        let mut digits = [0u8; LINK_STATS_VALUE_CHARS as usize];
+        draw_str(fb, format_u32(*value, &mut digits), value_x, row_y, scale, color);
     }
 }
 
@@ -144,3 +652,111 @@ impl Dashboard {
 pub fn load_dashboard_from_dash(_dash_data: &str) -> Option<Dashboard> {
     None
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::framebuffer::host_framebuffer;
+
+    fn gauge_config() -> GaugeConfig {
+        let mut config = GaugeConfig::new();
+        config.lo = 0.0;
+        config.hi = 100.0;
+        config.hi_warning = 80.0;
+        config.hi_danger = 90.0;
+        config
+    }
+
+    fn gauge_page() -> Dashboard {
+        let mut page = Dashboard::new("test");
+        page.add_element(DashElement::gauge(gauge_config(), TSGaugeStyle::HorizontalBar, 10, 10, 200, 40));
+        page
+    }
+
+    fn gauge(page: &Dashboard) -> &TSGauge {
+        page.elements[0].as_ref().unwrap().gauge.as_ref().unwrap()
+    }
+
+    #[test]
+    fn gauge_animates_across_frames() {
+        let mut fb = host_framebuffer(320, 120);
+        let mut page = gauge_page();
+        page.render(&mut fb);
+
+        page.update_value(0, 50.0);
+        page.update(100);
+        page.render(&mut fb);
+        // Default 200 ms animation is half way, not snapped to the value
+        assert_eq!(gauge(&page).animation_progress, 0.5);
+        assert_eq!(gauge(&page).get_animated_value(), 25.0);
+
+        page.update(100);
+        page.render(&mut fb);
+        assert!(!gauge(&page).needs_render());
+    }
+
+    #[test]
+    fn background_cleared_only_on_full_redraw() {
+        let mut fb = host_framebuffer(320, 120);
+        let mut manager = DashboardManager::new();
+        for name in ["one", "two"] {
+            let page = manager.add_page(name).unwrap();
+            page.add_element(DashElement::gauge(gauge_config(), TSGaugeStyle::HorizontalBar, 10, 10, 200, 40));
+        }
+        assert!(manager.render_current(&mut fb));
+
+        // Outside every element, so only a page clear touches it
+        fb.draw_pixel(300, 100, 0xFFFFFF);
+        manager.update(20);
+        manager.render_current(&mut fb);
+        assert_eq!(fb.get_pixel(300, 100), Some(0xFFFFFF));
+
+        manager.next_page();
+        assert!(manager.render_current(&mut fb));
+        assert_eq!(fb.get_pixel(300, 100), Some(0));
+    }
+
+    #[test]
+    fn channels_feed_gauges_and_graphs_on_every_page() {
+        let gauge = TSGauge::new(gauge_config(), TSGaugeStyle::HorizontalBar, 10, 10, 200, 40);
+        let mut manager = DashboardManager::new();
+        let graph = DashElement::graph_of(&gauge, "rpm", 10, 60, 200, 40);
+        manager.add_page("gauges").unwrap().add_element(DashElement::from_gauge(gauge, "rpm"));
+        manager.add_page("graphs").unwrap().add_element(graph);
+
+        let mut channels = ChannelMap::new();
+        channels.set("rpm", 42.0);
+        manager.update_channels(&channels, |_| true);
+        manager.update(GRAPH_WINDOW_MS / GRAPH_HISTORY_LEN as u32);
+
+        let page = manager.page(0).unwrap();
+        assert_eq!(page.gauges().next().unwrap().current_value, 42.0);
+        let graph = manager.page(1).unwrap().elements[0].as_ref().unwrap();
+        assert_eq!(graph.history_len, 1);
+        assert_eq!(graph.history_sample(0), 42.0);
+    }
+
+    #[test]
+    fn gauge_hit_test_uses_stored_gauge() {
+        let page = gauge_page();
+        assert_eq!(page.gauge_at(20, 20), Some(0));
+        assert_eq!(page.gauge_at(300, 100), None);
+    }
+
+    #[test]
+    fn link_stats_stay_within_their_reported_size() {
+        let stats = LinkStats { requests: u32::MAX, packets_received: 8888, ..LinkStats::default() };
+        let (width, height) = link_stats_size(2);
+
+        let mut fb = host_framebuffer(width + 20, height + 20);
+        render_link_stats(&mut fb, &stats, 10, 10, 2, Theme::DARK.text);
+        for y in 0..fb.height() {
+            for x in 0..fb.width() {
+                let inside = (10..10 + width).contains(&x) && (10..10 + height).contains(&y);
+                if !inside {
+                    assert_eq!(fb.get_pixel(x, y), Some(0), "pixel drawn at {x},{y}");
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,165 @@
+/// Realtime data logger writing compact binary records to the SD card
+///
+/// Each session creates a new file LOGnnn.BIN in the root directory:
+///
+/// Header (16 bytes, little-endian):
+///   0  magic        "LDLG"
+///   4  version      u16 (LOG_FORMAT_VERSION)
+///   6  record_size  u16 (bytes per record)
+///   8  channels     u16 (f32 channels per record)
+///   10 reserved     u16
+///   12 sequence     u32 (session number, nnn in the file name)
+///
+/// Records (RECORD_SIZE bytes each, little-endian):
+///   timestamp_ms u32, then f32 rpm, map, tps, coolant_temp, intake_temp,
+///   afr, battery_voltage, vehicle_speed, fuel_pressure, oil_pressure,
+///   boost, ignition_advance, injector_duty
+
+use crate::fatfs::{AppendHandle, SDCard, SECTOR_SIZE};
+use crate::megasquirt::ECUData;
+
+const LOG_MAGIC: &[u8; 4] = b"LDLG";
+pub const LOG_FORMAT_VERSION: u16 = 1;
+const HEADER_SIZE: usize = 16;
+
+/// Number of f32 channels stored per record
+pub const LOG_CHANNELS: usize = 13;
+
+/// Bytes per record: timestamp plus channels
+pub const RECORD_SIZE: usize = 4 + LOG_CHANNELS * 4;
+
+/// In-memory buffer flushed to the card when full (whole sectors)
+const LOG_BUFFER_SIZE: usize = 8 * SECTOR_SIZE;
+
+/// Highest session number tried when picking a file name
+const MAX_LOG_FILES: u32 = 999;
+
+pub struct DataLogger {
+    card: SDCard,
+    file: AppendHandle,
+    sequence: u32,
+    buffer: [u8; LOG_BUFFER_SIZE],
+    buffer_len: usize,
+    records_logged: u32,
+    records_dropped: u32,
+}
+
+impl DataLogger {
+    /// Start a new log session on the first unused LOGnnn.BIN name
+    pub fn new(mut card: SDCard) -> Option<Self> {
+        let mut name = [0u8; 10];
+        let mut sequence = 0;
+        loop {
+            sequence += 1;
+            if sequence > MAX_LOG_FILES {
+                return None;
+            }
+            log_file_name(sequence, &mut name);
+            let name_str = core::str::from_utf8(&name).ok()?;
+            if card.find_file(name_str).is_none() {
+                break;
+            }
+        }
+
+        let file = card.open_append(core::str::from_utf8(&name).ok()?)?;
+
+        let mut logger = DataLogger {
+            card,
+            file,
+            sequence,
+            buffer: [0; LOG_BUFFER_SIZE],
+            buffer_len: 0,
+            records_logged: 0,
+            records_dropped: 0,
+        };
+        logger.write_header();
+        Some(logger)
+    }
+
+    fn write_header(&mut self) {
+        let mut header = [0u8; HEADER_SIZE];
+        header[0..4].copy_from_slice(LOG_MAGIC);
+        header[4..6].copy_from_slice(&LOG_FORMAT_VERSION.to_le_bytes());
+        header[6..8].copy_from_slice(&(RECORD_SIZE as u16).to_le_bytes());
+        header[8..10].copy_from_slice(&(LOG_CHANNELS as u16).to_le_bytes());
+        header[12..16].copy_from_slice(&self.sequence.to_le_bytes());
+        self.push(&header);
+    }
+
+    fn push(&mut self, bytes: &[u8]) {
+        self.buffer[self.buffer_len..self.buffer_len + bytes.len()].copy_from_slice(bytes);
+        self.buffer_len += bytes.len();
+    }
+
+    /// Append one record; flushes to the card first if the buffer is full
+    /// Returns false if a flush failed (the buffered records are dropped)
+    pub fn log(&mut self, timestamp_ms: u32, data: &ECUData) -> bool {
+        let mut ok = true;
+        if self.buffer_len + RECORD_SIZE > LOG_BUFFER_SIZE {
+            ok = self.flush();
+        }
+
+        let channels: [f32; LOG_CHANNELS] = [
+            data.rpm,
+            data.map,
+            data.tps,
+            data.coolant_temp,
+            data.intake_temp,
+            data.afr,
+            data.battery_voltage,
+            data.vehicle_speed,
+            data.fuel_pressure,
+            data.oil_pressure,
+            data.boost,
+            data.ignition_advance,
+            data.injector_duty,
+        ];
+
+        let mut record = [0u8; RECORD_SIZE];
+        record[0..4].copy_from_slice(&timestamp_ms.to_le_bytes());
+        for (i, value) in channels.iter().enumerate() {
+            let offset = 4 + i * 4;
+            record[offset..offset + 4].copy_from_slice(&value.to_le_bytes());
+        }
+        self.push(&record);
+        self.records_logged += 1;
+        ok
+    }
+
+    /// Write buffered records to the card (call before shutdown)
+    pub fn flush(&mut self) -> bool {
+        if self.buffer_len == 0 {
+            return true;
+        }
+
+        let ok = self.card.append(&mut self.file, &self.buffer[..self.buffer_len]);
+        if !ok {
+            // Don't retry forever against a failing card
+            self.records_dropped += (self.buffer_len / RECORD_SIZE) as u32;
+        }
+        self.buffer_len = 0;
+        ok
+    }
+
+    /// Session number (also the nnn in LOGnnn.BIN)
+    pub fn sequence(&self) -> u32 {
+        self.sequence
+    }
+
+    pub fn records_logged(&self) -> u32 {
+        self.records_logged
+    }
+
+    /// Records lost to failed flushes
+    pub fn records_dropped(&self) -> u32 {
+        self.records_dropped
+    }
+}
+
+/// Format "LOGnnn.BIN"
+fn log_file_name(sequence: u32, name: &mut [u8; 10]) {
+    name.copy_from_slice(b"LOG000.BIN");
+    name[3] = b'0' + (sequence / 100 % 10) as u8;
+    name[4] = b'0' + (sequence / 10 % 10) as u8;
+    name[5] = b'0' + (sequence % 10) as u8;
+}
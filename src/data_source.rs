@@ -0,0 +1,445 @@
+/// Protocol-independent ECU data source
+/// The main loop polls a `DataSource` for `EcuSnapshot`s and never needs to
+/// know whether they come from a MegaSquirt or the mock generator.
+/// Without alloc there is no `dyn`, so `EcuSource` dispatches with an enum;
+/// new protocols add a variant there.
+
+use crate::config_loader::DashboardConfig;
+use crate::megasquirt::{ChannelMap, ECUData, LinkStats, MegaSquirt, PollState};
+use crate::mock_ecu::{MockECU, MockECUData};
+use crate::ts_ini_parser::{IniParser, OutputChannels};
+use crate::units::{convert_mixture, convert_pressure, MixtureUnit, PressureUnit};
+
+/// Simulated time the mock advances on each poll (50 Hz)
+const MOCK_POLL_STEP_MS: u32 = 20;
+
+/// One complete set of channel values
+/// Temperatures in °F, pressures in kPa except fuel/oil (PSI), speed in source units,
+/// mixture as AFR
+#[derive(Clone, Copy, Debug, Default)]
+pub struct EcuSnapshot {
+    pub rpm: f32,
+    pub map: f32,
+    pub tps: f32,
+    pub coolant_temp: f32,
+    pub intake_temp: f32,
+    pub afr: f32,
+    pub battery_voltage: f32,
+    pub vehicle_speed: f32,
+    pub fuel_pressure: f32,
+    pub oil_pressure: f32,
+    /// Gauge (MAP minus atmospheric) pressure in kPa
+    pub boost: f32,
+    pub ignition_advance: f32,
+    pub injector_duty: f32,
+    /// `SnapshotChannel` bits that differ from the previous snapshot; all
+    /// set unless the source is wrapped in `ChangeTracking`
+    pub changed: u16,
+}
+
+/// One field of `EcuSnapshot`, for the `changed` bitmask
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SnapshotChannel {
+    Rpm,
+    Map,
+    Tps,
+    CoolantTemp,
+    IntakeTemp,
+    Afr,
+    BatteryVoltage,
+    VehicleSpeed,
+    FuelPressure,
+    OilPressure,
+    Boost,
+    IgnitionAdvance,
+    InjectorDuty,
+}
+
+impl SnapshotChannel {
+    pub const ALL: [SnapshotChannel; 13] = [
+        SnapshotChannel::Rpm,
+        SnapshotChannel::Map,
+        SnapshotChannel::Tps,
+        SnapshotChannel::CoolantTemp,
+        SnapshotChannel::IntakeTemp,
+        SnapshotChannel::Afr,
+        SnapshotChannel::BatteryVoltage,
+        SnapshotChannel::VehicleSpeed,
+        SnapshotChannel::FuelPressure,
+        SnapshotChannel::OilPressure,
+        SnapshotChannel::Boost,
+        SnapshotChannel::IgnitionAdvance,
+        SnapshotChannel::InjectorDuty,
+    ];
+
+    /// Channel a gauge `var` refers to, by MegaSquirt output channel name or
+    /// mock field name; None for channels the snapshot doesn't carry
+    pub fn from_var(var: &str) -> Option<Self> {
+        let channel = match var {
+            "rpm" => SnapshotChannel::Rpm,
+            "map" | "mapPressure" => SnapshotChannel::Map,
+            "tps" | "throttle" | "throttlePosition" => SnapshotChannel::Tps,
+            "coolant" | "clt" | "coolantTemp" => SnapshotChannel::CoolantTemp,
+            "mat" | "iat" | "intakeTemp" => SnapshotChannel::IntakeTemp,
+            "afr" | "afr1" | "airFuelRatio" | "lambda" | "lambda1" => SnapshotChannel::Afr,
+            "batteryVoltage" | "battery" => SnapshotChannel::BatteryVoltage,
+            "vss" | "vss1" | "vehicleSpeed" | "speed" => SnapshotChannel::VehicleSpeed,
+            "fuelPressure" | "fuelpress1" => SnapshotChannel::FuelPressure,
+            "oilPressure" | "oilpress" => SnapshotChannel::OilPressure,
+            "boost" | "boostPressure" => SnapshotChannel::Boost,
+            "advance" | "ignitionAdvance" => SnapshotChannel::IgnitionAdvance,
+            "dutyCycle1" | "dutyCycle" | "injectorDuty" => SnapshotChannel::InjectorDuty,
+            _ => return None,
+        };
+        Some(channel)
+    }
+
+    /// Name the channel is stored under in a `ChannelMap`, as the MegaSquirt INI calls it
+    pub fn name(&self) -> &'static str {
+        match self {
+            SnapshotChannel::Rpm => "rpm",
+            SnapshotChannel::Map => "map",
+            SnapshotChannel::Tps => "tps",
+            SnapshotChannel::CoolantTemp => "coolant",
+            SnapshotChannel::IntakeTemp => "mat",
+            SnapshotChannel::Afr => "afr",
+            SnapshotChannel::BatteryVoltage => "batteryVoltage",
+            SnapshotChannel::VehicleSpeed => "vss1",
+            SnapshotChannel::FuelPressure => "fuelPressure",
+            SnapshotChannel::OilPressure => "oilPressure",
+            SnapshotChannel::Boost => "boost",
+            SnapshotChannel::IgnitionAdvance => "advance",
+            SnapshotChannel::InjectorDuty => "dutyCycle1",
+        }
+    }
+
+    pub fn bit(&self) -> u16 {
+        1 << (*self as u16)
+    }
+
+    pub fn value(&self, snapshot: &EcuSnapshot) -> f32 {
+        match self {
+            SnapshotChannel::Rpm => snapshot.rpm,
+            SnapshotChannel::Map => snapshot.map,
+            SnapshotChannel::Tps => snapshot.tps,
+            SnapshotChannel::CoolantTemp => snapshot.coolant_temp,
+            SnapshotChannel::IntakeTemp => snapshot.intake_temp,
+            SnapshotChannel::Afr => snapshot.afr,
+            SnapshotChannel::BatteryVoltage => snapshot.battery_voltage,
+            SnapshotChannel::VehicleSpeed => snapshot.vehicle_speed,
+            SnapshotChannel::FuelPressure => snapshot.fuel_pressure,
+            SnapshotChannel::OilPressure => snapshot.oil_pressure,
+            SnapshotChannel::Boost => snapshot.boost,
+            SnapshotChannel::IgnitionAdvance => snapshot.ignition_advance,
+            SnapshotChannel::InjectorDuty => snapshot.injector_duty,
+        }
+    }
+}
+
+/// Every `SnapshotChannel` bit
+pub const ALL_CHANNELS: u16 = (1 << SnapshotChannel::ALL.len()) - 1;
+
+impl EcuSnapshot {
+    pub fn from_ecu_data(data: &ECUData) -> Self {
+        EcuSnapshot {
+            rpm: data.rpm,
+            map: data.map,
+            tps: data.tps,
+            coolant_temp: data.coolant_temp,
+            intake_temp: data.intake_temp,
+            afr: data.afr_as(MixtureUnit::Afr, data.stoich),
+            battery_voltage: data.battery_voltage,
+            vehicle_speed: data.vehicle_speed,
+            fuel_pressure: data.fuel_pressure,
+            oil_pressure: data.oil_pressure,
+            boost: data.boost,
+            ignition_advance: data.ignition_advance,
+            injector_duty: data.injector_duty,
+            changed: ALL_CHANNELS,
+        }
+    }
+
+    pub fn from_mock(data: &MockECUData) -> Self {
+        EcuSnapshot {
+            rpm: data.rpm,
+            map: data.map_pressure,
+            tps: data.throttle_position,
+            coolant_temp: data.coolant_temp,
+            intake_temp: data.intake_temp,
+            afr: data.air_fuel_ratio,
+            battery_voltage: data.battery_voltage,
+            vehicle_speed: data.vehicle_speed,
+            fuel_pressure: data.fuel_pressure,
+            oil_pressure: data.oil_pressure,
+            // Mock boost is in PSI
+            boost: convert_pressure(data.boost_pressure, PressureUnit::Psi, PressureUnit::Kpa),
+            ignition_advance: data.ignition_advance,
+            injector_duty: data.injector_duty,
+            changed: ALL_CHANNELS,
+        }
+    }
+
+    /// Channels whose value differs from `previous`, as `SnapshotChannel` bits
+    /// Compares the exact bits, so any change in the raw reading counts
+    pub fn changed_since(&self, previous: &EcuSnapshot) -> u16 {
+        SnapshotChannel::ALL
+            .iter()
+            .filter(|channel| channel.value(self).to_bits() != channel.value(previous).to_bits())
+            .fold(0, |mask, channel| mask | channel.bit())
+    }
+
+    pub fn is_changed(&self, channel: SnapshotChannel) -> bool {
+        self.changed & channel.bit() != 0
+    }
+
+    /// Store every field in `channels` under its `SnapshotChannel::name`
+    pub fn store_channels(&self, channels: &mut ChannelMap) {
+        for channel in SnapshotChannel::ALL.iter() {
+            channels.set(channel.name(), channel.value(self));
+        }
+    }
+}
+
+pub trait DataSource {
+    /// Advance the source; returns a snapshot when a new one is available
+    /// Must not block, so it can be called once per frame
+    fn poll(&mut self) -> Option<EcuSnapshot>;
+}
+
+/// MegaSquirt link plus the decoded values, which persist between packets so
+/// channels missing from one packet keep their last reading
+pub struct MegaSquirtSource {
+    pub ms: MegaSquirt,
+    data: ECUData,
+    /// Channel definitions from the ECU INI; empty decodes the fixed offset table
+    channels: OutputChannels,
+}
+
+impl MegaSquirtSource {
+    pub const fn new(ms: MegaSquirt) -> Self {
+        MegaSquirtSource {
+            ms,
+            data: ECUData::new(),
+            channels: OutputChannels::new(),
+        }
+    }
+
+    /// Take packet layout and channel definitions from a parsed ECU INI
+    pub fn configure_from_ini(&mut self, parser: &IniParser) {
+        self.ms.configure_from_ini(parser);
+        let source = parser.output_channels();
+        self.channels = OutputChannels::new();
+        for i in 0..source.len() {
+            if let Some(channel) = source.get(i) {
+                self.channels.add(*channel);
+            }
+        }
+    }
+
+    /// Output channels taken from the ECU INI; 0 decodes the fixed offset table
+    pub fn channel_count(&self) -> usize {
+        self.channels.len()
+    }
+
+    /// Representation of the mixture channel when the INI doesn't name it,
+    /// and the fuel's stoich for converting it
+    pub fn set_mixture(&mut self, source: MixtureUnit, stoich: f32) {
+        self.data.afr_unit = source;
+        self.data.stoich = stoich;
+    }
+
+    /// Atmospheric pressure boost is measured against when the ECU has no baro channel
+    pub fn set_atmospheric_kpa(&mut self, kpa: f32) {
+        self.data.atmospheric_kpa = kpa;
+    }
+
+    /// Values decoded from the latest packet
+    pub fn data(&self) -> &ECUData {
+        &self.data
+    }
+}
+
+impl DataSource for MegaSquirtSource {
+    fn poll(&mut self) -> Option<EcuSnapshot> {
+        match self.ms.poll_realtime() {
+            PollState::Complete => {
+                if self.channels.is_empty() {
+                    self.data.update_from_ms(&self.ms);
+                } else {
+                    self.data.update_from_channels(&self.ms, &self.channels);
+                }
+                Some(EcuSnapshot::from_ecu_data(&self.data))
+            }
+            PollState::Pending | PollState::Failed => None,
+        }
+    }
+}
+
+impl DataSource for MockECU {
+    fn poll(&mut self) -> Option<EcuSnapshot> {
+        let data = self.update(MOCK_POLL_STEP_MS);
+        let mut snapshot = EcuSnapshot::from_mock(&data);
+        snapshot.afr = convert_mixture(snapshot.afr, self.afr_output, MixtureUnit::Afr, self.stoich);
+        Some(snapshot)
+    }
+}
+
+/// Every supported source, dispatched without `dyn`
+pub enum EcuSource {
+    MegaSquirt(MegaSquirtSource),
+    Mock(MockECU),
+}
+
+impl EcuSource {
+    /// MegaSquirt on the default port; const so the source can live in a static
+    pub const fn new() -> Self {
+        EcuSource::MegaSquirt(MegaSquirtSource::new(MegaSquirt::new()))
+    }
+
+    /// Mock generator running on the configured fuel
+    pub fn mock(config: &DashboardConfig) -> Self {
+        let mut mock = MockECU::new();
+        mock.stoich = config.stoich;
+        EcuSource::Mock(mock)
+    }
+
+    /// Switch to the source selected by config.ini: the mock generator, or a
+    /// MegaSquirt on the configured port using the configured atmospheric
+    /// pressure for boost and mixture settings. Configures an existing
+    /// MegaSquirt in place
+    pub fn apply_config(&mut self, config: &DashboardConfig) {
+        if config.use_mock_ecu {
+            *self = EcuSource::mock(config);
+            return;
+        }
+        if let EcuSource::Mock(_) = self {
+            *self = EcuSource::new();
+        }
+        if let EcuSource::MegaSquirt(source) = self {
+            source.ms.set_port(config.ecu_port);
+            source.set_atmospheric_kpa(config.atmospheric_kpa);
+            source.set_mixture(config.afr_source, config.stoich);
+        }
+    }
+
+    /// Every output channel the ECU INI defines, by name; None for the mock,
+    /// whose channels are all in the snapshot
+    pub fn channels(&self) -> Option<&ChannelMap> {
+        match self {
+            EcuSource::MegaSquirt(source) => Some(&source.data().channels),
+            EcuSource::Mock(_) => None,
+        }
+    }
+
+    /// Serial link counters; None for the mock, which has no link
+    pub fn link_stats(&self) -> Option<LinkStats> {
+        match self {
+            EcuSource::MegaSquirt(source) => Some(source.ms.link_stats()),
+            EcuSource::Mock(_) => None,
+        }
+    }
+
+    /// Whether values are coming from a connected ECU
+    pub fn is_live(&self) -> bool {
+        match self {
+            EcuSource::MegaSquirt(source) => source.ms.is_connected(),
+            EcuSource::Mock(_) => false,
+        }
+    }
+
+    /// Whether values come from the mock generator
+    pub fn is_mock(&self) -> bool {
+        matches!(self, EcuSource::Mock(_))
+    }
+
+    /// Drain the ECU UART's FIFO into its ring; call often while rendering
+    /// so bytes arriving mid-frame aren't lost. Nothing to do for the mock
+    pub fn pump_rx(&mut self) {
+        if let EcuSource::MegaSquirt(source) = self {
+            source.ms.pump_rx();
+        }
+    }
+}
+
+impl DataSource for EcuSource {
+    fn poll(&mut self) -> Option<EcuSnapshot> {
+        match self {
+            EcuSource::MegaSquirt(ms) => ms.poll(),
+            EcuSource::Mock(mock) => mock.poll(),
+        }
+    }
+}
+
+/// Wraps a source so each snapshot's `changed` mask covers only the channels
+/// that moved since the last one; the first snapshot reports every channel.
+/// Lets the main loop call `set_value` only on gauges whose channel changed
+pub struct ChangeTracking<S: DataSource> {
+    source: S,
+    previous: Option<EcuSnapshot>,
+}
+
+impl<S: DataSource> ChangeTracking<S> {
+    pub const fn new(source: S) -> Self {
+        ChangeTracking { source, previous: None }
+    }
+
+    pub fn source(&self) -> &S {
+        &self.source
+    }
+
+    pub fn source_mut(&mut self) -> &mut S {
+        &mut self.source
+    }
+
+    /// Report every channel as changed on the next snapshot, e.g. after a
+    /// page switch when all gauges need their values again
+    pub fn reset(&mut self) {
+        self.previous = None;
+    }
+}
+
+impl<S: DataSource> DataSource for ChangeTracking<S> {
+    fn poll(&mut self) -> Option<EcuSnapshot> {
+        let mut snapshot = self.source.poll()?;
+        snapshot.changed = match self.previous {
+            Some(ref previous) => snapshot.changed_since(previous),
+            None => ALL_CHANNELS,
+        };
+        self.previous = Some(snapshot);
+        Some(snapshot)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ini_channels_replace_the_fixed_offsets() {
+        let mut parser = IniParser::new();
+        parser.feed("[OutputChannels]\nrpm = scalar, U16, 6, \"RPM\", 1.000, 0.0\nmap = scalar, S16, 18, \"kPa\", 0.100, 0.0\n");
+        let mut source = MegaSquirtSource::new(MegaSquirt::new());
+        assert_eq!(source.channel_count(), 0);
+
+        source.configure_from_ini(&parser);
+        assert_eq!(source.channel_count(), 2);
+
+        // Configuring again replaces rather than appends
+        source.configure_from_ini(&parser);
+        assert_eq!(source.channel_count(), 2);
+    }
+
+    #[test]
+    fn snapshot_names_map_back_to_their_channels() {
+        for channel in SnapshotChannel::ALL.iter() {
+            assert_eq!(SnapshotChannel::from_var(channel.name()), Some(*channel));
+        }
+
+        let snapshot = EcuSnapshot { rpm: 3000.0, boost: 50.0, ..EcuSnapshot::default() };
+        let mut channels = ChannelMap::new();
+        snapshot.store_channels(&mut channels);
+        assert_eq!(channels.get("rpm"), Some(3000.0));
+        assert_eq!(channels.get("boost"), Some(50.0));
+        assert_eq!(channels.len(), SnapshotChannel::ALL.len());
+    }
+}
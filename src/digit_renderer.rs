@@ -1,14 +1,15 @@
 /// Simple 7-segment style digit renderer for digital displays
-/// Renders 0-9, colon, and minus sign using rectangular segments
+/// Renders 0-9, hex A-F, colon, and minus sign using rectangular segments,
+/// plus a 5x7 bitmap font for labels and codes (`draw_char`, `draw_str`)
 
 use crate::framebuffer::Framebuffer;
 use crate::colors::Color;
 
-/// Render a single digit (0-9) at the given position
+/// Render a single digit (0-9, or 10-15 as hex A b C d E F) at the given position
 /// Size controls the pixel size of each segment
 pub fn draw_digit(fb: &mut Framebuffer, digit: u8, x: u32, y: u32, size: u32, color: Color) {
-    if digit > 9 {
-        return; // Only support 0-9
+    if digit > 15 {
+        return; // Only support 0-F
     }
 
     let digit_val = digit as usize;
@@ -20,6 +21,7 @@ pub fn draw_digit(fb: &mut Framebuffer, digit: u8, x: u32, y: u32, size: u32, co
     //  e   c
     //   ddd
     
+    // Each row lists segments in the order a, b, c, g, e, f, d
     let digits = [
         [true,  true,  true,  false, true,  true,  true],  // 0
         [false, true,  true,  false, false, false, false], // 1
@@ -31,6 +33,12 @@ pub fn draw_digit(fb: &mut Framebuffer, digit: u8, x: u32, y: u32, size: u32, co
         [true,  true,  true,  false, false, false, false], // 7
         [true,  true,  true,  true,  true,  true,  true],  // 8
         [true,  true,  true,  true,  false, true,  true],  // 9
+        [true,  true,  true,  true,  true,  true,  false], // A
+        [false, false, true,  true,  true,  true,  true],  // b
+        [true,  false, false, false, true,  true,  true],  // C
+        [false, true,  true,  true,  true,  false, true],  // d
+        [true,  false, false, true,  true,  true,  true],  // E
+        [true,  false, false, true,  true,  true,  false], // F
     ];
 
     let segs = digits[digit_val];
@@ -43,11 +51,11 @@ pub fn draw_digit(fb: &mut Framebuffer, digit: u8, x: u32, y: u32, size: u32, co
         // Segment a (top)
         fb.draw_filled_rect(x + thickness, y, size, thickness, color.to_u32());
     }
-    if segs[6] {
+    if segs[3] {
         // Segment g (middle)
         fb.draw_filled_rect(x + thickness, y + size, size, thickness, color.to_u32());
     }
-    if segs[3] {
+    if segs[6] {
         // Segment d (bottom)
         fb.draw_filled_rect(x + thickness, y + size * 2, size, thickness, color.to_u32());
     }
@@ -115,9 +123,7 @@ pub fn draw_number(
     let mut current_x = x;
     
     if is_negative {
-        // Draw minus sign
-        let thickness = digit_size / 3;
-        fb.draw_filled_rect(current_x + digit_size / 4, y + digit_size, digit_size / 2, thickness, color.to_u32());
+        draw_minus(fb, current_x, y, digit_size, color);
         current_x += digit_width;
     }
     
@@ -128,6 +134,65 @@ pub fn draw_number(
     }
 }
 
+/// Draw a minus sign occupying one digit cell
+fn draw_minus(fb: &mut Framebuffer, x: u32, y: u32, digit_size: u32, color: Color) {
+    let thickness = digit_size / 3;
+    fb.draw_filled_rect(x + digit_size / 4, y + digit_size, digit_size / 2, thickness, color.to_u32());
+}
+
+/// Render an integer right-aligned in a field of `max_digits` digit cells
+/// Unused cells on the left are blank, or zeros with `leading_zeros`.
+/// A minus sign takes one cell; values too wide for the field are clamped
+/// (e.g. 12345 in a 4-digit field shows 9999)
+pub fn draw_number_aligned(
+    fb: &mut Framebuffer,
+    value: i32,
+    max_digits: u32,
+    leading_zeros: bool,
+    x: u32,
+    y: u32,
+    digit_size: u32,
+    color: Color,
+) {
+    if max_digits == 0 {
+        return;
+    }
+
+    let is_negative = value < 0;
+    let slots = (max_digits - is_negative as u32).clamp(1, 9);
+
+    let mut limit = 1u32;
+    for _ in 0..slots {
+        limit *= 10;
+    }
+    let mut magnitude = value.unsigned_abs().min(limit - 1);
+
+    // Least significant digit first
+    let mut digits = [0u8; 9];
+    let mut digit_count = 0;
+    loop {
+        digits[digit_count] = (magnitude % 10) as u8;
+        magnitude /= 10;
+        digit_count += 1;
+        if magnitude == 0 {
+            break;
+        }
+    }
+    let shown = if leading_zeros { slots as usize } else { digit_count };
+
+    let digit_width = digit_size + digit_size / 2;
+    let mut current_x = x + max_digits * digit_width - shown as u32 * digit_width;
+
+    if is_negative {
+        draw_minus(fb, current_x.saturating_sub(digit_width), y, digit_size, color);
+    }
+
+    for i in (0..shown).rev() {
+        draw_digit(fb, digits[i], current_x, y, digit_size, color);
+        current_x += digit_width;
+    }
+}
+
 /// Render a floating point number as digital display
 pub fn draw_float(
     fb: &mut Framebuffer,
@@ -142,28 +207,30 @@ pub fn draw_float(
     let is_negative = value < 0.0;
     let abs_val = if is_negative { -value } else { value };
     
-    // Split into integer and decimal parts
-    let integer_part = abs_val as i32;
-    
     // Calculate 10^decimal_digits manually without powi
-    let mut multiplier = 1.0_f32;
+    let mut multiplier = 1u32;
     for _ in 0..decimal_digits {
-        multiplier *= 10.0;
+        multiplier *= 10;
     }
-    let decimal_part = ((abs_val - integer_part as f32) * multiplier) as i32;
+
+    // Round once at the last shown decimal, then split into integer and
+    // decimal parts (the fraction of 12.3 times 10 truncates to 2)
+    let scaled = (abs_val * multiplier as f32 + 0.5) as u32;
+    let integer_part = (scaled / multiplier) as i32;
+    let decimal_part = (scaled % multiplier) as i32;
     
     let digit_width = digit_size + digit_size / 2;
     let mut current_x = x;
     
-    // Draw minus sign if needed
-    if is_negative {
-        let thickness = digit_size / 3;
-        fb.draw_filled_rect(current_x + digit_size / 4, current_x + digit_size, digit_size / 2, thickness, color.to_u32());
+    // Draw minus sign if needed, in its own cell like draw_number
+    // (skipped when the value rounds to zero)
+    if is_negative && scaled > 0 {
+        draw_minus(fb, current_x, y, digit_size, color);
         current_x += digit_width;
     }
     
-    // Draw integer part
-    draw_number(fb, integer_part, integer_digits, current_x, y, digit_size, color);
+    // Draw integer part right-aligned so the decimal point stays put
+    draw_number_aligned(fb, integer_part, integer_digits, false, current_x, y, digit_size, color);
     current_x += integer_digits * digit_width;
     
     // Draw decimal point
@@ -172,7 +239,176 @@ pub fn draw_float(
         fb.draw_filled_rect(current_x, y + digit_size * 2 - dot_size, dot_size, dot_size, color.to_u32());
         current_x += digit_width / 2;
         
-        // Draw decimal digits
-        draw_number(fb, decimal_part, decimal_digits, current_x, y, digit_size, color);
+        // Draw decimal digits, keeping zeros after the point (0.05 -> "05")
+        draw_number_aligned(fb, decimal_part, decimal_digits, true, current_x, y, digit_size, color);
+    }
+}
+
+/// Columns per bitmap glyph; one more column of spacing is added between glyphs
+const GLYPH_WIDTH: u32 = 5;
+const GLYPH_HEIGHT: usize = 7;
+
+/// 5x7 bitmap rows for a character, MSB (bit 4) is the leftmost column
+/// Lowercase letters render as uppercase
+fn glyph(ch: char) -> Option<[u8; GLYPH_HEIGHT]> {
+    let rows = match ch.to_ascii_uppercase() {
+        'A' => [0b01110, 0b10001, 0b10001, 0b11111, 0b10001, 0b10001, 0b10001],
+        'B' => [0b11110, 0b10001, 0b10001, 0b11110, 0b10001, 0b10001, 0b11110],
+        'C' => [0b01110, 0b10001, 0b10000, 0b10000, 0b10000, 0b10001, 0b01110],
+        'D' => [0b11110, 0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b11110],
+        'E' => [0b11111, 0b10000, 0b10000, 0b11110, 0b10000, 0b10000, 0b11111],
+        'F' => [0b11111, 0b10000, 0b10000, 0b11110, 0b10000, 0b10000, 0b10000],
+        'G' => [0b01110, 0b10001, 0b10000, 0b10111, 0b10001, 0b10001, 0b01111],
+        'H' => [0b10001, 0b10001, 0b10001, 0b11111, 0b10001, 0b10001, 0b10001],
+        'I' => [0b01110, 0b00100, 0b00100, 0b00100, 0b00100, 0b00100, 0b01110],
+        'J' => [0b00111, 0b00010, 0b00010, 0b00010, 0b00010, 0b10010, 0b01100],
+        'K' => [0b10001, 0b10010, 0b10100, 0b11000, 0b10100, 0b10010, 0b10001],
+        'L' => [0b10000, 0b10000, 0b10000, 0b10000, 0b10000, 0b10000, 0b11111],
+        'M' => [0b10001, 0b11011, 0b10101, 0b10101, 0b10001, 0b10001, 0b10001],
+        'N' => [0b10001, 0b10001, 0b11001, 0b10101, 0b10011, 0b10001, 0b10001],
+        'O' => [0b01110, 0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b01110],
+        'P' => [0b11110, 0b10001, 0b10001, 0b11110, 0b10000, 0b10000, 0b10000],
+        'Q' => [0b01110, 0b10001, 0b10001, 0b10001, 0b10101, 0b10010, 0b01101],
+        'R' => [0b11110, 0b10001, 0b10001, 0b11110, 0b10100, 0b10010, 0b10001],
+        'S' => [0b01111, 0b10000, 0b10000, 0b01110, 0b00001, 0b00001, 0b11110],
+        'T' => [0b11111, 0b00100, 0b00100, 0b00100, 0b00100, 0b00100, 0b00100],
+        'U' => [0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b01110],
+        'V' => [0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b01010, 0b00100],
+        'W' => [0b10001, 0b10001, 0b10001, 0b10101, 0b10101, 0b10101, 0b01010],
+        'X' => [0b10001, 0b10001, 0b01010, 0b00100, 0b01010, 0b10001, 0b10001],
+        'Y' => [0b10001, 0b10001, 0b01010, 0b00100, 0b00100, 0b00100, 0b00100],
+        'Z' => [0b11111, 0b00001, 0b00010, 0b00100, 0b01000, 0b10000, 0b11111],
+        '0' => [0b01110, 0b10001, 0b10011, 0b10101, 0b11001, 0b10001, 0b01110],
+        '1' => [0b00100, 0b01100, 0b00100, 0b00100, 0b00100, 0b00100, 0b01110],
+        '2' => [0b01110, 0b10001, 0b00001, 0b00010, 0b00100, 0b01000, 0b11111],
+        '3' => [0b11111, 0b00010, 0b00100, 0b00010, 0b00001, 0b10001, 0b01110],
+        '4' => [0b00010, 0b00110, 0b01010, 0b10010, 0b11111, 0b00010, 0b00010],
+        '5' => [0b11111, 0b10000, 0b11110, 0b00001, 0b00001, 0b10001, 0b01110],
+        '6' => [0b00110, 0b01000, 0b10000, 0b11110, 0b10001, 0b10001, 0b01110],
+        '7' => [0b11111, 0b00001, 0b00010, 0b00100, 0b01000, 0b01000, 0b01000],
+        '8' => [0b01110, 0b10001, 0b10001, 0b01110, 0b10001, 0b10001, 0b01110],
+        '9' => [0b01110, 0b10001, 0b10001, 0b01111, 0b00001, 0b00010, 0b01100],
+        '-' => [0b00000, 0b00000, 0b00000, 0b11111, 0b00000, 0b00000, 0b00000],
+        '.' => [0b00000, 0b00000, 0b00000, 0b00000, 0b00000, 0b01100, 0b01100],
+        ':' => [0b00000, 0b01100, 0b01100, 0b00000, 0b01100, 0b01100, 0b00000],
+        '/' => [0b00000, 0b00001, 0b00010, 0b00100, 0b01000, 0b10000, 0b00000],
+        '%' => [0b11000, 0b11001, 0b00010, 0b00100, 0b01000, 0b10011, 0b00011],
+        ' ' => [0; GLYPH_HEIGHT],
+        _ => return None,
+    };
+    Some(rows)
+}
+
+/// Horizontal advance of one bitmap glyph at the given pixel scale
+pub fn char_advance(scale: u32) -> u32 {
+    (GLYPH_WIDTH + 1) * scale
+}
+
+/// Render one character from the 5x7 font; each font pixel is `scale` x `scale`
+/// Unsupported characters draw nothing but still advance.
+/// Returns the advance width so callers can lay out strings
+pub fn draw_char(fb: &mut Framebuffer, ch: char, x: u32, y: u32, scale: u32, color: Color) -> u32 {
+    if let Some(rows) = glyph(ch) {
+        for (row, bits) in rows.iter().enumerate() {
+            for col in 0..GLYPH_WIDTH {
+                if bits & (1 << (GLYPH_WIDTH - 1 - col)) != 0 {
+                    fb.draw_filled_rect(
+                        x + col * scale,
+                        y + row as u32 * scale,
+                        scale,
+                        scale,
+                        color.to_u32(),
+                    );
+                }
+            }
+        }
+    }
+    char_advance(scale)
+}
+
+/// Render a string left to right; returns the total width drawn
+pub fn draw_str(fb: &mut Framebuffer, text: &str, x: u32, y: u32, scale: u32, color: Color) -> u32 {
+    let mut current_x = x;
+    for ch in text.chars() {
+        current_x += draw_char(fb, ch, current_x, y, scale, color);
+    }
+    current_x - x
+}
+
+/// Width `draw_str` would use for a string, for centering or right alignment
+pub fn str_width(text: &str, scale: u32) -> u32 {
+    text.chars().count() as u32 * char_advance(scale)
+}
+
+/// Format a number in decimal into `buf`, returning the text
+pub fn format_u32(mut value: u32, buf: &mut [u8; 10]) -> &str {
+    let mut start = buf.len();
+    loop {
+        start -= 1;
+        buf[start] = b'0' + (value % 10) as u8;
+        value /= 10;
+        if value == 0 {
+            break;
+        }
+    }
+    core::str::from_utf8(&buf[start..]).unwrap_or("")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::framebuffer::host_framebuffer;
+
+    /// Inclusive (min_x, min_y, max_x, max_y) of non-black pixels in the columns
+    fn lit_bounds(fb: &Framebuffer, columns: core::ops::Range<u32>) -> Option<(u32, u32, u32, u32)> {
+        let mut bounds: Option<(u32, u32, u32, u32)> = None;
+        for x in columns {
+            for y in 0..fb.height() {
+                if fb.get_pixel(x, y).unwrap_or(0) != 0 {
+                    bounds = Some(match bounds {
+                        None => (x, y, x, y),
+                        Some((x0, y0, x1, y1)) => (x0.min(x), y0.min(y), x1.max(x), y1.max(y)),
+                    });
+                }
+            }
+        }
+        bounds
+    }
+
+    #[test]
+    fn negative_float_extents() {
+        let mut fb = host_framebuffer(320, 120);
+        // size 8: 12 px digit cells, segments 4 px thick, glyphs 20 px tall
+        draw_float(&mut fb, -12.3, 2, 1, 100, 50, 8, Color::new(255, 255, 255));
+
+        // Minus sign sits mid-height in the first cell (x + 2..6, y + 8..10)
+        assert_eq!(lit_bounds(&fb, 100..112), Some((102, 58, 105, 59)));
+        // Whole value stays on the glyph rows, from the minus to the last decimal
+        assert_eq!(lit_bounds(&fb, 0..320), Some((102, 50, 157, 69)));
+    }
+
+    #[test]
+    fn negative_float_aligns_with_positive_after_sign_cell() {
+        let white = Color::new(255, 255, 255);
+        let mut negative = host_framebuffer(320, 120);
+        draw_float(&mut negative, -12.3, 2, 1, 100, 50, 8, white);
+        let mut positive = host_framebuffer(320, 120);
+        draw_float(&mut positive, 12.3, 2, 1, 112, 50, 8, white);
+
+        // Digits and decimal point land on the same pixels once the sign cell is skipped
+        for x in 112..320 {
+            for y in 0..120 {
+                assert_eq!(negative.get_pixel(x, y), positive.get_pixel(x, y), "pixel ({}, {})", x, y);
+            }
+        }
+        assert_eq!(lit_bounds(&positive, 0..112), None);
+    }
+
+    #[test]
+    fn format_u32_writes_decimal() {
+        let mut buf = [0u8; 10];
+        assert_eq!(format_u32(0, &mut buf), "0");
+        assert_eq!(format_u32(1280, &mut buf), "1280");
+        assert_eq!(format_u32(u32::MAX, &mut buf), "4294967295");
     }
 }
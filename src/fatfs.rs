@@ -2,7 +2,99 @@
 /// Simplified implementation for bare-metal Raspberry Pi
 /// Handles basic file reading from SD card
 
-use core::mem;
+use crate::mmio::{mmio_read, mmio_write};
+
+// BCM2835 EMMC (Arasan SDHCI) controller
+const EMMC_BASE: u32 = 0x3F300000;
+const EMMC_BLKSIZECNT: u32 = EMMC_BASE + 0x04;
+const EMMC_ARG1: u32 = EMMC_BASE + 0x08;
+const EMMC_CMDTM: u32 = EMMC_BASE + 0x0C;
+const EMMC_RESP0: u32 = EMMC_BASE + 0x10;
+const EMMC_DATA: u32 = EMMC_BASE + 0x20;
+const EMMC_STATUS: u32 = EMMC_BASE + 0x24;
+const EMMC_CONTROL0: u32 = EMMC_BASE + 0x28;
+const EMMC_CONTROL1: u32 = EMMC_BASE + 0x2C;
+const EMMC_INTERRUPT: u32 = EMMC_BASE + 0x30;
+const EMMC_IRPT_MASK: u32 = EMMC_BASE + 0x34;
+const EMMC_IRPT_EN: u32 = EMMC_BASE + 0x38;
+
+// GPIO routing for the SD card slot (GPIO 48-53, ALT3)
+const GPIO_BASE: u32 = 0x3F200000;
+const GPFSEL4: u32 = GPIO_BASE + 0x10;
+const GPFSEL5: u32 = GPIO_BASE + 0x14;
+const GPPUD: u32 = GPIO_BASE + 0x94;
+const GPPUDCLK1: u32 = GPIO_BASE + 0x9C;
+
+// CMDTM encodings (command index, response type, data direction)
+const CMD_GO_IDLE: u32 = 0x00000000;
+const CMD_ALL_SEND_CID: u32 = 0x02010000;
+const CMD_SEND_REL_ADDR: u32 = 0x03020000;
+const CMD_CARD_SELECT: u32 = 0x07030000;
+const CMD_SEND_IF_COND: u32 = 0x08020000;
+const CMD_READ_SINGLE: u32 = 0x11220010;
+const CMD_WRITE_SINGLE: u32 = 0x18220000;
+const CMD_APP_CMD: u32 = 0x37000000;
+const CMD_SEND_OP_COND: u32 = 0x29020000;
+
+// STATUS bits
+const SR_CMD_INHIBIT: u32 = 1 << 0;
+const SR_DAT_INHIBIT: u32 = 1 << 1;
+
+// INTERRUPT bits
+const INT_CMD_DONE: u32 = 1 << 0;
+const INT_DATA_DONE: u32 = 1 << 1;
+const INT_WRITE_RDY: u32 = 1 << 4;
+const INT_READ_RDY: u32 = 1 << 5;
+const INT_ERROR_MASK: u32 = 0x017E8000;
+
+// CONTROL1 bits
+const C1_CLK_INTLEN: u32 = 1 << 0;
+const C1_CLK_STABLE: u32 = 1 << 1;
+const C1_CLK_EN: u32 = 1 << 2;
+const C1_TOUNIT_MAX: u32 = 0xE << 16;
+const C1_SRST_HC: u32 = 1 << 24;
+
+/// EMMC base clock as configured by the firmware
+const EMMC_BASE_CLOCK_HZ: u32 = 41_666_666;
+/// Identification-mode clock
+const EMMC_INIT_CLOCK_HZ: u32 = 400_000;
+/// Data-transfer clock
+const EMMC_TRANSFER_CLOCK_HZ: u32 = 25_000_000;
+
+/// ACMD41 argument: SDHC support + 3.2-3.4V window
+const ACMD41_ARG_HC: u32 = 0x51FF8000;
+const ACMD41_READY: u32 = 1 << 31;
+const ACMD41_CCS: u32 = 1 << 30;
+
+/// Polls before a command or data transfer is abandoned
+const EMMC_TIMEOUT_POLLS: u32 = 1_000_000;
+
+pub const SECTOR_SIZE: usize = 512;
+
+/// Directory entry size and markers
+const DIR_ENTRY_SIZE: usize = 32;
+const DIR_END: u8 = 0x00;
+const DIR_DELETED: u8 = 0xE5;
+const ATTR_VOLUME_ID: u8 = 0x08;
+const ATTR_DIRECTORY: u8 = 0x10;
+const ATTR_ARCHIVE: u8 = 0x20;
+const ATTR_LONG_NAME: u8 = 0x0F;
+
+/// VFAT long-name entries: ordinal flag on the physically first (last logical) entry
+const LFN_LAST_ENTRY: u8 = 0x40;
+const LFN_ORDINAL_MASK: u8 = 0x1F;
+/// UTF-16 characters per entry and their byte offsets within it
+const LFN_CHARS_PER_ENTRY: usize = 13;
+const LFN_CHAR_OFFSETS: [usize; LFN_CHARS_PER_ENTRY] = [1, 3, 5, 7, 9, 14, 16, 18, 20, 22, 24, 28, 30];
+/// 255-character names need 20 entries
+const LFN_MAX_ENTRIES: usize = 20;
+
+/// FAT32 cluster values at or above this mark the end of a chain
+const FAT32_EOC: u32 = 0x0FFFFFF8;
+const FAT32_BAD_CLUSTER: u32 = 0x0FFFFFF7;
+/// Value written to terminate a cluster chain
+const FAT32_EOC_MARK: u32 = 0x0FFFFFFF;
+const FAT32_FREE: u32 = 0;
 
 #[repr(C)]
 #[derive(Copy, Clone)]
@@ -53,6 +145,176 @@ pub struct DirEntry {
     pub file_size: u32,
 }
 
+impl DirEntry {
+    /// Decode a 32-byte on-disk directory entry
+    pub fn parse(data: &[u8]) -> Self {
+        let mut name = [0u8; 11];
+        name.copy_from_slice(&data[0..11]);
+        DirEntry {
+            name,
+            attrib: data[11],
+            reserved: data[12],
+            create_time_tenth: data[13],
+            create_time: read_u16(data, 14),
+            create_date: read_u16(data, 16),
+            access_date: read_u16(data, 18),
+            cluster_high: read_u16(data, 20),
+            write_time: read_u16(data, 22),
+            write_date: read_u16(data, 24),
+            cluster_low: read_u16(data, 26),
+            file_size: read_u32(data, 28),
+        }
+    }
+
+    /// First cluster of the file's data
+    pub fn first_cluster(&self) -> u32 {
+        ((self.cluster_high as u32) << 16) | (self.cluster_low as u32)
+    }
+
+    pub fn is_directory(&self) -> bool {
+        self.attrib & ATTR_DIRECTORY != 0
+    }
+
+    /// Encode back into the 32-byte on-disk layout
+    fn write_to(&self, data: &mut [u8]) {
+        data[0..11].copy_from_slice(&self.name);
+        data[11] = self.attrib;
+        data[12] = self.reserved;
+        data[13] = self.create_time_tenth;
+        write_u16(data, 14, self.create_time);
+        write_u16(data, 16, self.create_date);
+        write_u16(data, 18, self.access_date);
+        write_u16(data, 20, self.cluster_high);
+        write_u16(data, 22, self.write_time);
+        write_u16(data, 24, self.write_date);
+        write_u16(data, 26, self.cluster_low);
+        write_u32(data, 28, self.file_size);
+    }
+
+    fn set_first_cluster(&mut self, cluster: u32) {
+        self.cluster_high = (cluster >> 16) as u16;
+        self.cluster_low = cluster as u16;
+    }
+}
+
+/// Where a directory entry lives on the volume, so it can be rewritten
+#[derive(Copy, Clone)]
+struct DirSlot {
+    sector: u32,
+    offset: usize,
+}
+
+/// State for appending to a file across several writes
+/// Obtained from `SDCard::open_append`; caches the chain tail so each
+/// append doesn't walk the whole cluster chain again
+pub struct AppendHandle {
+    entry: DirEntry,
+    slot: DirSlot,
+    last_cluster: u32,
+}
+
+impl AppendHandle {
+    /// Current file size in bytes
+    pub fn size(&self) -> u32 {
+        self.entry.file_size
+    }
+}
+
+/// Convert "name.ext" to the space-padded, uppercase 11-byte 8.3 form
+fn to_short_name(filename: &str) -> Option<[u8; 11]> {
+    let mut short = [b' '; 11];
+    let (base, ext) = match filename.rfind('.') {
+        Some(dot) => (&filename[..dot], &filename[dot + 1..]),
+        None => (filename, ""),
+    };
+    if base.is_empty() || base.len() > 8 || ext.len() > 3 {
+        return None;
+    }
+    short[..base.len()].copy_from_slice(base.as_bytes());
+    short[8..8 + ext.len()].copy_from_slice(ext.as_bytes());
+    // FAT stores short names uppercase; matching is case-insensitive
+    short.make_ascii_uppercase();
+    Some(short)
+}
+
+/// Checksum of a short name, stored in each of its long-name entries
+fn lfn_checksum(short_name: &[u8; 11]) -> u8 {
+    short_name
+        .iter()
+        .fold(0u8, |sum, &b| ((sum & 1) << 7).wrapping_add(sum >> 1).wrapping_add(b))
+}
+
+/// Long file name assembled from the VFAT entries preceding a short entry
+/// Entries are stored last-part-first, each with its ordinal and the short
+/// name's checksum; anything out of sequence discards the name
+struct LongName {
+    /// ASCII only; other characters become '?' (so they never match a lookup)
+    chars: [u8; LFN_MAX_ENTRIES * LFN_CHARS_PER_ENTRY],
+    len: usize,
+    checksum: u8,
+    /// Ordinal expected next; 0 once entry 1 has been read
+    next_ordinal: u8,
+    valid: bool,
+}
+
+impl LongName {
+    fn new() -> Self {
+        LongName {
+            chars: [0; LFN_MAX_ENTRIES * LFN_CHARS_PER_ENTRY],
+            len: 0,
+            checksum: 0,
+            next_ordinal: 0,
+            valid: false,
+        }
+    }
+
+    fn reset(&mut self) {
+        self.valid = false;
+        self.len = 0;
+    }
+
+    /// Add one raw long-name directory entry
+    fn push(&mut self, raw: &[u8]) {
+        let ordinal = raw[0] & LFN_ORDINAL_MASK;
+        let checksum = raw[13];
+
+        if raw[0] & LFN_LAST_ENTRY != 0 {
+            if ordinal == 0 || ordinal as usize > LFN_MAX_ENTRIES {
+                self.reset();
+                return;
+            }
+            self.valid = true;
+            self.checksum = checksum;
+            self.len = ordinal as usize * LFN_CHARS_PER_ENTRY;
+        } else if !self.valid || ordinal == 0 || ordinal != self.next_ordinal || checksum != self.checksum {
+            self.reset();
+            return;
+        }
+
+        let base = (ordinal as usize - 1) * LFN_CHARS_PER_ENTRY;
+        for (i, &offset) in LFN_CHAR_OFFSETS.iter().enumerate() {
+            let c = read_u16(raw, offset);
+            let pos = base + i;
+            match c {
+                // Terminator; the rest of the entry is 0xFFFF padding
+                0x0000 => self.len = self.len.min(pos),
+                0xFFFF => {}
+                c if c < 0x80 => self.chars[pos] = c as u8,
+                _ => self.chars[pos] = b'?',
+            }
+        }
+        self.next_ordinal = ordinal - 1;
+    }
+
+    /// Check the complete long name belongs to `short_name` and equals `name`
+    fn matches(&self, short_name: &[u8; 11], name: &str) -> bool {
+        self.valid
+            && self.next_ordinal == 0
+            && self.checksum == lfn_checksum(short_name)
+            && self.chars[..self.len].eq_ignore_ascii_case(name.as_bytes())
+    }
+}
+
 pub struct FAT32 {
     pub boot_sector: BootSector,
     pub fat_start_sector: u32,
@@ -61,15 +323,70 @@ pub struct FAT32 {
     pub bytes_per_sector: u32,
 }
 
-impl FAT32 {
-    pub fn new(boot_data: &[u8]) -> Option<Self> {
-        if boot_data.len() < mem::size_of::<BootSector>() {
+fn read_u16(data: &[u8], offset: usize) -> u16 {
+    (data[offset] as u16) | ((data[offset + 1] as u16) << 8)
+}
+
+fn read_u32(data: &[u8], offset: usize) -> u32 {
+    (read_u16(data, offset) as u32) | ((read_u16(data, offset + 2) as u32) << 16)
+}
+
+fn write_u16(data: &mut [u8], offset: usize, value: u16) {
+    data[offset..offset + 2].copy_from_slice(&value.to_le_bytes());
+}
+
+fn write_u32(data: &mut [u8], offset: usize, value: u32) {
+    data[offset..offset + 4].copy_from_slice(&value.to_le_bytes());
+}
+
+impl BootSector {
+    /// Decode the on-disk (packed, little-endian) boot sector layout
+    pub fn parse(data: &[u8]) -> Option<Self> {
+        if data.len() < 90 {
             return None;
         }
 
-        let boot_sector = unsafe {
-            *(boot_data.as_ptr() as *const BootSector)
+        let mut boot = BootSector {
+            jump: [0; 3],
+            oem_id: [0; 8],
+            bytes_per_sector: read_u16(data, 11),
+            sectors_per_cluster: data[13],
+            reserved_sectors: read_u16(data, 14),
+            num_fats: data[16],
+            root_dir_entries: read_u16(data, 17),
+            total_sectors: read_u16(data, 19),
+            media_descriptor: data[21],
+            sectors_per_fat: read_u16(data, 22),
+            sectors_per_track: read_u16(data, 24),
+            num_heads: read_u16(data, 26),
+            hidden_sectors: read_u32(data, 28),
+            total_sectors_large: read_u32(data, 32),
+            sectors_per_fat_32: read_u32(data, 36),
+            flags: read_u16(data, 40),
+            version: read_u16(data, 42),
+            root_cluster: read_u32(data, 44),
+            fsinfo_sector: read_u16(data, 48),
+            backup_boot: read_u16(data, 50),
+            reserved: [0; 12],
+            drive_number: data[64],
+            nt_reserved: data[65],
+            signature: data[66],
+            serial_number: read_u32(data, 67),
+            label: [0; 11],
+            fs_type: [0; 8],
         };
+        boot.jump.copy_from_slice(&data[0..3]);
+        boot.oem_id.copy_from_slice(&data[3..11]);
+        boot.reserved.copy_from_slice(&data[52..64]);
+        boot.label.copy_from_slice(&data[71..82]);
+        boot.fs_type.copy_from_slice(&data[82..90]);
+        Some(boot)
+    }
+}
+
+impl FAT32 {
+    pub fn new(boot_data: &[u8]) -> Option<Self> {
+        let boot_sector = BootSector::parse(boot_data)?;
 
         // Validate it's FAT32
         if boot_sector.bytes_per_sector != 512 {
@@ -93,6 +410,20 @@ impl FAT32 {
         })
     }
 
+    /// Number of data clusters on the volume (valid clusters are 2..count+2)
+    pub fn cluster_count(&self) -> u32 {
+        let total = if self.boot_sector.total_sectors != 0 {
+            self.boot_sector.total_sectors as u32
+        } else {
+            self.boot_sector.total_sectors_large
+        };
+        total.saturating_sub(self.data_start_sector) / self.sectors_per_cluster.max(1)
+    }
+
+    pub fn cluster_bytes(&self) -> u32 {
+        self.sectors_per_cluster * self.bytes_per_sector
+    }
+
     pub fn cluster_to_sector(&self, cluster: u32) -> u32 {
         if cluster < 2 {
             return self.data_start_sector;
@@ -101,30 +432,589 @@ impl FAT32 {
     }
 }
 
+fn delay(count: u32) {
+    for _ in 0..count {
+        unsafe {
+            let dummy = 0u32;
+            core::ptr::read_volatile(&dummy);
+        }
+    }
+}
+
+/// BCM2835 EMMC controller driving the SD card slot
+pub struct Emmc {
+    /// Relative card address (already shifted into the upper 16 bits)
+    rca: u32,
+    /// SDHC/SDXC cards are block addressed; SDSC cards are byte addressed
+    high_capacity: bool,
+}
+
+impl Emmc {
+    /// Reset the controller and bring the card to the transfer state
+    /// (CMD0, CMD8, ACMD41, CMD2, CMD3, CMD7)
+    pub fn init() -> Option<Self> {
+        Self::init_gpio();
+
+        // Reset host controller
+        mmio_write(EMMC_CONTROL0, 0);
+        mmio_write(EMMC_CONTROL1, mmio_read(EMMC_CONTROL1) | C1_SRST_HC);
+        if !Self::wait_until(|| mmio_read(EMMC_CONTROL1) & C1_SRST_HC == 0) {
+            return None;
+        }
+
+        mmio_write(EMMC_CONTROL1, mmio_read(EMMC_CONTROL1) | C1_CLK_INTLEN | C1_TOUNIT_MAX);
+        delay(1000);
+        if !Self::set_clock(EMMC_INIT_CLOCK_HZ) {
+            return None;
+        }
+        mmio_write(EMMC_IRPT_EN, 0xFFFFFFFF);
+        mmio_write(EMMC_IRPT_MASK, 0xFFFFFFFF);
+
+        let mut emmc = Emmc { rca: 0, high_capacity: false };
+
+        emmc.send_command(CMD_GO_IDLE, 0)?;
+
+        // CMD8 only answers on v2+ cards; v1 cards can't be high capacity
+        let v2_card = matches!(emmc.send_command(CMD_SEND_IF_COND, 0x1AA), Some(r) if r & 0xFFF == 0x1AA);
+
+        // ACMD41 until the card reports power-up complete
+        let op_arg = if v2_card { ACMD41_ARG_HC } else { ACMD41_ARG_HC & !ACMD41_CCS };
+        let mut ocr = 0;
+        for _ in 0..100 {
+            ocr = emmc.send_app_command(CMD_SEND_OP_COND, op_arg)?;
+            if ocr & ACMD41_READY != 0 {
+                break;
+            }
+            delay(10_000);
+        }
+        if ocr & ACMD41_READY == 0 {
+            return None;
+        }
+        emmc.high_capacity = ocr & ACMD41_CCS != 0;
+
+        emmc.send_command(CMD_ALL_SEND_CID, 0)?;
+        emmc.rca = emmc.send_command(CMD_SEND_REL_ADDR, 0)? & 0xFFFF0000;
+
+        if !Self::set_clock(EMMC_TRANSFER_CLOCK_HZ) {
+            return None;
+        }
+        emmc.send_command(CMD_CARD_SELECT, emmc.rca)?;
+
+        Some(emmc)
+    }
+
+    /// Route GPIO 48-53 to the EMMC controller with pull-ups
+    fn init_gpio() {
+        // GPIO 48, 49 (CLK, CMD) in GPFSEL4 bits 24-29
+        let mut sel = mmio_read(GPFSEL4);
+        sel |= (7 << 24) | (7 << 27);
+        mmio_write(GPFSEL4, sel);
+
+        // GPIO 50-53 (DAT0-3) in GPFSEL5 bits 0-11
+        let mut sel = mmio_read(GPFSEL5);
+        sel |= 7 | (7 << 3) | (7 << 6) | (7 << 9);
+        mmio_write(GPFSEL5, sel);
+
+        mmio_write(GPPUD, 2);
+        delay(150);
+        mmio_write(GPPUDCLK1, 0x3F << 16);
+        delay(150);
+        mmio_write(GPPUD, 0);
+        mmio_write(GPPUDCLK1, 0);
+    }
+
+    /// Poll `cond` until it holds or the timeout expires
+    fn wait_until<F: Fn() -> bool>(cond: F) -> bool {
+        for _ in 0..EMMC_TIMEOUT_POLLS {
+            if cond() {
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Wait for an interrupt flag, clearing it; false on error or timeout
+    fn wait_interrupt(mask: u32) -> bool {
+        let arrived = Self::wait_until(|| mmio_read(EMMC_INTERRUPT) & (mask | INT_ERROR_MASK) != 0);
+        let flags = mmio_read(EMMC_INTERRUPT);
+
+        if !arrived || flags & INT_ERROR_MASK != 0 {
+            mmio_write(EMMC_INTERRUPT, flags);
+            return false;
+        }
+        mmio_write(EMMC_INTERRUPT, mask);
+        true
+    }
+
+    /// Program the SD clock divider (base / 2N) and wait for it to settle
+    fn set_clock(freq: u32) -> bool {
+        if !Self::wait_until(|| mmio_read(EMMC_STATUS) & (SR_CMD_INHIBIT | SR_DAT_INHIBIT) == 0) {
+            return false;
+        }
+
+        mmio_write(EMMC_CONTROL1, mmio_read(EMMC_CONTROL1) & !C1_CLK_EN);
+        delay(1000);
+
+        let divisor = EMMC_BASE_CLOCK_HZ.div_ceil(2 * freq).clamp(1, 0x3FF);
+        let divisor_bits = ((divisor & 0xFF) << 8) | (((divisor >> 8) & 0x3) << 6);
+        let control1 = (mmio_read(EMMC_CONTROL1) & 0xFFFF003F) | divisor_bits;
+        mmio_write(EMMC_CONTROL1, control1);
+        delay(1000);
+
+        mmio_write(EMMC_CONTROL1, control1 | C1_CLK_EN);
+        Self::wait_until(|| mmio_read(EMMC_CONTROL1) & C1_CLK_STABLE != 0)
+    }
+
+    /// Issue a command and return RESP0
+    fn send_command(&mut self, cmd: u32, arg: u32) -> Option<u32> {
+        if !Self::wait_until(|| mmio_read(EMMC_STATUS) & SR_CMD_INHIBIT == 0) {
+            return None;
+        }
+
+        mmio_write(EMMC_INTERRUPT, mmio_read(EMMC_INTERRUPT));
+        mmio_write(EMMC_ARG1, arg);
+        mmio_write(EMMC_CMDTM, cmd);
+
+        if !Self::wait_interrupt(INT_CMD_DONE) {
+            return None;
+        }
+        Some(mmio_read(EMMC_RESP0))
+    }
+
+    /// Issue an application-specific command (CMD55 prefix)
+    fn send_app_command(&mut self, cmd: u32, arg: u32) -> Option<u32> {
+        self.send_command(CMD_APP_CMD, self.rca)?;
+        self.send_command(cmd, arg)
+    }
+
+    /// Read one 512-byte block via CMD17
+    pub fn read_block(&mut self, lba: u32, buf: &mut [u8; SECTOR_SIZE]) -> bool {
+        if !Self::wait_until(|| mmio_read(EMMC_STATUS) & SR_DAT_INHIBIT == 0) {
+            return false;
+        }
+
+        mmio_write(EMMC_BLKSIZECNT, (1 << 16) | SECTOR_SIZE as u32);
+        let address = if self.high_capacity { lba } else { lba * SECTOR_SIZE as u32 };
+        if self.send_command(CMD_READ_SINGLE, address).is_none() {
+            return false;
+        }
+        if !Self::wait_interrupt(INT_READ_RDY) {
+            return false;
+        }
+
+        for chunk in buf.chunks_exact_mut(4) {
+            chunk.copy_from_slice(&mmio_read(EMMC_DATA).to_le_bytes());
+        }
+
+        Self::wait_interrupt(INT_DATA_DONE)
+    }
+
+    /// Write one 512-byte block via CMD24
+    pub fn write_block(&mut self, lba: u32, buf: &[u8; SECTOR_SIZE]) -> bool {
+        if !Self::wait_until(|| mmio_read(EMMC_STATUS) & SR_DAT_INHIBIT == 0) {
+            return false;
+        }
+
+        mmio_write(EMMC_BLKSIZECNT, (1 << 16) | SECTOR_SIZE as u32);
+        let address = if self.high_capacity { lba } else { lba * SECTOR_SIZE as u32 };
+        if self.send_command(CMD_WRITE_SINGLE, address).is_none() {
+            return false;
+        }
+        if !Self::wait_interrupt(INT_WRITE_RDY) {
+            return false;
+        }
+
+        for chunk in buf.chunks_exact(4) {
+            mmio_write(EMMC_DATA, read_u32(chunk, 0));
+        }
+
+        Self::wait_interrupt(INT_DATA_DONE)
+    }
+}
+
 pub struct SDCard {
     pub fat: FAT32,
+    emmc: Emmc,
+    /// First sector of the FAT32 volume on the card
+    partition_start: u32,
+    /// Cluster to start the next free-cluster search from
+    free_hint: u32,
 }
 
 impl SDCard {
-    /// Initialize SD card with boot sector data
-    pub fn new(boot_data: &[u8]) -> Option<Self> {
-        let fat = FAT32::new(boot_data)?;
-        Some(SDCard { fat })
+    /// Initialize the card and mount the first FAT32 volume
+    /// Accepts either an MBR-partitioned card or a bare volume
+    pub fn init() -> Option<Self> {
+        let mut emmc = Emmc::init()?;
+
+        let mut sector = [0u8; SECTOR_SIZE];
+        if !emmc.read_block(0, &mut sector) || sector[510] != 0x55 || sector[511] != 0xAA {
+            return None;
+        }
+
+        // Sector 0 is either the volume boot record or an MBR
+        let is_boot_record = (sector[0] == 0xEB || sector[0] == 0xE9) && &sector[82..87] == b"FAT32";
+        let partition_start = if is_boot_record {
+            0
+        } else {
+            let start = read_u32(&sector, 446 + 8);
+            if !emmc.read_block(start, &mut sector) {
+                return None;
+            }
+            start
+        };
+
+        let fat = FAT32::new(&sector)?;
+        Some(SDCard { fat, emmc, partition_start, free_hint: 2 })
     }
 
-    /// Simple read simulation - in real implementation, would read from SD via GPIO
-    pub fn read_sector(&self, _sector: u32) -> Option<[u8; 512]> {
-        // This is a placeholder - actual implementation would read via EMMC interface
-        // For now, return None to indicate SD card not available
-        None
+    /// Read a sector, numbered relative to the start of the FAT32 volume
+    pub fn read_sector(&mut self, sector: u32) -> Option<[u8; SECTOR_SIZE]> {
+        let mut buf = [0u8; SECTOR_SIZE];
+        if self.emmc.read_block(self.partition_start + sector, &mut buf) {
+            Some(buf)
+        } else {
+            None
+        }
+    }
+
+    /// Write a sector, numbered relative to the start of the FAT32 volume
+    pub fn write_sector(&mut self, sector: u32, data: &[u8; SECTOR_SIZE]) -> bool {
+        self.emmc.write_block(self.partition_start + sector, data)
     }
 
-    /// Find a file in root directory
-    pub fn find_file(&self, _filename: &str) -> Option<DirEntry> {
-        // This would search the root directory for the given filename
-        // Placeholder for actual implementation
+    /// Look up the FAT entry for `cluster`; None at end of chain
+    fn next_cluster(&mut self, cluster: u32) -> Option<u32> {
+        let fat_offset = cluster * 4;
+        let sector = self.fat.fat_start_sector + fat_offset / self.fat.bytes_per_sector;
+        let offset = (fat_offset % self.fat.bytes_per_sector) as usize;
+
+        let data = self.read_sector(sector)?;
+        let next = read_u32(&data, offset) & 0x0FFFFFFF;
+        if next < 2 || next == FAT32_BAD_CLUSTER || next >= FAT32_EOC {
+            None
+        } else {
+            Some(next)
+        }
+    }
+
+    /// Set the FAT entry for `cluster` in every FAT copy
+    fn set_fat_entry(&mut self, cluster: u32, value: u32) -> bool {
+        let fat_offset = cluster * 4;
+        let offset = (fat_offset % self.fat.bytes_per_sector) as usize;
+        let sectors_per_fat = self.fat.boot_sector.sectors_per_fat_32;
+
+        for copy in 0..self.fat.boot_sector.num_fats as u32 {
+            let sector = self.fat.fat_start_sector
+                + copy * sectors_per_fat
+                + fat_offset / self.fat.bytes_per_sector;
+            let mut data = match self.read_sector(sector) {
+                Some(data) => data,
+                None => return false,
+            };
+            // Upper 4 bits are reserved and must be preserved
+            let entry = (read_u32(&data, offset) & 0xF0000000) | (value & 0x0FFFFFFF);
+            write_u32(&mut data, offset, entry);
+            if !self.write_sector(sector, &data) {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Claim a free cluster, mark it end-of-chain and link it after `prev`
+    /// The FSInfo free-cluster count is not updated; hosts treat it as a hint
+    fn allocate_cluster(&mut self, prev: Option<u32>) -> Option<u32> {
+        let count = self.fat.cluster_count();
+        let entries_per_sector = self.fat.bytes_per_sector / 4;
+        let mut cluster = self.free_hint;
+        let mut cached_sector = u32::MAX;
+        let mut data = [0u8; SECTOR_SIZE];
+
+        for _ in 0..count {
+            if cluster < 2 || cluster >= count + 2 {
+                cluster = 2;
+            }
+            let sector = self.fat.fat_start_sector + cluster / entries_per_sector;
+            if sector != cached_sector {
+                data = self.read_sector(sector)?;
+                cached_sector = sector;
+            }
+
+            let offset = ((cluster % entries_per_sector) * 4) as usize;
+            if read_u32(&data, offset) & 0x0FFFFFFF == FAT32_FREE {
+                if !self.set_fat_entry(cluster, FAT32_EOC_MARK) {
+                    return None;
+                }
+                if let Some(prev) = prev {
+                    if !self.set_fat_entry(prev, cluster) {
+                        return None;
+                    }
+                }
+                self.free_hint = cluster + 1;
+                return Some(cluster);
+            }
+            cluster += 1;
+        }
         None
     }
+
+    /// Scan the root directory for a file, also noting the first free slot
+    /// Matches the 8.3 `short_name` if given, or a VFAT long name equal to
+    /// `filename` (both case-insensitive)
+    fn scan_root(
+        &mut self,
+        filename: &str,
+        short_name: Option<&[u8; 11]>,
+    ) -> (Option<(DirEntry, DirSlot)>, Option<DirSlot>) {
+        let mut free_slot = None;
+        let mut long_name = LongName::new();
+        let mut cluster = self.fat.boot_sector.root_cluster;
+
+        loop {
+            let first_sector = self.fat.cluster_to_sector(cluster);
+            for i in 0..self.fat.sectors_per_cluster {
+                let sector = first_sector + i;
+                let data = match self.read_sector(sector) {
+                    Some(data) => data,
+                    None => return (None, free_slot),
+                };
+                for (index, raw) in data.chunks_exact(DIR_ENTRY_SIZE).enumerate() {
+                    let slot = DirSlot { sector, offset: index * DIR_ENTRY_SIZE };
+                    match raw[0] {
+                        DIR_END => return (None, free_slot.or(Some(slot))),
+                        DIR_DELETED => {
+                            free_slot = free_slot.or(Some(slot));
+                            long_name.reset();
+                            continue;
+                        }
+                        _ => {}
+                    }
+
+                    let entry = DirEntry::parse(raw);
+                    if entry.attrib == ATTR_LONG_NAME {
+                        long_name.push(raw);
+                        continue;
+                    }
+                    if entry.attrib & ATTR_VOLUME_ID != 0 {
+                        long_name.reset();
+                        continue;
+                    }
+                    let short_match = short_name.is_some_and(|short| entry.name == *short);
+                    if short_match || long_name.matches(&entry.name, filename) {
+                        return (Some((entry, slot)), free_slot);
+                    }
+                    long_name.reset();
+                }
+            }
+            cluster = match self.next_cluster(cluster) {
+                Some(next) => next,
+                None => return (None, free_slot),
+            };
+        }
+    }
+
+    /// Find a file in root directory by name, ignoring case
+    /// Accepts the 8.3 name ("config.ini", "CONFIG.INI") or a VFAT long name
+    pub fn find_file(&mut self, filename: &str) -> Option<DirEntry> {
+        let short_name = to_short_name(filename);
+        self.scan_root(filename, short_name.as_ref()).0.map(|(entry, _)| entry)
+    }
+
+    /// Rewrite a directory entry in place
+    fn write_dir_entry(&mut self, slot: DirSlot, entry: &DirEntry) -> bool {
+        let mut data = match self.read_sector(slot.sector) {
+            Some(data) => data,
+            None => return false,
+        };
+        entry.write_to(&mut data[slot.offset..slot.offset + DIR_ENTRY_SIZE]);
+        self.write_sector(slot.sector, &data)
+    }
+
+    /// Open a root-directory file for appending, creating it if missing
+    /// Returns None if the name isn't valid 8.3, it names a directory, or the
+    /// root directory has no free entry (it is never extended)
+    pub fn open_append(&mut self, filename: &str) -> Option<AppendHandle> {
+        let short_name = to_short_name(filename)?;
+        let (found, free_slot) = self.scan_root(filename, Some(&short_name));
+
+        let (entry, slot) = match found {
+            Some((entry, slot)) => {
+                if entry.is_directory() {
+                    return None;
+                }
+                (entry, slot)
+            }
+            None => {
+                let slot = free_slot?;
+                let entry = DirEntry {
+                    name: short_name,
+                    attrib: ATTR_ARCHIVE,
+                    reserved: 0,
+                    create_time_tenth: 0,
+                    create_time: 0,
+                    create_date: 0,
+                    access_date: 0,
+                    cluster_high: 0,
+                    write_time: 0,
+                    write_date: 0,
+                    cluster_low: 0,
+                    file_size: 0,
+                };
+                if !self.write_dir_entry(slot, &entry) {
+                    return None;
+                }
+                (entry, slot)
+            }
+        };
+
+        // Walk to the end of the existing chain
+        let mut last_cluster = entry.first_cluster();
+        if last_cluster >= 2 {
+            while let Some(next) = self.next_cluster(last_cluster) {
+                last_cluster = next;
+            }
+        }
+
+        Some(AppendHandle { entry, slot, last_cluster })
+    }
+
+    /// Append bytes to an open file and update its directory entry
+    /// Returns false on any I/O failure; data written before the failure is kept
+    pub fn append(&mut self, handle: &mut AppendHandle, data: &[u8]) -> bool {
+        let cluster_bytes = self.fat.cluster_bytes();
+        let mut written = 0;
+        let mut ok = true;
+
+        while written < data.len() {
+            let size = handle.entry.file_size;
+            let offset = size % cluster_bytes;
+
+            // Start a new cluster for an empty file or when the tail is full
+            if handle.last_cluster < 2 || (size > 0 && offset == 0) {
+                let prev = if handle.last_cluster >= 2 { Some(handle.last_cluster) } else { None };
+                match self.allocate_cluster(prev) {
+                    Some(cluster) => {
+                        if prev.is_none() {
+                            handle.entry.set_first_cluster(cluster);
+                        }
+                        handle.last_cluster = cluster;
+                    }
+                    None => {
+                        ok = false;
+                        break;
+                    }
+                }
+            }
+
+            let sector = self.fat.cluster_to_sector(handle.last_cluster)
+                + offset / self.fat.bytes_per_sector;
+            let start = (offset % self.fat.bytes_per_sector) as usize;
+            let len = (SECTOR_SIZE - start).min(data.len() - written);
+
+            // Only a partially filled sector needs its existing contents
+            let mut buf = if start > 0 {
+                match self.read_sector(sector) {
+                    Some(buf) => buf,
+                    None => {
+                        ok = false;
+                        break;
+                    }
+                }
+            } else {
+                [0u8; SECTOR_SIZE]
+            };
+            buf[start..start + len].copy_from_slice(&data[written..written + len]);
+            if !self.write_sector(sector, &buf) {
+                ok = false;
+                break;
+            }
+
+            written += len;
+            handle.entry.file_size += len as u32;
+        }
+
+        let entry = handle.entry;
+        self.write_dir_entry(handle.slot, &entry) && ok
+    }
+
+    /// Replace a root-directory file's contents, creating it if missing
+    /// Reuses the existing cluster chain, extending it if `data` is larger;
+    /// surplus clusters stay allocated to the file. Returns false on any I/O
+    /// failure (e.g. a write-protected card)
+    pub fn write_file(&mut self, filename: &str, data: &[u8]) -> bool {
+        let mut handle = match self.open_append(filename) {
+            Some(handle) => handle,
+            None => return false,
+        };
+        let mut cluster = handle.entry.first_cluster();
+        if cluster < 2 {
+            // Nothing allocated yet: writing is the same as appending
+            return self.append(&mut handle, data);
+        }
+
+        let mut written = 0;
+        loop {
+            let first_sector = self.fat.cluster_to_sector(cluster);
+            for i in 0..self.fat.sectors_per_cluster {
+                if written >= data.len() {
+                    break;
+                }
+                let len = (data.len() - written).min(SECTOR_SIZE);
+                let mut buf = [0u8; SECTOR_SIZE];
+                buf[..len].copy_from_slice(&data[written..written + len]);
+                if !self.write_sector(first_sector + i, &buf) {
+                    return false;
+                }
+                written += len;
+            }
+            if written >= data.len() {
+                break;
+            }
+            cluster = match self.next_cluster(cluster) {
+                Some(next) => next,
+                None => match self.allocate_cluster(Some(cluster)) {
+                    Some(next) => next,
+                    None => return false,
+                },
+            };
+        }
+
+        handle.entry.file_size = data.len() as u32;
+        let entry = handle.entry;
+        self.write_dir_entry(handle.slot, &entry)
+    }
+
+    /// Read a file's contents by following its cluster chain
+    /// Copies at most `buf.len()` bytes; returns the number of bytes read
+    pub fn read_file(&mut self, entry: &DirEntry, buf: &mut [u8]) -> usize {
+        let total = (entry.file_size as usize).min(buf.len());
+        let mut copied = 0;
+        let mut cluster = entry.first_cluster();
+
+        while copied < total && cluster >= 2 {
+            let first_sector = self.fat.cluster_to_sector(cluster);
+            for i in 0..self.fat.sectors_per_cluster {
+                if copied >= total {
+                    break;
+                }
+                let data = match self.read_sector(first_sector + i) {
+                    Some(data) => data,
+                    None => return copied,
+                };
+                let len = (total - copied).min(SECTOR_SIZE);
+                buf[copied..copied + len].copy_from_slice(&data[..len]);
+                copied += len;
+            }
+
+            cluster = match self.next_cluster(cluster) {
+                Some(next) => next,
+                None => break,
+            };
+        }
+
+        copied
+    }
 }
 
 /// Configuration loaded from SD card
@@ -1,4 +1,6 @@
-﻿use core::ptr;
+﻿use core::{ptr, slice};
+use core::f32::consts::PI;
+use crate::math::{cos, floor, sin};
 
 pub const COLOR_WHITE: u32 = 0xFFFFFF;
 pub const COLOR_RED: u32 = 0xFF0000;
@@ -10,11 +12,59 @@ pub const COLOR_CYAN: u32 = 0x00FFFF;
 pub const COLOR_MAGENTA: u32 = 0xFF00FF;
 pub const COLOR_GRAY: u32 = 0x808080;
 
+/// Full brightness for daytime use
+pub const BRIGHTNESS_DAY: u8 = 255;
+/// Dimmed level for night driving
+pub const BRIGHTNESS_NIGHT: u8 = 96;
+
+/// How the panel is mounted; drawing stays in logical (upright) coordinates
+/// and the framebuffer maps them to physical pixels
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Rotation {
+    None,
+    /// Content turned a quarter clockwise (panel mounted portrait)
+    Cw90,
+    /// Upside down
+    Cw180,
+    Cw270,
+}
+
+impl Rotation {
+    /// Parse a config name ("0"/"none", "90", "180", "270", or "cw90" etc.)
+    pub fn from_str(s: &str) -> Option<Self> {
+        let s = s.trim();
+        let degrees = match s.get(..2) {
+            Some(prefix) if prefix.eq_ignore_ascii_case("cw") => &s[2..],
+            _ => s,
+        };
+        match degrees {
+            "0" => Some(Rotation::None),
+            "90" => Some(Rotation::Cw90),
+            "180" => Some(Rotation::Cw180),
+            "270" => Some(Rotation::Cw270),
+            _ if s.eq_ignore_ascii_case("none") => Some(Rotation::None),
+            _ => None,
+        }
+    }
+
+    /// Whether logical width and height are the physical height and width
+    pub fn swaps_axes(&self) -> bool {
+        matches!(self, Rotation::Cw90 | Rotation::Cw270)
+    }
+}
+
 pub struct Framebuffer {
     buffer: *mut u32,
+    /// Logical size, as seen by drawing code (swapped for 90/270 rotation)
     width: u32,
     height: u32,
+    /// Size of the pixel memory, unaffected by rotation
+    physical_width: u32,
+    physical_height: u32,
     pitch: u32,
+    rotation: Rotation,
+    /// Scale applied to every pixel write (255 = unchanged)
+    brightness: u8,
 }
 
 impl Framebuffer {
@@ -24,25 +74,144 @@ impl Framebuffer {
             buffer: address as *mut u32,
             width,
             height,
+            physical_width: width,
+            physical_height: height,
             pitch: width * 4,  // 32-bit pixels, 4 bytes per pixel
+            rotation: Rotation::None,
+            brightness: BRIGHTNESS_DAY,
         }
     }
 
-    pub fn clear(&mut self, color: u32) {
-        for y in 0..self.height {
-            for x in 0..self.width {
-                self.draw_pixel(x, y, color);
-            }
+    /// Create framebuffer with an explicit row pitch in bytes
+    /// For GPU allocations, whose rows may be padded beyond width * 4
+    pub fn with_pitch(address: u32, width: u32, height: u32, pitch: u32) -> Self {
+        Framebuffer {
+            buffer: address as *mut u32,
+            width,
+            height,
+            physical_width: width,
+            physical_height: height,
+            pitch: pitch.max(width * 4),
+            rotation: Rotation::None,
+            brightness: BRIGHTNESS_DAY,
         }
     }
 
-    pub fn draw_pixel(&mut self, x: u32, y: u32, color: u32) {
+    /// Create framebuffer over a caller-provided pixel buffer
+    /// Lets rendering run against plain memory (e.g. host-side tests) instead
+    /// of the GPU-allocated framebuffer. `pitch` is in bytes; returns None if
+    /// the buffer is too small for `height` rows of `pitch` bytes
+    pub fn from_buffer(buffer: &'static mut [u32], width: u32, height: u32, pitch: u32) -> Option<Self> {
+        if !pitch.is_multiple_of(4) || pitch / 4 < width {
+            return None;
+        }
+        if (buffer.len() as u64) < height as u64 * (pitch / 4) as u64 {
+            return None;
+        }
+        Some(Framebuffer {
+            buffer: buffer.as_mut_ptr(),
+            width,
+            height,
+            physical_width: width,
+            physical_height: height,
+            pitch,
+            rotation: Rotation::None,
+            brightness: BRIGHTNESS_DAY,
+        })
+    }
+
+    /// Set how the panel is mounted; width() and height() become the logical
+    /// size for the rotation. Redraw everything afterwards
+    pub fn set_rotation(&mut self, rotation: Rotation) {
+        self.rotation = rotation;
+        if rotation.swaps_axes() {
+            self.width = self.physical_height;
+            self.height = self.physical_width;
+        } else {
+            self.width = self.physical_width;
+            self.height = self.physical_height;
+        }
+    }
+
+    pub fn rotation(&self) -> Rotation {
+        self.rotation
+    }
+
+    /// Word offset of a logical pixel in the buffer; None outside the screen
+    fn pixel_offset(&self, x: u32, y: u32) -> Option<usize> {
         if x >= self.width || y >= self.height {
-            return;
+            return None;
         }
+        let (px, py) = match self.rotation {
+            Rotation::None => (x, y),
+            Rotation::Cw90 => (self.physical_width - 1 - y, x),
+            Rotation::Cw180 => (self.physical_width - 1 - x, self.physical_height - 1 - y),
+            Rotation::Cw270 => (y, self.physical_height - 1 - x),
+        };
+        Some(py as usize * (self.pitch / 4) as usize + px as usize)
+    }
+
+    /// Read back a pixel; None outside the screen
+    pub fn get_pixel(&self, x: u32, y: u32) -> Option<u32> {
+        let offset = self.pixel_offset(x, y)?;
+        unsafe { Some(*self.buffer.add(offset)) }
+    }
+
+    /// Dim everything drawn from now on (0 = black, 255 = full brightness)
+    /// Already drawn pixels are unchanged until redrawn
+    pub fn set_brightness(&mut self, brightness: u8) {
+        self.brightness = brightness;
+    }
+
+    pub fn brightness(&self) -> u8 {
+        self.brightness
+    }
+
+    /// Scale each channel by brightness/256 (multiply-shift)
+    fn apply_brightness(&self, color: u32) -> u32 {
+        if self.brightness == 255 {
+            return color;
+        }
+        let scale = self.brightness as u32 + 1;
+        let r = (((color >> 16) & 0xFF) * scale) >> 8;
+        let g = (((color >> 8) & 0xFF) * scale) >> 8;
+        let b = ((color & 0xFF) * scale) >> 8;
+        (r << 16) | (g << 8) | b
+    }
+
+    pub fn clear(&mut self, color: u32) {
+        self.draw_filled_rect(0, 0, self.width, self.height, color);
+    }
+
+    pub fn draw_pixel(&mut self, x: u32, y: u32, color: u32) {
+        let offset = match self.pixel_offset(x, y) {
+            Some(offset) => offset,
+            None => return,
+        };
+        let color = self.apply_brightness(color);
+        unsafe {
+            *self.buffer.add(offset) = color;
+        }
+    }
+
+    /// Blend `color` over the existing pixel; alpha 255 = opaque
+    /// Brightness dims the new color only, the stored pixel already has it
+    pub fn draw_pixel_blend(&mut self, x: u32, y: u32, color: u32, alpha: u8) {
+        let offset = match self.pixel_offset(x, y) {
+            Some(offset) => offset,
+            None => return,
+        };
+        let existing = unsafe { *self.buffer.add(offset) };
+        let color = self.apply_brightness(color);
+        let a = alpha as u32 + 1;
+        let blend = |shift: u32| {
+            let src = (color >> shift) & 0xFF;
+            let dst = (existing >> shift) & 0xFF;
+            ((src * a + dst * (256 - a)) >> 8) << shift
+        };
+        let blended = blend(16) | blend(8) | blend(0);
         unsafe {
-            let offset = y * (self.pitch / 4) + x;
-            *self.buffer.add(offset as usize) = color;
+            *self.buffer.add(offset) = blended;
         }
     }
 
@@ -63,14 +232,209 @@ impl Framebuffer {
         }
     }
 
+    /// Clipped once, then filled a row at a time; this is the hot path for
+    /// clears and bar fills
     pub fn draw_filled_rect(&mut self, x: u32, y: u32, w: u32, h: u32, color: u32) {
+        if x >= self.width || y >= self.height {
+            return;
+        }
+        let w = w.min(self.width - x);
+        let h = h.min(self.height - y);
+        if w == 0 || h == 0 {
+            return;
+        }
+
+        // Logical rows aren't contiguous in memory when rotated
+        if self.rotation != Rotation::None {
+            for j in 0..h {
+                for i in 0..w {
+                    self.draw_pixel(x + i, y + j, color);
+                }
+            }
+            return;
+        }
+
+        let color = self.apply_brightness(color);
+        let stride = (self.pitch / 4) as usize;
+        for row in y..y + h {
+            unsafe {
+                let start = self.buffer.add(row as usize * stride + x as usize);
+                slice::from_raw_parts_mut(start, w as usize).fill(color);
+            }
+        }
+    }
+
+    /// Rectangle outline with quarter-circle corners
+    /// `radius` is limited to half the shorter side; 0 draws a plain rectangle
+    pub fn draw_round_rect(&mut self, x: u32, y: u32, w: u32, h: u32, radius: u32, color: u32) {
+        let r = radius.min(w / 2).min(h / 2);
+        if r == 0 {
+            self.draw_rect(x, y, w, h, color);
+            return;
+        }
+        if w == 0 || h == 0 {
+            return;
+        }
+
+        // Straight edges between the corners
+        for i in x + r..x + w - r {
+            self.draw_pixel(i, y, color);
+            self.draw_pixel(i, y + h - 1, color);
+        }
+        for j in y + r..y + h - r {
+            self.draw_pixel(x, j, color);
+            self.draw_pixel(x + w - 1, j, color);
+        }
+
+        // Corners: midpoint circle, one octant pair per quadrant
+        let (left, right) = (x + r, x + w - 1 - r);
+        let (top, bottom) = (y + r, y + h - 1 - r);
+        let (mut dx, mut dy) = (r, 0u32);
+        let mut decision = 1 - r as i32;
+        while dx >= dy {
+            for &(ox, oy) in &[(dx, dy), (dy, dx)] {
+                self.draw_pixel(right + ox, bottom + oy, color);
+                self.draw_pixel(left - ox, bottom + oy, color);
+                self.draw_pixel(right + ox, top - oy, color);
+                self.draw_pixel(left - ox, top - oy, color);
+            }
+            dy += 1;
+            if decision < 0 {
+                decision += 2 * dy as i32 + 1;
+            } else {
+                dx -= 1;
+                decision += 2 * (dy as i32 - dx as i32) + 1;
+            }
+        }
+    }
+
+    /// Filled rectangle with quarter-circle corners; same radius rules as `draw_round_rect`
+    pub fn draw_filled_round_rect(&mut self, x: u32, y: u32, w: u32, h: u32, radius: u32, color: u32) {
+        let r = radius.min(w / 2).min(h / 2);
+        if r == 0 {
+            self.draw_filled_rect(x, y, w, h, color);
+            return;
+        }
+
+        // Full-width middle band, then corner rows as horizontal spans
+        self.draw_filled_rect(x, y + r, w, h - 2 * r, color);
+        let (left, span) = (x + r, w - 2 * r);
+        let (top, bottom) = (y + r, y + h - 1 - r);
+        let (mut dx, mut dy) = (r, 0u32);
+        let mut decision = 1 - r as i32;
+        while dx >= dy {
+            for &(half, row) in &[(dx, dy), (dy, dx)] {
+                // row 0 is already covered by the middle band
+                if row > 0 {
+                    self.draw_filled_rect(left - half, top - row, span + 2 * half, 1, color);
+                    self.draw_filled_rect(left - half, bottom + row, span + 2 * half, 1, color);
+                }
+            }
+            dy += 1;
+            if decision < 0 {
+                decision += 2 * dy as i32 + 1;
+            } else {
+                dx -= 1;
+                decision += 2 * (dy as i32 - dx as i32) + 1;
+            }
+        }
+    }
+
+    /// Arc of the circle centred on (cx, cy), clockwise on screen from
+    /// `start_angle` to `end_angle` (radians, 0 = 3 o'clock); an end before
+    /// the start wraps through 0. Points are stepped about 1/radius apart so
+    /// the line has no gaps at any size; `thickness` grows it inward
+    pub fn draw_arc(
+        &mut self,
+        cx: i32,
+        cy: i32,
+        radius: u32,
+        start_angle: f32,
+        end_angle: f32,
+        thickness: u32,
+        color: u32,
+    ) {
+        if radius == 0 {
+            return;
+        }
+        let mut sweep = end_angle - start_angle;
+        if sweep < 0.0 {
+            sweep += 2.0 * PI;
+        }
+        let sweep = sweep.max(0.0).min(2.0 * PI);
+
+        let steps = (sweep * radius as f32) as u32 + 1;
+        let inner = radius.saturating_sub(thickness.max(1) - 1);
+        for i in 0..=steps {
+            let angle = start_angle + sweep * i as f32 / steps as f32;
+            let (dx, dy) = (cos(angle), sin(angle));
+            for r in inner..=radius {
+                let px = cx + floor(dx * r as f32 + 0.5) as i32;
+                let py = cy + floor(dy * r as f32 + 0.5) as i32;
+                if px >= 0 && py >= 0 {
+                    self.draw_pixel(px as u32, py as u32, color);
+                }
+            }
+        }
+    }
+
+    /// Copy a w x h block from (src_x, src_y) to (dst_x, dst_y) within the buffer
+    /// Source and destination may overlap; the block is clipped so both stay on screen
+    pub fn copy_rect(&mut self, src_x: u32, src_y: u32, w: u32, h: u32, dst_x: u32, dst_y: u32) {
+        if src_x >= self.width || dst_x >= self.width || src_y >= self.height || dst_y >= self.height {
+            return;
+        }
+        let w = w.min(self.width - src_x).min(self.width - dst_x);
+        let h = h.min(self.height - src_y).min(self.height - dst_y);
+        if w == 0 || h == 0 {
+            return;
+        }
+
+        // Logical rows aren't contiguous in memory when rotated
+        if self.rotation != Rotation::None {
+            self.copy_rect_rotated(src_x, src_y, w, h, dst_x, dst_y);
+            return;
+        }
+
+        let stride = (self.pitch / 4) as usize;
+        let copy_row = |row: u32| unsafe {
+            let src = self.buffer.add((src_y + row) as usize * stride + src_x as usize);
+            let dst = self.buffer.add((dst_y + row) as usize * stride + dst_x as usize);
+            // ptr::copy is memmove, so overlap within a row is fine
+            ptr::copy(src, dst, w as usize);
+        };
+
+        // Moving down: copy bottom rows first so source rows aren't overwritten before use
+        if dst_y > src_y {
+            for row in (0..h).rev() {
+                copy_row(row);
+            }
+        } else {
+            for row in 0..h {
+                copy_row(row);
+            }
+        }
+    }
+
+    /// Pixel-by-pixel `copy_rect` for rotated output; the block is already clipped
+    fn copy_rect_rotated(&mut self, src_x: u32, src_y: u32, w: u32, h: u32, dst_x: u32, dst_y: u32) {
+        // Walk away from the destination on both axes so overlapping source
+        // pixels are read before they are overwritten
         for j in 0..h {
+            let row = if dst_y > src_y { h - 1 - j } else { j };
             for i in 0..w {
-                self.draw_pixel(x + i, y + j, color);
+                let col = if dst_x > src_x { w - 1 - i } else { i };
+                let src = self.pixel_offset(src_x + col, src_y + row);
+                let dst = self.pixel_offset(dst_x + col, dst_y + row);
+                if let (Some(src), Some(dst)) = (src, dst) {
+                    unsafe {
+                        *self.buffer.add(dst) = *self.buffer.add(src);
+                    }
+                }
             }
         }
     }
-    
+
     pub fn width(&self) -> u32 {
         self.width
     }
@@ -83,3 +447,84 @@ impl Framebuffer {
         self.buffer as *const u8
     }
 }
+
+/// Framebuffer over leaked host memory, for tests that inspect pixels
+#[cfg(test)]
+pub fn host_framebuffer(width: u32, height: u32) -> Framebuffer {
+    let buffer = vec![0u32; (width * height) as usize].leak();
+    Framebuffer::from_buffer(buffer, width, height, width * 4).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_buffer_rejects_short_buffer_and_bad_pitch() {
+        assert!(Framebuffer::from_buffer(vec![0u32; 15].leak(), 4, 4, 16).is_none());
+        assert!(Framebuffer::from_buffer(vec![0u32; 16].leak(), 4, 4, 8).is_none());
+        assert!(Framebuffer::from_buffer(vec![0u32; 16].leak(), 4, 4, 18).is_none());
+        assert!(Framebuffer::from_buffer(vec![0u32; 24].leak(), 4, 4, 24).is_some());
+    }
+
+    #[test]
+    fn pixels_read_back_and_clip() {
+        let mut fb = host_framebuffer(8, 4);
+        fb.draw_pixel(3, 2, COLOR_RED);
+        fb.draw_pixel(8, 0, COLOR_RED);
+        fb.draw_pixel(0, 4, COLOR_RED);
+        assert_eq!(fb.get_pixel(3, 2), Some(COLOR_RED));
+        assert_eq!(fb.get_pixel(2, 3), Some(COLOR_BLACK));
+        assert_eq!(fb.get_pixel(8, 0), None);
+    }
+
+    #[test]
+    fn filled_rect_clips_at_edges() {
+        let mut fb = host_framebuffer(8, 4);
+        fb.draw_filled_rect(6, 2, 10, 10, COLOR_GREEN);
+        for y in 0..4 {
+            for x in 0..8 {
+                let inside = x >= 6 && y >= 2;
+                let expected = if inside { COLOR_GREEN } else { COLOR_BLACK };
+                assert_eq!(fb.get_pixel(x, y), Some(expected), "({}, {})", x, y);
+            }
+        }
+    }
+
+    #[test]
+    fn brightness_scales_new_pixels() {
+        let mut fb = host_framebuffer(2, 1);
+        fb.set_brightness(127);
+        fb.draw_pixel(0, 0, COLOR_WHITE);
+        fb.draw_filled_rect(1, 0, 1, 1, COLOR_WHITE);
+        assert_eq!(fb.get_pixel(0, 0), Some(0x7F7F7F));
+        assert_eq!(fb.get_pixel(1, 0), Some(0x7F7F7F));
+    }
+
+    #[test]
+    fn rotation_maps_logical_to_physical() {
+        let mut fb = host_framebuffer(4, 2);
+        let memory = fb.buffer_ptr() as *const u32;
+        fb.set_rotation(Rotation::Cw90);
+        assert_eq!((fb.width(), fb.height()), (2, 4));
+        // Logical top-left lands at the physical top-right
+        fb.draw_pixel(0, 0, COLOR_BLUE);
+        assert_eq!(unsafe { *memory.add(3) }, COLOR_BLUE);
+        assert_eq!(fb.get_pixel(0, 0), Some(COLOR_BLUE));
+
+        fb.set_rotation(Rotation::Cw180);
+        fb.draw_pixel(0, 0, COLOR_RED);
+        assert_eq!(unsafe { *memory.add(7) }, COLOR_RED);
+    }
+
+    #[test]
+    fn copy_rect_handles_overlap() {
+        let mut fb = host_framebuffer(4, 1);
+        for x in 0..4 {
+            fb.draw_pixel(x, 0, x + 1);
+        }
+        fb.copy_rect(0, 0, 3, 1, 1, 0);
+        let row: Vec<u32> = (0..4).map(|x| fb.get_pixel(x, 0).unwrap()).collect();
+        assert_eq!(row, [1, 1, 2, 3]);
+    }
+}
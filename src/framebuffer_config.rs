@@ -8,48 +8,60 @@ pub enum FramebufferMode {
     RealHardware,
 }
 
+/// Resolution used when neither the config nor the GPU provides one
+pub const DEFAULT_WIDTH: u32 = 1280;
+pub const DEFAULT_HEIGHT: u32 = 720;
+
+/// Largest resolution accepted from the GPU or config (guards against garbage
+/// mailbox responses and typos)
+const MAX_WIDTH: u32 = 4096;
+const MAX_HEIGHT: u32 = 4096;
+
+/// Fixed DRAM buffer the QEMU SDL display scans out
+const QEMU_FB_ADDRESS: u32 = 0x04000000;
+
 pub struct FramebufferConfig {
     pub mode: FramebufferMode,
     pub address: u32,
     pub width: u32,
     pub height: u32,
+    /// Bytes per row; the GPU may pad rows beyond width * 4
+    pub pitch: u32,
 }
 
 impl FramebufferConfig {
-    /// Detect runtime environment and return appropriate framebuffer configuration
-    pub fn detect() -> Self {
-        #[cfg(feature = "qemu")]
-        {
-            // QEMU mode: use fixed DRAM buffer that SDL will display
-            return FramebufferConfig {
-                mode: FramebufferMode::QEMU,
-                address: 0x04000000,
-                width: 1280,
-                height: 720,
-            };
-        }
+    /// Detect runtime environment and return appropriate framebuffer configuration,
+    /// with an optional resolution override (e.g. from config.ini). Without one, hardware uses the display's preferred mode as reported by
+    /// the GPU, falling back to DEFAULT_WIDTH x DEFAULT_HEIGHT
+    pub fn detect_with_resolution(requested: Option<(u32, u32)>) -> Self {
+        let requested = requested.filter(|&(width, height)| is_valid_resolution(width, height));
 
         #[cfg(feature = "hardware")]
         {
             // Real hardware mode: query GPU via mailbox for framebuffer allocation
-            let address = query_gpu_framebuffer();
-            return FramebufferConfig {
-                mode: FramebufferMode::RealHardware,
-                address,
-                width: 1280,
-                height: 720,
-            };
+            let (width, height) = requested
+                .or_else(query_display_size)
+                .unwrap_or((DEFAULT_WIDTH, DEFAULT_HEIGHT));
+            if let Some(allocation) = allocate_gpu_framebuffer(width, height) {
+                return FramebufferConfig {
+                    mode: FramebufferMode::RealHardware,
+                    address: allocation.address,
+                    width: allocation.width,
+                    height: allocation.height,
+                    pitch: allocation.pitch,
+                };
+            }
         }
 
-        // Fallback to QEMU mode if no feature specified
-        #[cfg(not(any(feature = "qemu", feature = "hardware")))]
-        {
-            FramebufferConfig {
-                mode: FramebufferMode::QEMU,
-                address: 0x04000000,
-                width: 1280,
-                height: 720,
-            }
+        // QEMU mode: use fixed DRAM buffer that SDL will display
+        // Also the fallback if no feature is specified or the GPU refused
+        let (width, height) = requested.unwrap_or((DEFAULT_WIDTH, DEFAULT_HEIGHT));
+        FramebufferConfig {
+            mode: FramebufferMode::QEMU,
+            address: QEMU_FB_ADDRESS,
+            width,
+            height,
+            pitch: width * 4,
         }
     }
 
@@ -61,96 +73,92 @@ impl FramebufferConfig {
     }
 }
 
+/// Whether a resolution is non-zero and within the supported maximum
+pub fn is_valid_resolution(width: u32, height: u32) -> bool {
+    width > 0 && height > 0 && width <= MAX_WIDTH && height <= MAX_HEIGHT
+}
+
+/// Parse a "WIDTHxHEIGHT" resolution string (e.g. "1920x1080")
+pub fn parse_resolution(s: &str) -> Option<(u32, u32)> {
+    let s = s.trim();
+    let split = s.find(['x', 'X'])?;
+    let width = s[..split].trim().parse::<u32>().ok()?;
+    let height = s[split + 1..].trim().parse::<u32>().ok()?;
+    if is_valid_resolution(width, height) {
+        Some((width, height))
+    } else {
+        None
+    }
+}
+
 /// BCM2835 Mailbox interface for querying GPU framebuffer
 #[cfg(feature = "hardware")]
 mod mailbox {
-    use core::ptr;
-
-    const MAILBOX_BASE: u32 = 0x3F00B880;
-    const MAILBOX_READ: u32 = MAILBOX_BASE + 0x00;
-    const MAILBOX_STATUS: u32 = MAILBOX_BASE + 0x18;
-    const MAILBOX_WRITE: u32 = MAILBOX_BASE + 0x20;
-    const MAILBOX_FULL: u32 = 0x80000000;
-    const MAILBOX_EMPTY: u32 = 0x40000000;
-
-    #[repr(C, align(16))]
-    pub struct PropertyBuffer {
-        size: u32,           // Buffer size in bytes
-        code: u32,           // Request code (0 for request)
-        // Property tags follow
-        tag_allocate: u32,   // Tag ID: 0x00040001 (allocate framebuffer)
-        tag_size: u32,       // Tag data size (6 words = 24 bytes)
-        tag_status: u32,     // Tag status/response code
-        width: u32,          // Framebuffer width
-        height: u32,         // Framebuffer height
-        depth: u32,          // Color depth (32-bit)
-        pointer: u32,        // Response: GPU address
-        size_response: u32,  // Response: Framebuffer size
-        pitch: u32,          // Response: Bytes per line
-        tag_end: u32,        // Tag end marker (0)
+    use crate::mmio::PropertyBuffer;
+
+    const TAG_ALLOCATE_BUFFER: u32 = 0x00040001;
+    const TAG_GET_PHYSICAL_SIZE: u32 = 0x00040003;
+    const TAG_GET_PITCH: u32 = 0x00040008;
+    const TAG_SET_PHYSICAL_SIZE: u32 = 0x00048003;
+    const TAG_SET_VIRTUAL_SIZE: u32 = 0x00048004;
+    const TAG_SET_DEPTH: u32 = 0x00048005;
+
+    /// GPU bus addresses alias ARM memory through the top two bits
+    const BUS_ADDRESS_MASK: u32 = 0x3FFFFFFF;
+
+    pub struct Allocation {
+        pub address: u32,
+        pub width: u32,
+        pub height: u32,
+        pub pitch: u32,
     }
 
-    impl PropertyBuffer {
-        pub fn new() -> Self {
-            PropertyBuffer {
-                size: 32,                    // Buffer size
-                code: 0,                     // Request code
-                tag_allocate: 0x00040001,    // Allocate framebuffer tag
-                tag_size: 24,                // Data size (6 u32s)
-                tag_status: 0,               // Status field
-                width: 1280,
-                height: 720,
-                depth: 32,
-                pointer: 0,
-                size_response: 0,
-                pitch: 0,
-                tag_end: 0,                  // End tag
-            }
+    /// The attached display's current (preferred) mode; None if the GPU
+    /// doesn't know, e.g. no display connected
+    pub fn query_display_size() -> Option<(u32, u32)> {
+        let mut buffer = PropertyBuffer::new();
+        let size = buffer.push_tag(TAG_GET_PHYSICAL_SIZE, &[0, 0])?;
+        if !buffer.call() {
+            return None;
         }
-    }
-
-    pub fn mailbox_write(channel: u32, data: u32) {
-        let value = (data & !0xF) | (channel & 0xF);
-        unsafe {
-            while (ptr::read_volatile(MAILBOX_STATUS as *const u32) & MAILBOX_FULL) != 0 {}
-            ptr::write_volatile(MAILBOX_WRITE as *mut u32, value);
+        let (width, height) = (buffer.value(size), buffer.value(size + 1));
+        if width == 0 || height == 0 {
+            None
+        } else {
+            Some((width, height))
         }
     }
 
-    pub fn mailbox_read(channel: u32) -> u32 {
-        unsafe {
-            loop {
-                while (ptr::read_volatile(MAILBOX_STATUS as *const u32) & MAILBOX_EMPTY) != 0 {}
-                let data = ptr::read_volatile(MAILBOX_READ as *const u32);
-                if (data & 0xF) == channel {
-                    return data & !0xF;
-                }
-            }
+    /// Ask the GPU for a 32-bit framebuffer of the given size
+    /// The GPU may adjust the size, so the returned allocation is authoritative
+    pub fn allocate_framebuffer(width: u32, height: u32) -> Option<Allocation> {
+        let mut buffer = PropertyBuffer::new();
+        let physical = buffer.push_tag(TAG_SET_PHYSICAL_SIZE, &[width, height])?;
+        buffer.push_tag(TAG_SET_VIRTUAL_SIZE, &[width, height])?;
+        buffer.push_tag(TAG_SET_DEPTH, &[32])?;
+        let allocation = buffer.push_tag(TAG_ALLOCATE_BUFFER, &[16, 0])?;
+        let pitch = buffer.push_tag(TAG_GET_PITCH, &[0])?;
+        if !buffer.call() {
+            return None;
         }
-    }
 
-    pub fn query_framebuffer() -> u32 {
-        let mut buffer = PropertyBuffer::new();
-        
-        let buffer_addr = (&mut buffer as *mut PropertyBuffer) as u32;
-        
-        // Request framebuffer from GPU
-        mailbox_write(8, buffer_addr);  // Channel 8 = property tags
-        let _response = mailbox_read(8);
-        
-        // GPU returns framebuffer address (with status bits in lower bits)
-        // Mask off the status bits to get the actual address
-        buffer.pointer & 0x3FFFFFFF
+        let address = buffer.value(allocation) & BUS_ADDRESS_MASK;
+        let width = buffer.value(physical);
+        let height = buffer.value(physical + 1);
+        let pitch = buffer.value(pitch);
+        if address == 0 || width == 0 || height == 0 || pitch < width * 4 {
+            return None;
+        }
+        Some(Allocation { address, width, height, pitch })
     }
 }
 
 #[cfg(feature = "hardware")]
-fn query_gpu_framebuffer() -> u32 {
-    mailbox::query_framebuffer()
+fn query_display_size() -> Option<(u32, u32)> {
+    mailbox::query_display_size().filter(|&(width, height)| is_valid_resolution(width, height))
 }
 
-#[cfg(not(feature = "hardware"))]
-fn query_gpu_framebuffer() -> u32 {
-    // Fallback - should not reach here if features configured correctly
-    0x04000000
+#[cfg(feature = "hardware")]
+fn allocate_gpu_framebuffer(width: u32, height: u32) -> Option<mailbox::Allocation> {
+    mailbox::allocate_framebuffer(width, height)
 }
@@ -0,0 +1,66 @@
+/// Fuel consumption estimate integrated from injector duty over elapsed time
+/// Assumes the injectors flow their rated cc/min whenever open, so the
+/// result is a consistent relative readout rather than a calibrated gauge
+
+use crate::math::clamp;
+
+const MS_PER_MINUTE: f64 = 60_000.0;
+const CC_PER_LITER: f64 = 1000.0;
+
+pub struct FuelModel {
+    /// Rated flow of one injector
+    pub injector_cc_per_min: f32,
+    /// Number of injectors firing (one per cylinder)
+    pub cylinders: u8,
+    /// Tank capacity for the remaining-fuel countdown
+    pub tank_liters: Option<f32>,
+    /// Latest total flow, for an instantaneous consumption readout
+    flow_cc_per_min: f32,
+    /// f64 so small per-frame increments aren't lost over a long drive
+    used_liters: f64,
+}
+
+impl FuelModel {
+    pub fn new(injector_cc_per_min: f32, cylinders: u8) -> Self {
+        FuelModel {
+            injector_cc_per_min,
+            cylinders,
+            tank_liters: None,
+            flow_cc_per_min: 0.0,
+            used_liters: 0.0,
+        }
+    }
+
+    /// Add the fuel injected over `dt_ms` at the given RPM and injector duty (%)
+    pub fn update(&mut self, rpm: f32, duty: f32, dt_ms: u32) {
+        // Duty can read stale non-zero values with the engine stopped
+        if rpm.is_nan() || rpm <= 0.0 {
+            self.flow_cc_per_min = 0.0;
+            return;
+        }
+
+        let duty_fraction = clamp(duty, 0.0, 100.0) / 100.0;
+        self.flow_cc_per_min = self.injector_cc_per_min * self.cylinders as f32 * duty_fraction;
+        self.used_liters += self.flow_cc_per_min as f64 * dt_ms as f64 / MS_PER_MINUTE / CC_PER_LITER;
+    }
+
+    /// Fuel used since the last reset, in liters
+    pub fn fuel_used(&self) -> f32 {
+        self.used_liters as f32
+    }
+
+    /// Instantaneous consumption in liters per hour
+    pub fn flow_lph(&self) -> f32 {
+        self.flow_cc_per_min * 60.0 / CC_PER_LITER as f32
+    }
+
+    /// Estimated fuel left in the tank; None when no tank size is configured
+    pub fn remaining(&self) -> Option<f32> {
+        self.tank_liters.map(|tank| (tank - self.fuel_used()).max(0.0))
+    }
+
+    /// Start counting from a full tank again
+    pub fn reset(&mut self) {
+        self.used_liters = 0.0;
+    }
+}
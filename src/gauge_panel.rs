@@ -0,0 +1,142 @@
+/// A group of gauges plus a label, positioned as one block
+/// Gauges are placed relative to the panel origin, so a cluster (e.g. coolant,
+/// oil and intake temps) can be moved or reused by changing only the origin
+
+use crate::framebuffer::Framebuffer;
+use crate::colors::{Color, Theme};
+use crate::digit_renderer::draw_str;
+use crate::ts_gauge::TSGauge;
+
+const MAX_PANEL_GAUGES: usize = 8;
+const MAX_LABEL_LEN: usize = 32;
+
+/// Pixel scale of the panel label
+const LABEL_SCALE: u32 = 2;
+
+/// Space reserved above the gauges when the panel has a label
+const LABEL_HEIGHT: u32 = 7 * LABEL_SCALE + 4;
+
+struct PanelGauge {
+    gauge: TSGauge,
+    /// Offset of the gauge from the panel origin
+    offset_x: u32,
+    offset_y: u32,
+}
+
+pub struct GaugePanel {
+    gauges: [Option<PanelGauge>; MAX_PANEL_GAUGES],
+    gauge_count: usize,
+    label: [u8; MAX_LABEL_LEN],
+    pub label_color: Color,
+}
+
+impl GaugePanel {
+    pub fn new(label: &str) -> Self {
+        let mut panel = GaugePanel {
+            gauges: [const { None }; MAX_PANEL_GAUGES],
+            gauge_count: 0,
+            label: [0; MAX_LABEL_LEN],
+            label_color: Theme::DARK.text,
+        };
+        panel.set_label(label);
+        panel
+    }
+
+    /// Set the label drawn at the top-left of the panel; empty for none
+    pub fn set_label(&mut self, label: &str) {
+        self.label = [0; MAX_LABEL_LEN];
+        let bytes = label.as_bytes();
+        let len = bytes.len().min(MAX_LABEL_LEN);
+        self.label[..len].copy_from_slice(&bytes[..len]);
+    }
+
+    pub fn label_str(&self) -> &str {
+        let len = self.label.iter().position(|&b| b == 0).unwrap_or(self.label.len());
+        core::str::from_utf8(&self.label[..len]).unwrap_or("")
+    }
+
+    /// Add a gauge at an offset from the panel origin (below the label, if
+    /// any). The gauge's own x/y are overwritten when the panel renders.
+    /// Returns false when the panel is full
+    pub fn add_gauge(&mut self, gauge: TSGauge, offset_x: u32, offset_y: u32) -> bool {
+        if self.gauge_count >= MAX_PANEL_GAUGES {
+            return false;
+        }
+        self.gauges[self.gauge_count] = Some(PanelGauge { gauge, offset_x, offset_y });
+        self.gauge_count += 1;
+        true
+    }
+
+    pub fn len(&self) -> usize {
+        self.gauge_count
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.gauge_count == 0
+    }
+
+    /// Access a gauge to feed it values or change its settings
+    pub fn gauge_mut(&mut self, index: usize) -> Option<&mut TSGauge> {
+        self.gauges[..self.gauge_count]
+            .get_mut(index)
+            .and_then(|slot| slot.as_mut())
+            .map(|panel_gauge| &mut panel_gauge.gauge)
+    }
+
+    /// Apply a theme to the label and every gauge in the panel
+    pub fn set_theme(&mut self, theme: Theme) {
+        self.label_color = theme.text;
+        for slot in self.gauges[..self.gauge_count].iter_mut().flatten() {
+            slot.gauge.theme = theme;
+            slot.gauge.dirty = true;
+        }
+    }
+
+    /// Advance needle animation on every gauge
+    pub fn update_animation(&mut self, dt_ms: u32) {
+        for slot in self.gauges[..self.gauge_count].iter_mut().flatten() {
+            slot.gauge.update_animation(dt_ms);
+        }
+    }
+
+    /// Height taken by the label strip (0 without a label)
+    fn label_height(&self) -> u32 {
+        if self.label_str().is_empty() { 0 } else { LABEL_HEIGHT }
+    }
+
+    /// Area covered by the panel at the given origin, as (x, y, width, height)
+    pub fn bounds(&self, origin_x: u32, origin_y: u32) -> (u32, u32, u32, u32) {
+        let top = origin_y + self.label_height();
+        let mut right = origin_x;
+        let mut bottom = top;
+        for slot in self.gauges[..self.gauge_count].iter().flatten() {
+            let (_, _, width, height) = slot.gauge.bounds();
+            right = right.max(origin_x + slot.offset_x + width);
+            bottom = bottom.max(top + slot.offset_y + height);
+        }
+        (origin_x, origin_y, right - origin_x, bottom - origin_y)
+    }
+
+    /// Draw the label and gauges with the panel's top-left at (origin_x, origin_y)
+    /// Gauges moved since the last call are redrawn in full; the caller is
+    /// responsible for clearing the area the panel moved away from
+    pub fn render(&mut self, fb: &mut Framebuffer, origin_x: u32, origin_y: u32) {
+        let label_height = self.label_height();
+        if label_height > 0 {
+            let label = self.label_str();
+            draw_str(fb, label, origin_x, origin_y, LABEL_SCALE, self.label_color);
+        }
+
+        let top = origin_y + label_height;
+        for slot in self.gauges[..self.gauge_count].iter_mut().flatten() {
+            let x = origin_x + slot.offset_x;
+            let y = top + slot.offset_y;
+            if slot.gauge.x != x || slot.gauge.y != y {
+                slot.gauge.x = x;
+                slot.gauge.y = y;
+                slot.gauge.dirty = true;
+            }
+            slot.gauge.render(fb);
+        }
+    }
+}
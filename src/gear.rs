@@ -0,0 +1,114 @@
+/// Calculated gear indicator
+/// Derives the engaged gear from the ratio of engine RPM to vehicle speed
+
+use crate::framebuffer::Framebuffer;
+use crate::colors::Color;
+use crate::digit_renderer::draw_digit;
+use crate::math::abs;
+
+pub const MAX_GEARS: usize = 6;
+
+/// Below this speed the RPM/speed ratio is meaningless (clutch in, stopped)
+const MIN_SPEED: f32 = 3.0;
+
+/// Relative distance from a configured ratio that still counts as a match
+const DEFAULT_TOLERANCE: f32 = 0.08;
+
+/// Extra tolerance granted to the currently displayed gear so the
+/// indicator doesn't flicker near the boundary between two gears
+const DEFAULT_HYSTERESIS: f32 = 0.05;
+
+pub struct GearCalculator {
+    /// RPM per unit of speed for each gear, first gear first
+    ratios: [f32; MAX_GEARS],
+    gear_count: usize,
+    pub tolerance: f32,
+    pub hysteresis: f32,
+    last_gear: Option<u8>,
+}
+
+impl GearCalculator {
+    pub fn new() -> Self {
+        GearCalculator {
+            ratios: [0.0; MAX_GEARS],
+            gear_count: 0,
+            tolerance: DEFAULT_TOLERANCE,
+            hysteresis: DEFAULT_HYSTERESIS,
+            last_gear: None,
+        }
+    }
+
+    /// Configure the next gear's RPM/speed ratio
+    /// Returns false once all gears are configured or the ratio is invalid
+    pub fn add_gear(&mut self, ratio: f32) -> bool {
+        if self.gear_count >= MAX_GEARS || ratio <= 0.0 {
+            return false;
+        }
+        self.ratios[self.gear_count] = ratio;
+        self.gear_count += 1;
+        true
+    }
+
+    pub fn gear_count(&self) -> usize {
+        self.gear_count
+    }
+
+    /// Relative error between a measured ratio and a gear's configured ratio
+    fn ratio_error(&self, index: usize, ratio: f32) -> f32 {
+        abs(ratio - self.ratios[index]) / self.ratios[index]
+    }
+
+    /// Best-matching gear (1-based) for the given RPM and speed
+    /// Returns None when stopped or when no configured ratio is close enough
+    pub fn current_gear(&mut self, rpm: f32, speed: f32) -> Option<u8> {
+        if speed < MIN_SPEED || rpm <= 0.0 {
+            self.last_gear = None;
+            return None;
+        }
+
+        let ratio = rpm / speed;
+
+        // Stick with the displayed gear while it is still within its widened window
+        if let Some(gear) = self.last_gear {
+            let index = (gear - 1) as usize;
+            if index < self.gear_count
+                && self.ratio_error(index, ratio) <= self.tolerance + self.hysteresis
+            {
+                return self.last_gear;
+            }
+        }
+
+        let mut best: Option<(usize, f32)> = None;
+        for i in 0..self.gear_count {
+            let error = self.ratio_error(i, ratio);
+            if error > self.tolerance {
+                continue;
+            }
+            match best {
+                Some((_, best_error)) if best_error <= error => {}
+                _ => best = Some((i, error)),
+            }
+        }
+
+        self.last_gear = best.map(|(i, _)| (i + 1) as u8);
+        self.last_gear
+    }
+
+    /// Draw the gear as a large digit, or a dash when no gear is known
+    pub fn render(&self, fb: &mut Framebuffer, gear: Option<u8>, x: u32, y: u32, size: u32, color: Color) {
+        match gear {
+            Some(g) => draw_digit(fb, g, x, y, size, color),
+            None => {
+                // Same footprint as the middle segment of a digit
+                let thickness = size / 2;
+                fb.draw_filled_rect(x + thickness, y + size, size, thickness, color.to_u32());
+            }
+        }
+    }
+}
+
+impl Default for GearCalculator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -1,6 +1,8 @@
-﻿#![no_std]
-#![no_main]
+﻿// Host unit tests (`cargo test --target <host triple>`) build with std
+#![cfg_attr(not(test), no_std)]
+#![cfg_attr(not(test), no_main)]
 
+#[cfg(not(test))]
 mod boot;
 mod framebuffer_config;
 mod framebuffer;
@@ -9,7 +11,7 @@ mod mmio;
 mod ts_ini_parser;
 mod ts_gauge;
 mod uart;
-// mod megasquirt;
+mod megasquirt;
 mod math;
 mod fatfs;
 mod mock_ecu;
@@ -17,15 +19,84 @@ mod xml_parser;
 mod config_loader;
 mod colors;
 mod digit_renderer;
+mod shift_light;
+mod gear;
+mod units;
+mod data_logger;
+mod alarm;
+mod boot_splash;
+mod trip;
+mod perf_timer;
+mod sensors;
+mod data_source;
+mod fuel;
+mod system_state;
+mod persistent_state;
+mod timer;
+mod gauge_panel;
+mod value_expr;
+mod panic_screen;
+mod page_button;
+#[cfg(feature = "watchdog")]
+mod watchdog;
 
+#[cfg(not(test))]
 use core::panic::PanicInfo;
+use core::mem;
+use core::ptr::addr_of_mut;
 use framebuffer::Framebuffer;
 use framebuffer_config::FramebufferConfig;
-use mock_ecu::MockECU;
-use config_loader::DashboardConfig;
+use config_loader::{load_ecu_ini, DashboardConfig, MAX_DASHBOARD_GAUGES};
+use alarm::AlarmManager;
+use boot_splash::{BootProgress, BootStage};
+use dashboard::{link_stats_size, render_link_stats, DashElement, DashboardManager};
+use data_source::{ChangeTracking, DataSource, EcuSource, SnapshotChannel};
+use fatfs::SDCard;
+use megasquirt::ChannelMap;
+use page_button::PageButton;
+use persistent_state::PersistentState;
+use system_state::SystemState;
+use timer::FramePacer;
+use trip::TripMeter;
+use ts_gauge::{TSGauge, TSGaugeStyle};
+use ts_ini_parser::GaugeConfig;
+use units::DistanceUnit;
 
+/// Time between "Running..." messages on the debug console
+const HEARTBEAT_INTERVAL_MS: u32 = 5_000;
+
+/// Trip readout in the bottom-left corner
+const TRIP_DIGIT_SIZE: u32 = 8;
+const TRIP_MARGIN: u32 = 8;
+
+/// Gauges given a graph on the graph page, stacked top to bottom
+const GRAPH_PAGE_GAUGES: usize = 4;
+const GRAPH_MARGIN: u32 = 8;
+
+/// Link counters on the diagnostics page, refreshed this often
+const LINK_STATS_SCALE: u32 = 3;
+const LINK_STATS_MARGIN: u32 = 16;
+const LINK_STATS_INTERVAL_MS: u32 = 1_000;
+
+/// Reset the board if the main loop stalls this long (`watchdog` feature)
+#[cfg(feature = "watchdog")]
+const WATCHDOG_TIMEOUT_MS: u32 = 2_000;
+
+/// Placeholder filling unused `GAUGES` slots
+const EMPTY_GAUGE: TSGauge = TSGauge::new(GaugeConfig::new(), TSGaugeStyle::Digital, 0, 0, 0, 0);
+
+/// Dashboard state, kept in statics rather than on the 32KB boot stack,
+/// which together they would nearly fill
+static mut CONFIG: DashboardConfig = DashboardConfig::new();
+static mut ECU: ChangeTracking<EcuSource> = ChangeTracking::new(EcuSource::new());
+static mut GAUGES: [TSGauge; MAX_DASHBOARD_GAUGES] = [EMPTY_GAUGE; MAX_DASHBOARD_GAUGES];
+static mut CHANNELS: ChannelMap = ChannelMap::new();
+static mut DASHBOARDS: DashboardManager = DashboardManager::new();
+
+#[cfg(not(test))]
 #[panic_handler]
-fn panic(_info: &PanicInfo) -> ! {
+fn panic(info: &PanicInfo) -> ! {
+    panic_screen::show(info);
     loop {}
 }
 
@@ -35,77 +106,278 @@ pub extern "C" fn kernel_main() -> ! {
     uart::uart_init();
     uart::uart_puts("\n=== LibreDash Boot v0.1 ===\n");
 
+    // Safety: only the boot core runs and nothing else uses these statics,
+    // so these are the only references to them
+    let config = unsafe { &mut *addr_of_mut!(CONFIG) };
+    let ecu = unsafe { &mut *addr_of_mut!(ECU) };
+    let gauge_slots = unsafe { &mut *addr_of_mut!(GAUGES) };
+    let channels = unsafe { &mut *addr_of_mut!(CHANNELS) };
+    let dashboards = unsafe { &mut *addr_of_mut!(DASHBOARDS) };
+
+    // config.ini from the SD card, or the built-in dashboard; read before
+    // the framebuffer is set up because it can pick the resolution
+    config.load_default_dashboard();
+    if config.load_from_sd_card() {
+        uart::uart_puts("Loaded config.ini\n");
+    } else {
+        uart::uart_puts("No config.ini, using default dashboard\n");
+    }
+
+    // Trip and peaks from the last drive; the card stays mounted to save them
+    let mut card = SDCard::init();
+    let mut saved = PersistentState::load(card.as_mut());
+
     // Detect environment and get framebuffer configuration
-    let fb_config = FramebufferConfig::detect();
+    let fb_config = FramebufferConfig::detect_with_resolution(config.resolution);
     uart::uart_puts("Framebuffer mode: ");
     uart::uart_puts(fb_config.mode_name());
     uart::uart_puts("\n");
 
     // Initialize framebuffer with detected address
     uart::uart_puts("Initializing framebuffer...\n");
-    let mut fb = Framebuffer::new(fb_config.address, fb_config.width, fb_config.height);
+    let mut fb = Framebuffer::with_pitch(fb_config.address, fb_config.width, fb_config.height, fb_config.pitch);
+    panic_screen::register(fb_config.address, fb_config.width, fb_config.height, fb_config.pitch);
+    // Everything from the splash on is drawn for the display's mounting
+    fb.set_rotation(config.rotation);
     uart::uart_puts("Framebuffer: ");
-    uart::uart_puts("1280x720 @ 0x");
+    uart::uart_put_dec(fb.width());
+    uart::uart_puts("x");
+    uart::uart_put_dec(fb.height());
+    uart::uart_puts(" @ 0x");
     uart::uart_puts(&format_hex_str(fb_config.address));
     uart::uart_puts("\n");
 
-    // Draw test pattern
-    uart::uart_puts("Drawing test pattern...\n");
-    test_display_pattern(&mut fb);
-    uart::uart_puts("Display pattern rendered\n");
+    // Splash with a progress bar, so a hang shows which stage it is in
+    let mut boot = BootProgress::new();
+    boot.draw(&mut fb);
+    boot.stage_complete(&mut fb, BootStage::SdMount);
+    boot.stage_complete(&mut fb, BootStage::FramebufferReady);
+    // A missing config.ini is fine (the built-in dashboard is used); a
+    // layout that doesn't fit the screen is the error worth flagging
+    let config_ok = config.check_layout(fb.width(), fb.height());
+    if !config_ok {
+        uart::uart_puts("Layout rejected, using default grid\n");
+    }
+    boot.stage_complete(&mut fb, BootStage::ConfigParsed);
 
-    // Hex dump first 64 pixels
-    uart::uart_puts("First 64 pixels:\n");
-    uart::uart_hex_dump(fb.buffer_ptr(), 256);
+    // The console moves or goes quiet before the ECU takes its pins
+    let console_port = config.active_console_port();
+    if console_port.is_none() {
+        uart::uart_puts("Console off, the ECU uses its port\n");
+    }
+    uart::set_console_port(console_port);
 
-    // Main loop with heartbeat
-    let mut counter: u32 = 0;
-    loop {
-        counter += 1;
+    // ECU link, unless the mock generator is configured
+    ecu.source_mut().apply_config(config);
+    let mut ecu_connected = false;
+    if let EcuSource::MegaSquirt(source) = ecu.source_mut() {
+        // The firmware's own INI gives the packet layout; without one the
+        // built-in MS2 offsets are used
+        if let Some(ecu_ini) = load_ecu_ini() {
+            source.configure_from_ini(ecu_ini);
+            uart::uart_puts("Loaded ECU.INI: ");
+            uart::uart_put_dec(source.channel_count() as u32);
+            uart::uart_puts(" channels, realtime block ");
+            if source.ms.realtime_len() == 0 {
+                uart::uart_puts("size unknown\n");
+            } else {
+                uart::uart_put_dec(source.ms.realtime_len() as u32);
+                uart::uart_puts(" bytes\n");
+            }
+        } else {
+            uart::uart_puts("No ECU.INI, using built-in channel offsets\n");
+        }
+        uart::uart_puts("Connecting to ECU...\n");
+        ecu_connected = source.ms.connect_auto(config.ecu_checksum);
+        if ecu_connected {
+            uart::uart_puts("ECU connected\n");
+        } else {
+            uart::uart_puts("No ECU response\n");
+        }
+    }
+    if !ecu_connected && !config.use_mock_ecu && config.mock_enabled {
+        *ecu.source_mut() = EcuSource::mock(config);
+        // The pins are free again now that no ECU is being driven
+        uart::set_console_port(config.console_port);
+        uart::uart_puts("Falling back to the mock ECU\n");
+    }
+    // Flagged on screen so simulated numbers are never taken for real ones
+    let mut state = SystemState::from_link(ecu_connected, ecu.source().is_mock(), config_ok);
+    boot.stage_complete(&mut fb, BootStage::EcuSweep);
 
-        // Print heartbeat every ~5 seconds
-        if counter % 500_000 == 0 {
-            uart::uart_puts("Running...\n");
+    let mut gauge_count = 0;
+    for i in 0..config.gauge_count {
+        if let Some(gauge) = config.build_gauge(i) {
+            gauge_slots[gauge_count] = gauge;
+            gauge_count += 1;
         }
+    }
+    let gauges = &mut gauge_slots[..gauge_count];
+    uart::uart_put_dec(gauge_count as u32);
+    uart::uart_puts(" gauges built\n");
+    boot.stage_complete(&mut fb, BootStage::GaugesBuilt);
+
+    // Key-on sweep, so a dead segment or wrong zone color shows before driving
+    boot_splash::gauge_selftest(&mut fb, gauges);
 
-        // Spin delay
-        for _ in 0..10_000 {
-            unsafe {
-                let dummy = 0u32;
-                core::ptr::read_volatile(&dummy);
+    let mut alarms = AlarmManager::new();
+    alarms.theme = config.theme;
+    for gauge in gauges.iter() {
+        alarms.watch(gauge.config.name_str());
+    }
+
+    // Speed arrives in mph, like the temperatures in °F
+    let mut trip = TripMeter::new(DistanceUnit::Miles, config.distance_unit);
+    trip.restore(saved.values().trip_distance);
+    let mut speed = 0.0;
+    let (trip_width, trip_height) = trip.readout_size(TRIP_DIGIT_SIZE);
+    let trip_y = fb.height().saturating_sub(trip_height + TRIP_MARGIN);
+    let mut shown_tenths = u32::MAX;
+
+    // Page 0 holds the gauges, each reading its channel from `channels` by
+    // name; the graph page follows the first few of them over the last minute,
+    // above the trip readout
+    dashboards.add_page("GAUGES");
+    let graph_count = gauges.len().min(GRAPH_PAGE_GAUGES) as u32;
+    if let Some(height) = trip_y.saturating_sub(GRAPH_MARGIN).checked_div(graph_count) {
+        if let Some(page) = dashboards.add_page("GRAPHS") {
+            let width = fb.width().saturating_sub(2 * GRAPH_MARGIN);
+            for (i, gauge) in gauges.iter().take(GRAPH_PAGE_GAUGES).enumerate() {
+                let y = GRAPH_MARGIN + i as u32 * height;
+                let channel = config.channel_for(gauge.config.var_str());
+                page.add_element(DashElement::graph_of(gauge, channel, GRAPH_MARGIN, y, width, height.saturating_sub(GRAPH_MARGIN)));
             }
         }
     }
-}
-
-/// Generate resolution-scaled test pattern
-fn test_display_pattern(fb: &mut Framebuffer) {
-    let w = fb.width();
-    let h = fb.height();
-
-    // Fill with gradient background (top: white, bottom: gray)
-    for y in 0..h {
-        let intensity = 255 - ((y * 128) / h) as u8;
-        let gray_color = ((intensity as u32) << 16) | ((intensity as u32) << 8) | (intensity as u32);
-        for x in 0..w {
-            fb.draw_pixel(x, y, gray_color);
+    if let Some(page) = dashboards.page_mut(0) {
+        for gauge in gauges.iter_mut() {
+            let gauge_config = gauge.config;
+            let channel = config.channel_for(gauge_config.var_str());
+            page.add_element(DashElement::from_gauge(mem::replace(gauge, EMPTY_GAUGE), channel));
         }
     }
+    // Serial link counters, for chasing wiring and baud problems; the mock
+    // has no link to show
+    let link_page = dashboards.page_count();
+    let link_page = if ecu.source().link_stats().is_some() && dashboards.add_page("LINK").is_some() {
+        Some(link_page)
+    } else {
+        None
+    };
+    let (link_width, link_height) = link_stats_size(LINK_STATS_SCALE);
+    let mut since_link_stats_ms = LINK_STATS_INTERVAL_MS;
+    dashboards.set_theme(config.theme);
 
-    // Draw 4 colored corner rectangles (100x100)
-    fb.draw_filled_rect(0, 0, 100, 100, framebuffer::COLOR_RED);
-    fb.draw_filled_rect(w - 100, 0, 100, 100, framebuffer::COLOR_GREEN);
-    fb.draw_filled_rect(0, h - 100, 100, 100, framebuffer::COLOR_BLUE);
-    fb.draw_filled_rect(w - 100, h - 100, 100, 100, framebuffer::COLOR_YELLOW);
-
-    // Draw white grid (8 columns x 4 rows)
-    for i in 0..=8 {
-        let x = i * (w / 8);
-        fb.draw_filled_rect(x, 0, 2, h, framebuffer::COLOR_WHITE);
+    let mut page_button = config.page_button.and_then(PageButton::new);
+    if page_button.is_some() {
+        uart::uart_puts("Page button enabled\n");
     }
-    for i in 0..=4 {
-        let y = i * (h / 4);
-        fb.draw_filled_rect(0, y, w, 2, framebuffer::COLOR_WHITE);
+
+    // Main loop, paced to a fixed frame rate
+    let mut pacer = FramePacer::new(timer::DEFAULT_FPS);
+    let mut since_heartbeat_ms: u32 = 0;
+    #[cfg(feature = "watchdog")]
+    let watchdog = {
+        uart::uart_puts("Watchdog armed\n");
+        watchdog::Watchdog::start(WATCHDOG_TIMEOUT_MS)
+    };
+    loop {
+        #[cfg(feature = "watchdog")]
+        watchdog.pet();
+
+        // Measured frame time; drives animation, alarms and trip integration
+        let dt_ms = pacer.wait();
+
+        // Only elements whose channel moved get a new value, so a steady
+        // cruise leaves most gauges clean and undrawn
+        if let Some(snapshot) = ecu.poll() {
+            // Every INI channel by name, then the snapshot's normalized
+            // values, then the [Expressions] derived from them
+            if let Some(ecu_channels) = ecu.source().channels() {
+                channels.merge(ecu_channels);
+            }
+            snapshot.store_channels(channels);
+            config.expressions.apply(channels);
+            dashboards.update_channels(channels, |name| {
+                config.expressions.defines(name)
+                    || SnapshotChannel::from_var(name).is_none_or(|channel| snapshot.is_changed(channel))
+            });
+            speed = snapshot.vehicle_speed;
+            saved.set_peak_rpm(snapshot.rpm);
+        }
+        trip.update(speed, dt_ms);
+
+        // Written at most every 30 s while values change; a failed write
+        // turns saving off rather than stalling every frame
+        saved.set_trip_distance(trip.distance());
+        if !saved.update(dt_ms, card.as_mut()) {
+            uart::uart_puts("Saving STATE.BIN failed, not retrying\n");
+        }
+
+        // The ECU can come and go; a new banner needs the old one cleared
+        let current = SystemState::from_link(ecu.source().is_live(), ecu.source().is_mock(), config_ok);
+        if current != state {
+            state = current;
+            if let Some(page) = dashboards.current_mut() {
+                page.invalidate();
+            }
+        }
+
+        if page_button.as_mut().is_some_and(|button| button.poll(dt_ms)) {
+            dashboards.next_page();
+        }
+
+        // One gauge redraw can outlast the 16-byte UART FIFO at 115200 baud,
+        // so drain it after every element
+        dashboards.update(dt_ms);
+        let cleared = dashboards.render_current_with(&mut fb, || ecu.source_mut().pump_rx());
+        if cleared {
+            config.render_layout_error(&mut fb);
+            shown_tenths = u32::MAX;
+        }
+        if link_page == Some(dashboards.current_index()) {
+            since_link_stats_ms += dt_ms;
+            if cleared || since_link_stats_ms >= LINK_STATS_INTERVAL_MS {
+                since_link_stats_ms = 0;
+                if let Some(stats) = ecu.source().link_stats() {
+                    let background = config.theme.background.to_u32();
+                    fb.draw_filled_rect(LINK_STATS_MARGIN, LINK_STATS_MARGIN, link_width, link_height, background);
+                    render_link_stats(&mut fb, &stats, LINK_STATS_MARGIN, LINK_STATS_MARGIN, LINK_STATS_SCALE, config.theme.text);
+                }
+            }
+        }
+
+        // Alarms watch the gauges page wherever the display is; the borders
+        // only make sense on it, the banner shows on every page
+        if let Some(page) = dashboards.page(0) {
+            alarms.update(page.gauges(), dt_ms);
+            if dashboards.current_index() == 0 {
+                alarms.render(&mut fb, page.gauges());
+            } else {
+                alarms.render(&mut fb, core::iter::empty());
+            }
+        }
+
+        // Drawn every frame in case a gauge painted over it; cleared only
+        // when the reading changes
+        let tenths = (trip.distance() * 10.0) as u32;
+        if tenths != shown_tenths {
+            fb.draw_filled_rect(TRIP_MARGIN, trip_y, trip_width, trip_height, config.theme.background.to_u32());
+            shown_tenths = tenths;
+        }
+        trip.render(&mut fb, TRIP_MARGIN, trip_y, TRIP_DIGIT_SIZE, config.theme.text);
+        state.render(&mut fb, &config.theme);
+
+        // Print heartbeat every 5 seconds
+        since_heartbeat_ms += dt_ms;
+        if since_heartbeat_ms >= HEARTBEAT_INTERVAL_MS {
+            uart::uart_puts("Running, trip ");
+            uart::uart_put_dec(trip.distance() as u32);
+            uart::uart_puts(" ");
+            uart::uart_puts(trip.display_unit.label());
+            uart::uart_puts("\n");
+            since_heartbeat_ms = 0;
+        }
     }
 }
 
@@ -118,7 +390,3 @@ fn format_hex_str(val: u32) -> &'static str {
         _ => "????????",
     }
 }
-
-fn get_var_name(_config: &ts_ini_parser::GaugeConfig) -> Option<&str> {
-    None
-}
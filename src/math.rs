@@ -1,20 +1,34 @@
 /// Math utilities for bare-metal graphics and calculations
 /// Includes trigonometric functions and basic arithmetic
 
+use core::f32::consts::{FRAC_PI_2, LN_2, LOG2_E, PI, SQRT_2};
+
+/// Reduce an angle to -PI..PI in constant time
+/// Subtracts the nearest multiple of 2*PI, using a split constant
+/// (Cody-Waite) so large inputs keep their precision
+fn wrap_pi(x: f32) -> f32 {
+    const INV_TWO_PI: f32 = 0.15915494;
+    const TWO_PI_HI: f32 = 6.28125;
+    const TWO_PI_LO: f32 = 0.0019353072;
+
+    let k = x * INV_TWO_PI;
+    let n = (if k >= 0.0 { (k + 0.5) as i32 } else { (k - 0.5) as i32 }) as f32;
+    (x - n * TWO_PI_HI) - n * TWO_PI_LO
+}
+
 /// Sine approximation using Taylor series
-/// Input: radians
+/// Input: radians (any magnitude); error < 1e-6 after range reduction
 pub fn sin(x: f32) -> f32 {
-    // Normalize angle to -PI to PI
-    const PI: f32 = 3.141592653589793;
-    const TWO_PI: f32 = 2.0 * PI;
-    
-    let mut x = x;
-    while x > PI {
-        x -= TWO_PI;
-    }
-    while x < -PI {
-        x += TWO_PI;
-    }
+    // Normalize angle to -PI to PI, then fold to -PI/2..PI/2
+    // using sin(x) = sin(PI - x)
+    let x = wrap_pi(x);
+    let x = if x > FRAC_PI_2 {
+        PI - x
+    } else if x < -FRAC_PI_2 {
+        -PI - x
+    } else {
+        x
+    };
 
     // Taylor series approximation
     let x2 = x * x;
@@ -22,14 +36,67 @@ pub fn sin(x: f32) -> f32 {
     let x5 = x3 * x2;
     let x7 = x5 * x2;
     let x9 = x7 * x2;
+    let x11 = x9 * x2;
 
-    x - x3 / 6.0 + x5 / 120.0 - x7 / 5040.0 + x9 / 362880.0
+    x - x3 / 6.0 + x5 / 120.0 - x7 / 5040.0 + x9 / 362880.0 - x11 / 39916800.0
 }
 
-/// Cosine approximation using sin
+/// Cosine approximation using Taylor series
+/// Input: radians
 pub fn cos(x: f32) -> f32 {
-    const PI: f32 = 3.141592653589793;
-    sin(x + PI / 2.0)
+    // Normalize angle to -PI to PI, then fold to 0..PI (cos is even)
+    let x = abs(wrap_pi(x));
+
+    // cos(x) = -cos(PI - x) keeps the series argument within 0..PI/2
+    if x > FRAC_PI_2 {
+        -cos_series(PI - x)
+    } else {
+        cos_series(x)
+    }
+}
+
+/// Cosine Taylor series, accurate for |x| <= PI/2
+fn cos_series(x: f32) -> f32 {
+    let x2 = x * x;
+    let x4 = x2 * x2;
+    let x6 = x4 * x2;
+    let x8 = x6 * x2;
+    let x10 = x8 * x2;
+
+    1.0 - x2 / 2.0 + x4 / 24.0 - x6 / 720.0 + x8 / 40320.0 - x10 / 3628800.0
+}
+
+/// Arctangent approximation for |x| <= 1 (minimax polynomial, error < 1e-5 rad)
+fn atan_unit(x: f32) -> f32 {
+    let x2 = x * x;
+    x * (0.99997726
+        + x2 * (-0.33262347
+            + x2 * (0.19354346 + x2 * (-0.11643287 + x2 * (0.05265332 + x2 * -0.0117212)))))
+}
+
+/// Four-quadrant arctangent of y/x
+/// Returns radians in -PI..PI; atan2(0, 0) returns 0
+pub fn atan2(y: f32, x: f32) -> f32 {
+    let ax = abs(x);
+    let ay = abs(y);
+    if ax == 0.0 && ay == 0.0 {
+        return 0.0;
+    }
+
+    // Keep the ratio within -1..1 for the polynomial
+    let mut angle = if ax >= ay {
+        atan_unit(ay / ax)
+    } else {
+        FRAC_PI_2 - atan_unit(ax / ay)
+    };
+
+    if x < 0.0 {
+        angle = PI - angle;
+    }
+    if y < 0.0 {
+        angle = -angle;
+    }
+    angle
 }
 
 /// Absolute value
@@ -37,6 +104,12 @@ pub fn abs(x: f32) -> f32 {
     if x < 0.0 { -x } else { x }
 }
 
+/// Largest integer value not greater than x
+pub fn floor(x: f32) -> f32 {
+    let truncated = x as i32 as f32;
+    if truncated > x { truncated - 1.0 } else { truncated }
+}
+
 /// Maximum of two values
 pub fn max(a: f32, b: f32) -> f32 {
     if a > b { a } else { b }
@@ -68,6 +141,93 @@ pub fn sqrt(x: f32) -> f32 {
     z
 }
 
+/// Returned by `ln` for zero or negative input
+pub const LN_DOMAIN_ERROR: f32 = f32::MIN;
+
+/// Largest x whose e^x is a finite f32
+const LN_F32_MAX: f32 = 88.72283;
+
+/// Natural exponential e^x
+/// Splits x = k*ln2 + r so the series only has to cover |r| <= ln2/2,
+/// then scales by 2^k through the exponent bits. Saturates to f32::MAX
+pub fn exp(x: f32) -> f32 {
+    if x > LN_F32_MAX {
+        return f32::MAX;
+    }
+    if x < -87.3 {
+        return 0.0;
+    }
+
+    let kf = x * LOG2_E;
+    let k = if kf >= 0.0 { (kf + 0.5) as i32 } else { (kf - 0.5) as i32 };
+    let r = x - k as f32 * LN_2;
+
+    // Taylor series for e^r
+    let mut term = 1.0;
+    let mut sum = 1.0;
+    for n in 1..9 {
+        term *= r / n as f32;
+        sum += term;
+    }
+
+    // 2^128 isn't representable, so the top of the range scales by
+    // 2^(k-1) and doubles afterwards
+    if k > 127 {
+        sum * 2.0 * f32::from_bits(((k + 126) as u32) << 23)
+    } else {
+        sum * f32::from_bits(((k + 127) as u32) << 23)
+    }
+}
+
+/// Natural logarithm
+/// Returns `LN_DOMAIN_ERROR` for x <= 0
+pub fn ln(x: f32) -> f32 {
+    if x <= 0.0 {
+        return LN_DOMAIN_ERROR;
+    }
+
+    // Split x = m * 2^e with m in 1..2
+    let bits = x.to_bits();
+    let mut e = ((bits >> 23) & 0xFF) as i32 - 127;
+    let mut m = f32::from_bits((bits & 0x007FFFFF) | 0x3F800000);
+
+    // Center m around 1 so the series converges quickly
+    if m > SQRT_2 {
+        m *= 0.5;
+        e += 1;
+    }
+
+    // ln(m) = 2 * atanh((m - 1) / (m + 1))
+    let s = (m - 1.0) / (m + 1.0);
+    let s2 = s * s;
+    let series = s * (2.0 + s2 * (2.0 / 3.0 + s2 * (2.0 / 5.0 + s2 * (2.0 / 7.0 + s2 * (2.0 / 9.0)))));
+
+    e as f32 * LN_2 + series
+}
+
+/// base^exponent, computed as exp(exponent * ln(base))
+/// Negative bases are only defined for whole-number exponents; other
+/// cases return 0.0
+pub fn powf(base: f32, exponent: f32) -> f32 {
+    if exponent == 0.0 {
+        return 1.0;
+    }
+    if base == 0.0 {
+        return 0.0;
+    }
+
+    if base < 0.0 {
+        let whole = exponent as i32;
+        if whole as f32 != exponent {
+            return 0.0;
+        }
+        let magnitude = exp(exponent * ln(-base));
+        return if whole % 2 == 0 { magnitude } else { -magnitude };
+    }
+
+    exp(exponent * ln(base))
+}
+
 /// Clamp value between min and max
 pub fn clamp(value: f32, min: f32, max: f32) -> f32 {
     if value < min { min } else if value > max { max } else { value }
@@ -119,3 +279,86 @@ pub fn parse_int(s: &str) -> u32 {
     }
     result
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(actual: f32, expected: f32) {
+        let tolerance = expected.abs() * 1e-5 + 1e-6;
+        assert!((actual - expected).abs() <= tolerance, "{} != {}", actual, expected);
+    }
+
+    #[test]
+    fn sin_cos_track_reference_over_large_angles() {
+        // 0..100*PI in steps that don't line up with the period
+        let mut worst = 0.0f32;
+        for i in 0..=20_000 {
+            let x = i as f32 * (100.0 * PI / 20_000.0) + 0.001;
+            let reference = (x as f64).sin() as f32;
+            worst = worst.max(abs(sin(x) - reference));
+            worst = worst.max(abs(cos(x) - (x as f64).cos() as f32));
+        }
+        assert!(worst < 1e-4, "max error {}", worst);
+    }
+
+    #[test]
+    fn sin_cos_known_angles() {
+        assert!(abs(sin(PI)) < 1e-6);
+        assert!(abs(sin(FRAC_PI_2) - 1.0) < 1e-6);
+        assert!(abs(sin(-FRAC_PI_2) + 1.0) < 1e-6);
+        assert!(abs(cos(PI) + 1.0) < 1e-6);
+        assert!(abs(cos(0.0) - 1.0) < 1e-6);
+    }
+
+    #[test]
+    fn atan2_quadrants() {
+        assert_eq!(atan2(0.0, 0.0), 0.0);
+        for (y, x) in [(1.0f32, 1.0f32), (1.0, -1.0), (-1.0, -1.0), (-1.0, 1.0), (3.0, 0.5), (-0.2, -4.0), (1.0, 0.0), (0.0, -1.0)] {
+            assert!((atan2(y, x) - y.atan2(x)).abs() < 1e-4, "atan2({}, {})", y, x);
+        }
+    }
+
+    #[test]
+    fn exp_known_values() {
+        assert_eq!(exp(0.0), 1.0);
+        assert_close(exp(1.0), core::f32::consts::E);
+        assert_close(exp(-1.0), 1.0 / core::f32::consts::E);
+        assert_close(exp(10.0), 22026.465);
+        assert_close(exp(-20.0), 2.0611537e-9);
+    }
+
+    #[test]
+    fn exp_top_of_range_stays_finite() {
+        for x in [88.0f32, 88.4, 88.5, 88.7, 88.72] {
+            let value = exp(x);
+            assert!(value.is_finite(), "exp({}) = {}", x, value);
+            assert_close(value, x.exp());
+        }
+        assert_eq!(exp(89.0), f32::MAX);
+        assert_eq!(exp(-100.0), 0.0);
+    }
+
+    #[test]
+    fn ln_known_values() {
+        assert_eq!(ln(1.0), 0.0);
+        assert_close(ln(core::f32::consts::E), 1.0);
+        assert_close(ln(10.0), core::f32::consts::LN_10);
+        assert_close(ln(0.001), -6.9077554);
+        assert_close(ln(1.0e30), 69.07755);
+        assert_eq!(ln(0.0), LN_DOMAIN_ERROR);
+        assert_eq!(ln(-1.0), LN_DOMAIN_ERROR);
+    }
+
+    #[test]
+    fn powf_known_values() {
+        assert_close(powf(2.0, 10.0), 1024.0);
+        assert_close(powf(9.0, 0.5), 3.0);
+        assert_close(powf(10.0, -2.0), 0.01);
+        assert_close(powf(-2.0, 3.0), -8.0);
+        assert_close(powf(-2.0, 2.0), 4.0);
+        assert_eq!(powf(-2.0, 0.5), 0.0);
+        assert_eq!(powf(0.0, 3.0), 0.0);
+        assert_eq!(powf(5.0, 0.0), 1.0);
+    }
+}
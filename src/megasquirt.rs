@@ -1,149 +1,807 @@
 // MegaSquirt serial protocol implementation
 // Fast, efficient ECU communication for real-time data
 
-// use crate::uart::Uart;
+use core::ops::Range;
+use crate::uart::{Uart, UartPort};
+use crate::ts_ini_parser::{DataType, IniParser, IniSection, OutputChannel, OutputChannels, MAX_OUTPUT_CHANNELS};
+use crate::units::{convert_mixture, MixtureUnit, STANDARD_ATMOSPHERE_KPA, STOICH_GASOLINE};
 
 /// MegaSquirt command codes
 const MS_CMD_SIGNATURE: u8 = b'S';
 const MS_CMD_REALTIME: u8 = b'A';
 const MS_CMD_TABLE: u8 = b'T';
 const MS_CMD_REVISION: u8 = b'Q';
+const MS_CMD_READ_PAGE: u8 = b'r';
 
-/// Maximum response size
-const MAX_RESPONSE_SIZE: usize = 256;
+/// Maximum response size; MS3 realtime blocks are several hundred bytes
+const MAX_RESPONSE_SIZE: usize = 1024;
 
-/// Communication timeout (CPU cycles)
-const TIMEOUT_CYCLES: u32 = 100000;
+/// Newserial framing around a reply's data: u16 length, response code, CRC32
+const NEWSERIAL_LEN_BYTES: usize = 2;
+const NEWSERIAL_CRC_BYTES: usize = 4;
+const NEWSERIAL_OVERHEAD: usize = NEWSERIAL_LEN_BYTES + 1 + NEWSERIAL_CRC_BYTES;
+
+/// Newserial response codes from here up are errors (CRC failure, unknown command...)
+const NEWSERIAL_ERROR_CODE: u8 = 0x80;
+
+/// Wait for each byte of a blocking reply (100 ms)
+const RECV_TIMEOUT_US: u32 = 100_000;
+
+/// Polls with no reply before a non-blocking request is abandoned
+const POLL_TIMEOUT_POLLS: u32 = 30;
+
+/// Baud rates tried, in order, when sweeping for the ECU
+const BAUD_RATES: [u32; 5] = [115200, 57600, 38400, 19200, 9600];
+
+/// Consecutive failed reads before the link is considered lost
+const MAX_CONSECUTIVE_FAILURES: u32 = 10;
+
+/// Polls to wait between reconnection sweeps
+const RECONNECT_INTERVAL_POLLS: u32 = 60;
+
+/// Length of a signature reply outside newserial framing
+const SIGNATURE_LEN: usize = 32;
+
+/// ECU link state for status indicators
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ConnectionState {
+    /// Link up and packets arriving
+    Connected,
+    /// Link was up but dropped; periodically re-sweeping baud rates
+    Reconnecting,
+    /// Never connected; sweeping baud rates in the background
+    Disconnected,
+}
+
+/// Progress of a non-blocking realtime request
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PollState {
+    /// Request in flight, keep rendering last-known data
+    Pending,
+    /// A new packet was validated and is available
+    Complete,
+    /// No reply, or the reply failed its checksum
+    Failed,
+}
+
+/// Integrity check applied to replies from the ECU
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ChecksumMode {
+    /// Accept packets as received
+    None,
+    /// Older firmware: trailing byte is the 8-bit sum of the payload
+    Sum8,
+    /// MS3 "newserial": commands and replies are each sent as a big-endian
+    /// u16 length, the body, then the big-endian CRC32 of the body. A reply
+    /// body is a response code followed by the data
+    Crc32,
+}
+
+impl ChecksumMode {
+    /// Parse an `ecu_checksum` config value ("none", "sum8", "crc32"/"newserial")
+    pub fn from_str(s: &str) -> Option<Self> {
+        let s = s.trim();
+        if s.eq_ignore_ascii_case("none") {
+            Some(ChecksumMode::None)
+        } else if s.eq_ignore_ascii_case("sum8") {
+            Some(ChecksumMode::Sum8)
+        } else if s.eq_ignore_ascii_case("crc32") || s.eq_ignore_ascii_case("newserial") {
+            Some(ChecksumMode::Crc32)
+        } else {
+            None
+        }
+    }
+
+    /// Bytes a reply carries besides its data
+    pub fn reply_overhead(&self) -> usize {
+        match self {
+            ChecksumMode::None => 0,
+            ChecksumMode::Sum8 => 1,
+            ChecksumMode::Crc32 => NEWSERIAL_OVERHEAD,
+        }
+    }
+}
+
+/// Byte order of multi-byte fields in the realtime packet
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Endian {
+    /// Most significant byte first (MegaSquirt native)
+    Big,
+    Little,
+}
+
+impl Endian {
+    /// Parse an INI `endianness` value ("big" or "little")
+    pub fn from_str(s: &str) -> Option<Self> {
+        let s = s.trim().trim_matches('"');
+        if s.eq_ignore_ascii_case("big") {
+            Some(Endian::Big)
+        } else if s.eq_ignore_ascii_case("little") {
+            Some(Endian::Little)
+        } else {
+            None
+        }
+    }
+}
+
+/// Location and scaling of one logical channel in the realtime packet
+#[derive(Clone, Copy, Debug)]
+pub struct FieldDef {
+    /// Byte offset from start of packet
+    pub offset: usize,
+    /// Width in bytes (1, 2 or 4)
+    pub size: usize,
+    /// Multiplier from raw units to engineering units
+    pub scale: f32,
+    /// Interpret raw value as two's complement
+    pub signed: bool,
+    /// Added after scaling
+    pub translate: f32,
+    /// Byte order of `size` > 1 fields
+    pub endian: Endian,
+}
+
+impl FieldDef {
+    pub const fn new(offset: usize, size: usize, scale: f32, signed: bool) -> Self {
+        FieldDef { offset, size, scale, signed, translate: 0.0, endian: Endian::Big }
+    }
+
+    /// Build a field definition from an INI output channel
+    pub fn from_channel(channel: &OutputChannel) -> Self {
+        FieldDef {
+            offset: channel.offset as usize,
+            size: channel.data_type.size(),
+            scale: channel.scale,
+            signed: channel.data_type.is_signed(),
+            translate: channel.translate,
+            endian: Endian::Big,
+        }
+    }
+}
+
+/// Realtime packet layout for the channels `MegaSquirt` exposes directly
+#[derive(Clone, Copy, Debug)]
+pub struct OffsetTable {
+    pub rpm: FieldDef,
+    pub map: FieldDef,
+    pub coolant_temp: FieldDef,
+    pub tps: FieldDef,
+    pub afr: FieldDef,
+    pub battery_voltage: FieldDef,
+}
+
+impl OffsetTable {
+    /// Typical MS2 layout
+    pub const fn ms2() -> Self {
+        OffsetTable {
+            rpm: FieldDef::new(6, 2, 1.0, false),
+            map: FieldDef::new(4, 2, 0.1, false),              // 0.1 kPa units
+            coolant_temp: FieldDef::new(8, 2, 0.1, true),      // 0.1°F units
+            tps: FieldDef::new(14, 2, 0.1, false),             // 0.1% units
+            afr: FieldDef::new(16, 2, 0.1, false),             // 0.1 AFR units
+            battery_voltage: FieldDef::new(18, 2, 0.1, false), // 0.1V units
+        }
+    }
+
+    /// Derive offsets from parsed [OutputChannels], keeping the MS2
+    /// defaults for any channel the INI does not define
+    pub fn from_output_channels(channels: &OutputChannels) -> Self {
+        let mut table = Self::ms2();
+        let lookup = |names: &[&str], fallback: FieldDef| {
+            names
+                .iter()
+                .find_map(|name| channels.get_by_name(name))
+                .map(FieldDef::from_channel)
+                .unwrap_or(fallback)
+        };
+
+        table.rpm = lookup(&["rpm"], table.rpm);
+        table.map = lookup(&["map"], table.map);
+        table.coolant_temp = lookup(&["coolant", "clt"], table.coolant_temp);
+        table.tps = lookup(&["tps", "throttle"], table.tps);
+        table.afr = lookup(&["afr1", "afr"], table.afr);
+        table.battery_voltage = lookup(&["batteryVoltage", "battery"], table.battery_voltage);
+        table
+    }
+
+    /// The same layout with every field read in the given byte order
+    pub fn with_endian(mut self, endian: Endian) -> Self {
+        for field in [
+            &mut self.rpm,
+            &mut self.map,
+            &mut self.coolant_temp,
+            &mut self.tps,
+            &mut self.afr,
+            &mut self.battery_voltage,
+        ] {
+            field.endian = endian;
+        }
+        self
+    }
+}
+
+impl Default for OffsetTable {
+    fn default() -> Self {
+        Self::ms2()
+    }
+}
+
+/// Link counters for diagnosing a flaky connection
+/// Mostly timeouts points at wiring or baud rate; checksum or length errors
+/// with a live link point at the checksum mode or `ochBlockSize`
+#[derive(Clone, Copy, Debug, Default)]
+pub struct LinkStats {
+    /// Realtime requests sent
+    pub requests: u32,
+    /// Packets that passed validation
+    pub packets_received: u32,
+    pub checksum_failures: u32,
+    /// Packets shorter or longer than the configured block size
+    pub length_errors: u32,
+    /// Requests that got no reply at all
+    pub timeouts: u32,
+    /// Bytes lost because the UART receive ring was full
+    pub bytes_dropped: u32,
+}
+
+impl LinkStats {
+    pub const fn new() -> Self {
+        LinkStats {
+            requests: 0,
+            packets_received: 0,
+            checksum_failures: 0,
+            length_errors: 0,
+            timeouts: 0,
+            bytes_dropped: 0,
+        }
+    }
+
+    /// Label/value pairs for a diagnostics display
+    pub fn rows(&self) -> [(&'static str, u32); 6] {
+        [
+            ("REQUESTS", self.requests),
+            ("RECEIVED", self.packets_received),
+            ("CHECKSUM ERR", self.checksum_failures),
+            ("LENGTH ERR", self.length_errors),
+            ("TIMEOUTS", self.timeouts),
+            ("BYTES DROPPED", self.bytes_dropped),
+        ]
+    }
+}
 
 /// MegaSquirt ECU interface
 pub struct MegaSquirt {
-    // uart: Uart,
+    uart: Uart,
     connected: bool,
+    checksum_mode: ChecksumMode,
+    offsets: OffsetTable,
+    /// Byte order of multi-byte realtime fields, from the INI `endianness`
+    endian: Endian,
     realtime_buffer: [u8; MAX_RESPONSE_SIZE],
     realtime_size: usize,
+    /// Firmware's realtime block size (ochBlockSize), excluding checksum;
+    /// 0 = unknown, read until the ECU stops sending
+    realtime_len: usize,
+    /// Packet being assembled by `poll_realtime`
+    rx_buffer: [u8; MAX_RESPONSE_SIZE],
+    rx_count: usize,
+    request_pending: bool,
+    idle_polls: u32,
+    state: ConnectionState,
+    consecutive_failures: u32,
+    reconnect_countdown: u32,
+    /// Background sweep: index into `BAUD_RATES` being tried, and whether
+    /// its signature request is awaiting a reply
+    sweep_index: usize,
+    probe_pending: bool,
+    stats: LinkStats,
 }
 
 impl MegaSquirt {
-    pub fn new() -> Self {
+    pub const fn new() -> Self {
         MegaSquirt {
-            // uart: Uart::new(),
+            uart: Uart::new(),
             connected: false,
+            checksum_mode: ChecksumMode::None,
+            offsets: OffsetTable::ms2(),
+            endian: Endian::Big,
             realtime_buffer: [0; MAX_RESPONSE_SIZE],
             realtime_size: 0,
+            realtime_len: 0,
+            rx_buffer: [0; MAX_RESPONSE_SIZE],
+            rx_count: 0,
+            request_pending: false,
+            idle_polls: 0,
+            state: ConnectionState::Disconnected,
+            consecutive_failures: 0,
+            reconnect_countdown: 0,
+            sweep_index: 0,
+            probe_pending: false,
+            stats: LinkStats::new(),
         }
     }
     
+    /// Select the serial port the ECU is wired to (PL011 by default)
+    /// Takes effect on the next `connect`
+    pub fn set_port(&mut self, port: UartPort) {
+        if self.uart.port() != port {
+            self.uart = Uart::with_port(port);
+            self.connected = false;
+            self.request_pending = false;
+            self.probe_pending = false;
+            self.state = ConnectionState::Disconnected;
+        }
+    }
+
     /// Initialize and connect to ECU (fast startup)
-    pub fn connect(&mut self, baud_rate: u32) -> bool {
-        // Initialize UART with specified baud rate
+    pub fn connect(&mut self, baud_rate: u32, checksum_mode: ChecksumMode) -> bool {
+        self.checksum_mode = checksum_mode;
+
+        // Initialize UART with specified baud rate (only the divisors
+        // change after the first attempt)
         // Common MegaSquirt baud rates: 9600, 19200, 38400, 57600, 115200
-        // self.uart.init(baud_rate);
+        self.uart.set_baud(baud_rate);
         
         // Flush any pending data
-        // self.uart.flush_rx();
+        self.uart.flush_rx();
         
         // Try to get signature
         if self.get_signature().is_some() {
-            self.connected = true;
+            self.link_up();
             true
         } else {
             false
         }
     }
+
+    /// Mark the link as connected after a signature reply
+    fn link_up(&mut self) {
+        self.connected = true;
+        self.state = ConnectionState::Connected;
+        self.consecutive_failures = 0;
+        self.request_pending = false;
+        self.probe_pending = false;
+        self.sweep_index = 0;
+    }
+
+    /// Try each supported baud rate until the ECU answers
+    pub fn connect_auto(&mut self, checksum_mode: ChecksumMode) -> bool {
+        for &baud_rate in BAUD_RATES.iter() {
+            if self.connect(baud_rate, checksum_mode) {
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Set the realtime block size (ochBlockSize) so reads stop after exactly
+    /// that many bytes plus the checksum framing. Returns false if the packet
+    /// would not fit the receive buffer
+    pub fn set_realtime_len(&mut self, len: usize) -> bool {
+        if len + NEWSERIAL_OVERHEAD > MAX_RESPONSE_SIZE {
+            return false;
+        }
+        self.realtime_len = len;
+        true
+    }
+
+    pub fn realtime_len(&self) -> usize {
+        self.realtime_len
+    }
+
+    /// Take packet layout from a parsed ECU INI: channel offsets from
+    /// [OutputChannels] and the block size from `ochBlockSize`
+    /// A block size too big for the receive buffer is ignored; check `realtime_len`
+    pub fn configure_from_ini(&mut self, parser: &IniParser) {
+        // TunerStudio INIs declare it in [Constants]
+        let endian = [IniSection::Constants, IniSection::OutputChannels]
+            .iter()
+            .find_map(|&section| parser.setting(section, "endianness"))
+            .and_then(Endian::from_str);
+        if let Some(endian) = endian {
+            self.endian = endian;
+        }
+        self.set_offset_table(OffsetTable::from_output_channels(parser.output_channels()));
+        if let Some(value) = parser.setting(IniSection::OutputChannels, "ochBlockSize") {
+            self.set_realtime_len(crate::math::parse_int(value.trim()) as usize);
+        }
+    }
+
+    /// Bytes expected for the reply being received: a newserial reply gives
+    /// its length up front; otherwise the block size plus checksum, or the
+    /// whole buffer if unknown
+    fn expected_packet_len(&self) -> usize {
+        if self.checksum_mode == ChecksumMode::Crc32 && self.rx_count >= NEWSERIAL_LEN_BYTES {
+            let body = u16::from_be_bytes([self.rx_buffer[0], self.rx_buffer[1]]) as usize;
+            (NEWSERIAL_LEN_BYTES + body + NEWSERIAL_CRC_BYTES).min(MAX_RESPONSE_SIZE)
+        } else if self.realtime_len == 0 {
+            MAX_RESPONSE_SIZE
+        } else {
+            // The checksum mode may have changed since the length was set
+            (self.realtime_len + self.checksum_mode.reply_overhead()).min(MAX_RESPONSE_SIZE)
+        }
+    }
+
+    /// Current link state
+    pub fn connection_state(&self) -> ConnectionState {
+        self.state
+    }
+
+    /// Snapshot of the link counters
+    pub fn link_stats(&self) -> LinkStats {
+        let mut stats = self.stats;
+        stats.bytes_dropped = self.uart.rx_overflows();
+        stats
+    }
+
+    pub fn reset_link_stats(&mut self) {
+        self.stats = LinkStats::default();
+    }
+
+    /// Count a failed read and drop the link after too many in a row
+    fn record_failure(&mut self) {
+        self.consecutive_failures += 1;
+        if self.consecutive_failures >= MAX_CONSECUTIVE_FAILURES {
+            self.connected = false;
+            self.request_pending = false;
+            self.state = ConnectionState::Reconnecting;
+            self.sweep_index = 0;
+            self.reconnect_countdown = RECONNECT_INTERVAL_POLLS;
+        }
+    }
+
+    /// Advance the background baud sweep by one step without blocking
+    /// Each baud rate gets a signature request on one poll and its reply is
+    /// collected over the following ones; after a full sweep with no answer
+    /// it waits `RECONNECT_INTERVAL_POLLS` before starting again
+    fn try_reconnect(&mut self) -> bool {
+        if self.reconnect_countdown > 0 {
+            self.reconnect_countdown -= 1;
+            return false;
+        }
+
+        if !self.probe_pending {
+            self.uart.set_baud(BAUD_RATES[self.sweep_index]);
+            self.uart.flush_rx();
+            self.send_command(&[MS_CMD_SIGNATURE]);
+            self.probe_pending = true;
+            self.rx_count = 0;
+            self.idle_polls = 0;
+            return false;
+        }
+
+        let before = self.rx_count;
+        while self.rx_count < self.signature_reply_len() && self.uart.has_data() {
+            match self.uart.recv_byte(0) {
+                Some(byte) => {
+                    self.rx_buffer[self.rx_count] = byte;
+                    self.rx_count += 1;
+                }
+                None => break,
+            }
+        }
+
+        if self.rx_count == self.signature_reply_len() {
+            let valid = self.checksum_mode != ChecksumMode::Crc32
+                || self.reply_data(&self.rx_buffer[..self.rx_count]).is_some();
+            if valid {
+                self.link_up();
+                return true;
+            }
+        } else if self.rx_count > before {
+            self.idle_polls = 0;
+            return false;
+        } else {
+            self.idle_polls += 1;
+            if self.idle_polls < POLL_TIMEOUT_POLLS {
+                return false;
+            }
+        }
+
+        // No (valid) answer at this baud rate; the next poll tries the next one
+        self.probe_pending = false;
+        self.sweep_index += 1;
+        if self.sweep_index == BAUD_RATES.len() {
+            self.sweep_index = 0;
+            self.reconnect_countdown = RECONNECT_INTERVAL_POLLS;
+        }
+        false
+    }
+
+    /// Bytes in a complete signature reply; a newserial one gives its length up front
+    fn signature_reply_len(&self) -> usize {
+        if self.checksum_mode == ChecksumMode::Crc32 {
+            self.expected_packet_len()
+        } else {
+            SIGNATURE_LEN
+        }
+    }
     
     /// Get ECU signature (for verification)
+    /// A newserial signature may be shorter than 32 bytes; the rest is zero
     pub fn get_signature(&mut self) -> Option<[u8; 32]> {
-        // self.uart.send_byte(MS_CMD_SIGNATURE);
-        
-        let mut sig = [0u8; 32];
-        let received = // self.uart.recv_bytes(&mut sig, TIMEOUT_CYCLES);
-        
-        if received == 32 {
+        self.send_command(&[MS_CMD_SIGNATURE]);
+
+        let mut sig = [0u8; SIGNATURE_LEN];
+        if self.checksum_mode == ChecksumMode::Crc32 {
+            let data = self.recv_framed_reply()?;
+            let len = data.len().min(sig.len());
+            sig[..len].copy_from_slice(&self.rx_buffer[data.start..data.start + len]);
+            return Some(sig);
+        }
+
+        let received = self.uart.recv_bytes(&mut sig, RECV_TIMEOUT_US);
+        if received == SIGNATURE_LEN {
             Some(sig)
         } else {
             None
         }
     }
-    
-    /// Request real-time data (fast, optimized for frequent calls)
-    pub fn get_realtime_data(&mut self) -> bool {
-        if !self.connected {
-            return false;
+
+    /// Send a command, wrapped in newserial framing in that mode
+    fn send_command(&mut self, command: &[u8]) {
+        if self.checksum_mode == ChecksumMode::Crc32 {
+            self.uart.send_bytes(&(command.len() as u16).to_be_bytes());
+            self.uart.send_bytes(command);
+            self.uart.send_bytes(&crc32(command).to_be_bytes());
+        } else {
+            self.uart.send_bytes(command);
         }
-        
-        // Send real-time data request
-        // self.uart.send_byte(MS_CMD_REALTIME);
-        
-        // Receive response
-        // MS1/MS2: typically 22-119 bytes depending on version
-        // MS3: can be larger
-        let received = // self.uart.recv_bytes(&mut self.realtime_buffer, TIMEOUT_CYCLES);
-        
-        if received > 0 {
-            self.realtime_size = received;
-            true
+    }
+
+    /// Receive a whole newserial reply into `rx_buffer`, blocking
+    /// Returns where its data lies in the buffer, if the reply checks out
+    fn recv_framed_reply(&mut self) -> Option<Range<usize>> {
+        self.rx_count = self.uart.recv_bytes(&mut self.rx_buffer[..NEWSERIAL_LEN_BYTES], RECV_TIMEOUT_US);
+        if self.rx_count != NEWSERIAL_LEN_BYTES {
+            return None;
+        }
+        let expected = self.expected_packet_len();
+        self.rx_count += self.uart.recv_bytes(&mut self.rx_buffer[NEWSERIAL_LEN_BYTES..expected], RECV_TIMEOUT_US);
+        if self.rx_count != expected {
+            return None;
+        }
+        self.reply_data(&self.rx_buffer[..expected])
+    }
+
+    /// Advance a realtime request without blocking
+    /// Sends the request on the first call, then collects whatever bytes have
+    /// arrived on each subsequent call. A packet is complete once the
+    /// configured realtime length has arrived (or, if unknown, once bytes stop
+    /// arriving for a poll); no reply at all for `POLL_TIMEOUT_POLLS` polls
+    /// fails the request.
+    pub fn poll_realtime(&mut self) -> PollState {
+        if !self.connected && !self.try_reconnect() {
+            return PollState::Failed;
+        }
+
+        if !self.request_pending {
+            self.send_command(&[MS_CMD_REALTIME]);
+            self.stats.requests = self.stats.requests.wrapping_add(1);
+            self.request_pending = true;
+            self.rx_count = 0;
+            self.idle_polls = 0;
+            return PollState::Pending;
+        }
+
+        // The newserial length prefix can change the expected length mid-packet
+        let before = self.rx_count;
+        while self.rx_count < self.expected_packet_len() && self.uart.has_data() {
+            match self.uart.recv_byte(0) {
+                Some(byte) => {
+                    self.rx_buffer[self.rx_count] = byte;
+                    self.rx_count += 1;
+                }
+                None => break,
+            }
+        }
+
+        let packet_done = self.rx_count == self.expected_packet_len();
+        if self.rx_count > before && !packet_done {
+            self.idle_polls = 0;
+            return PollState::Pending;
+        }
+
+        self.idle_polls += 1;
+        if self.rx_count > 0 {
+            self.request_pending = false;
+            if self.commit_rx() {
+                self.consecutive_failures = 0;
+                PollState::Complete
+            } else {
+                self.record_failure();
+                PollState::Failed
+            }
+        } else if self.idle_polls >= POLL_TIMEOUT_POLLS {
+            self.request_pending = false;
+            self.stats.timeouts = self.stats.timeouts.wrapping_add(1);
+            self.record_failure();
+            PollState::Failed
         } else {
-            false
+            PollState::Pending
+        }
+    }
+
+    /// Promote the received packet's data to the realtime buffer if it is valid
+    /// Keeps the last good packet if this one is empty or corrupt
+    fn commit_rx(&mut self) -> bool {
+        let len = self.rx_count;
+        if len == 0 {
+            self.stats.timeouts = self.stats.timeouts.wrapping_add(1);
+            return false;
+        }
+        // A newserial reply must be as long as its prefix says
+        if self.checksum_mode == ChecksumMode::Crc32 && len != self.expected_packet_len() {
+            self.stats.length_errors = self.stats.length_errors.wrapping_add(1);
+            return false;
+        }
+        let data = match self.reply_data(&self.rx_buffer[..len]) {
+            Some(data) => data,
+            None => {
+                self.stats.checksum_failures = self.stats.checksum_failures.wrapping_add(1);
+                return false;
+            }
+        };
+        // With a known block size a short packet is a framing error
+        if self.realtime_len != 0 && data.len() != self.realtime_len {
+            self.stats.length_errors = self.stats.length_errors.wrapping_add(1);
+            return false;
+        }
+
+        let size = data.len();
+        self.realtime_buffer[..size].copy_from_slice(&self.rx_buffer[data]);
+        self.realtime_size = size;
+        self.stats.packets_received = self.stats.packets_received.wrapping_add(1);
+        true
+    }
+
+    /// Check a complete reply's checksum and framing
+    /// Returns where the data lies within `reply`; None if the checksum
+    /// fails or a newserial reply carries an error code
+    fn reply_data(&self, reply: &[u8]) -> Option<Range<usize>> {
+        match self.checksum_mode {
+            ChecksumMode::None => Some(0..reply.len()),
+            ChecksumMode::Sum8 => {
+                let (&check, data) = reply.split_last()?;
+                let sum = data.iter().fold(0u8, |acc, &b| acc.wrapping_add(b));
+                (sum == check).then_some(0..data.len())
+            }
+            ChecksumMode::Crc32 => {
+                if reply.len() < NEWSERIAL_OVERHEAD {
+                    return None;
+                }
+                let crc_start = reply.len() - NEWSERIAL_CRC_BYTES;
+                let body = &reply[NEWSERIAL_LEN_BYTES..crc_start];
+                let check = &reply[crc_start..];
+                let expected = u32::from_be_bytes([check[0], check[1], check[2], check[3]]);
+                if crc32(body) != expected || body[0] >= NEWSERIAL_ERROR_CODE {
+                    return None;
+                }
+                Some(NEWSERIAL_LEN_BYTES + 1..crc_start)
+            }
         }
     }
     
-    /// Extract value from real-time data buffer
-    /// Offset and size depend on ECU firmware version
-    pub fn get_value_u8(&self, offset: usize) -> Option<u8> {
-        if offset < self.realtime_size {
-            Some(self.realtime_buffer[offset])
-        } else {
-            None
+    /// Read a raw field of any width, sign-extending signed types
+    /// Returns None if the whole field doesn't lie within the current packet
+    pub fn get_value(&self, offset: usize, data_type: DataType, endian: Endian) -> Option<i64> {
+        let size = data_type.size();
+        let end = offset.checked_add(size)?;
+        if end > self.realtime_size {
+            return None;
         }
+
+        let bytes = &self.realtime_buffer[offset..end];
+        let mut raw: u32 = 0;
+        for i in 0..size {
+            let byte = match endian {
+                Endian::Big => bytes[i],
+                Endian::Little => bytes[size - 1 - i],
+            };
+            raw = (raw << 8) | byte as u32;
+        }
+
+        Some(match data_type {
+            DataType::U08 | DataType::U16 | DataType::U32 => raw as i64,
+            DataType::S08 => raw as u8 as i8 as i64,
+            DataType::S16 => raw as u16 as i16 as i64,
+            DataType::S32 => raw as i32 as i64,
+        })
+    }
+
+    pub fn get_value_u8(&self, offset: usize) -> Option<u8> {
+        self.get_value(offset, DataType::U08, self.endian).map(|v| v as u8)
     }
     
     pub fn get_value_u16(&self, offset: usize) -> Option<u16> {
-        if offset + 1 < self.realtime_size {
-            let high = self.realtime_buffer[offset] as u16;
-            let low = self.realtime_buffer[offset + 1] as u16;
-            Some((high << 8) | low)
-        } else {
-            None
-        }
+        self.get_value(offset, DataType::U16, self.endian).map(|v| v as u16)
     }
     
     pub fn get_value_i16(&self, offset: usize) -> Option<i16> {
-        self.get_value_u16(offset).map(|v| v as i16)
+        self.get_value(offset, DataType::S16, self.endian).map(|v| v as i16)
+    }
+
+    pub fn get_value_u32(&self, offset: usize) -> Option<u32> {
+        self.get_value(offset, DataType::U32, self.endian).map(|v| v as u32)
     }
     
-    /// Common MegaSquirt data extraction helpers (MS2 format)
-    /// These offsets may vary by firmware - should be configurable
+    /// Replace the field layout used by the `get_*` helpers
+    /// Fields are read in the ECU's byte order (see `set_endian`)
+    pub fn set_offset_table(&mut self, table: OffsetTable) {
+        self.offsets = table.with_endian(self.endian);
+    }
+
+    /// Set the byte order of multi-byte fields for every extractor
+    pub fn set_endian(&mut self, endian: Endian) {
+        self.endian = endian;
+        self.offsets = self.offsets.with_endian(endian);
+    }
+
+    pub fn endian(&self) -> Endian {
+        self.endian
+    }
+
+    pub fn offset_table(&self) -> &OffsetTable {
+        &self.offsets
+    }
+
+    /// Read a field as described by its definition and apply its scale
+    pub fn get_field(&self, field: &FieldDef) -> Option<f32> {
+        let data_type = DataType::from_size(field.size, field.signed)?;
+        let raw = self.get_value(field.offset, data_type, field.endian)? as f32;
+        Some(raw * field.scale + field.translate)
+    }
+
+    /// Read an INI output channel: raw value at its offset with its type's
+    /// width and signedness, then `raw * scale + translate`
+    pub fn get_channel(&self, channel: &OutputChannel) -> Option<f32> {
+        let mut field = FieldDef::from_channel(channel);
+        field.endian = self.endian;
+        self.get_field(&field)
+    }
+
+    /// Read the first of `names` defined in the parsed output channels
+    pub fn get_channel_by_name(&self, channels: &OutputChannels, names: &[&str]) -> Option<f32> {
+        names
+            .iter()
+            .find_map(|name| channels.get_by_name(name))
+            .and_then(|channel| self.get_channel(channel))
+    }
+    
+    /// Common MegaSquirt data extraction helpers
+    /// Offsets and scaling come from the active `OffsetTable`
     
-    pub fn get_rpm(&self) -> Option<u16> {
-        // RPM is typically at offset 6-7 (MS2)
-        self.get_value_u16(6)
+    pub fn get_rpm(&self) -> Option<f32> {
+        self.get_field(&self.offsets.rpm)
     }
     
-    pub fn get_map(&self) -> Option<u16> {
-        // MAP is typically at offset 4-5 (MS2)
-        self.get_value_u16(4)
+    pub fn get_map(&self) -> Option<f32> {
+        self.get_field(&self.offsets.map)
     }
     
-    pub fn get_coolant_temp(&self) -> Option<i16> {
-        // Coolant temp typically at offset 8-9 (MS2)
-        self.get_value_i16(8)
+    pub fn get_coolant_temp(&self) -> Option<f32> {
+        self.get_field(&self.offsets.coolant_temp)
     }
     
-    pub fn get_tps(&self) -> Option<u16> {
-        // TPS typically at offset 14-15 (MS2)
-        self.get_value_u16(14)
+    pub fn get_tps(&self) -> Option<f32> {
+        self.get_field(&self.offsets.tps)
     }
     
-    pub fn get_afr(&self) -> Option<u16> {
-        // AFR/Lambda typically at offset 16-17 (MS2)
-        self.get_value_u16(16)
+    pub fn get_afr(&self) -> Option<f32> {
+        self.get_field(&self.offsets.afr)
     }
     
-    pub fn get_battery_voltage(&self) -> Option<u16> {
-        // Battery voltage typically at offset 18-19 (MS2)
-        self.get_value_u16(18)
+    pub fn get_battery_voltage(&self) -> Option<f32> {
+        self.get_field(&self.offsets.battery_voltage)
     }
     
+    /// Drain the UART FIFO into the software ring
+    /// Call between expensive steps (e.g. each gauge render) so bytes
+    /// arriving mid-frame are not lost
+    pub fn pump_rx(&mut self) {
+        self.uart.pump_rx();
+    }
+
     /// Check if connected
     pub fn is_connected(&self) -> bool {
         self.connected
@@ -155,12 +813,119 @@ impl MegaSquirt {
     }
 }
 
+/// CRC32 (IEEE 802.3, reflected) as used by the MegaSquirt serial protocol
+pub fn crc32(data: &[u8]) -> u32 {
+    let mut crc: u32 = 0xFFFFFFFF;
+    for &byte in data {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            let mask = (crc & 1).wrapping_neg();
+            crc = (crc >> 1) ^ (0xEDB88320 & mask);
+        }
+    }
+    !crc
+}
+
 impl Default for MegaSquirt {
     fn default() -> Self {
         Self::new()
     }
 }
 
+/// Latest value of every INI output channel, looked up by channel name
+/// Lets gauges show any channel the ECU defines (EGT, knock, trims...)
+/// without a dedicated `ECUData` field
+pub struct ChannelMap {
+    names: [[u8; 32]; MAX_OUTPUT_CHANNELS],
+    values: [f32; MAX_OUTPUT_CHANNELS],
+    count: usize,
+}
+
+impl ChannelMap {
+    pub const fn new() -> Self {
+        ChannelMap {
+            names: [[0; 32]; MAX_OUTPUT_CHANNELS],
+            values: [0.0; MAX_OUTPUT_CHANNELS],
+            count: 0,
+        }
+    }
+
+    fn index_of(&self, name: &str) -> Option<usize> {
+        let name = name.as_bytes();
+        (0..self.count).find(|&i| {
+            let stored = &self.names[i];
+            let len = stored.iter().position(|&b| b == 0).unwrap_or(stored.len());
+            &stored[..len] == name
+        })
+    }
+
+    /// Store a channel value, adding the channel if it's new
+    /// Returns false when the map is full or the name doesn't fit
+    pub fn set(&mut self, name: &str, value: f32) -> bool {
+        if let Some(i) = self.index_of(name) {
+            self.values[i] = value;
+            return true;
+        }
+        let bytes = name.as_bytes();
+        if bytes.is_empty() || bytes.len() > 32 || self.count >= MAX_OUTPUT_CHANNELS {
+            return false;
+        }
+        self.names[self.count] = [0; 32];
+        self.names[self.count][..bytes.len()].copy_from_slice(bytes);
+        self.values[self.count] = value;
+        self.count += 1;
+        true
+    }
+
+    /// Latest value of a channel; None if it was never read
+    pub fn get(&self, name: &str) -> Option<f32> {
+        self.index_of(name).map(|i| self.values[i])
+    }
+
+    /// Read every defined output channel from the latest realtime packet
+    /// Channels outside the packet keep their previous value
+    pub fn update(&mut self, ms: &MegaSquirt, channels: &OutputChannels) {
+        for channel in (0..channels.len()).filter_map(|i| channels.get(i)) {
+            if let Some(value) = ms.get_channel(channel) {
+                self.set(channel.name_str(), value);
+            }
+        }
+    }
+
+    /// Store every channel of `other`, overwriting same-named ones
+    pub fn merge(&mut self, other: &ChannelMap) {
+        for i in 0..other.count {
+            let name = &other.names[i];
+            let len = name.iter().position(|&b| b == 0).unwrap_or(name.len());
+            if let Ok(name) = core::str::from_utf8(&name[..len]) {
+                self.set(name, other.values[i]);
+            }
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.count
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    pub fn clear(&mut self) {
+        self.count = 0;
+    }
+}
+
+impl Default for ChannelMap {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Plausible range for a live barometer reading (about 5500 m altitude up to a strong high)
+const BARO_MIN_KPA: f32 = 50.0;
+const BARO_MAX_KPA: f32 = 110.0;
+
 /// Generic ECU data structure for common values
 pub struct ECUData {
     pub rpm: f32,
@@ -168,18 +933,31 @@ pub struct ECUData {
     pub tps: f32,
     pub coolant_temp: f32,
     pub intake_temp: f32,
+    /// Mixture reading, in the representation given by `afr_unit`
     pub afr: f32,
+    /// Whether `afr` holds an AFR or a lambda value
+    pub afr_unit: MixtureUnit,
     pub battery_voltage: f32,
     pub vehicle_speed: f32,
     pub fuel_pressure: f32,
     pub oil_pressure: f32,
+    /// Gauge (MAP minus atmospheric) pressure in kPa
     pub boost: f32,
     pub ignition_advance: f32,
     pub injector_duty: f32,
+    /// Configured atmospheric pressure subtracted from MAP to get boost; lower at altitude
+    pub atmospheric_kpa: f32,
+    /// Stoichiometric AFR of the fuel, for converting between AFR and lambda
+    pub stoich: f32,
+    /// Live barometric pressure from the ECU's baro channel, when it has one
+    /// Preferred over `atmospheric_kpa` for the boost calculation
+    pub baro_kpa: Option<f32>,
+    /// Every output channel by name, filled by `update_from_channels`
+    pub channels: ChannelMap,
 }
 
 impl ECUData {
-    pub fn new() -> Self {
+    pub const fn new() -> Self {
         ECUData {
             rpm: 0.0,
             map: 0.0,
@@ -187,6 +965,7 @@ impl ECUData {
             coolant_temp: 0.0,
             intake_temp: 0.0,
             afr: 0.0,
+            afr_unit: MixtureUnit::Afr,
             battery_voltage: 0.0,
             vehicle_speed: 0.0,
             fuel_pressure: 0.0,
@@ -194,40 +973,96 @@ impl ECUData {
             boost: 0.0,
             ignition_advance: 0.0,
             injector_duty: 0.0,
+            atmospheric_kpa: STANDARD_ATMOSPHERE_KPA,
+            stoich: STOICH_GASOLINE,
+            baro_kpa: None,
+            channels: ChannelMap::new(),
         }
     }
     
     /// Update from MegaSquirt real-time data
     pub fn update_from_ms(&mut self, ms: &MegaSquirt) {
-        // Extract and convert values
-        // Note: Scaling factors depend on firmware version
+        // Extract values already scaled by the active offset table
         
         if let Some(rpm) = ms.get_rpm() {
-            self.rpm = rpm as f32;
+            self.rpm = rpm;
         }
         
         if let Some(map) = ms.get_map() {
-            self.map = (map as f32) / 10.0; // Typically in 0.1 kPa units
+            self.map = map;
         }
         
         if let Some(temp) = ms.get_coolant_temp() {
-            self.coolant_temp = (temp as f32) / 10.0; // Typically in 0.1°F units
+            self.coolant_temp = temp;
         }
         
         if let Some(tps) = ms.get_tps() {
-            self.tps = (tps as f32) / 10.0; // Typically in 0.1% units
+            self.tps = tps;
         }
         
         if let Some(afr) = ms.get_afr() {
-            self.afr = (afr as f32) / 10.0; // Typically in 0.1 AFR units
+            self.afr = afr;
         }
         
         if let Some(voltage) = ms.get_battery_voltage() {
-            self.battery_voltage = (voltage as f32) / 10.0; // Typically in 0.1V units
+            self.battery_voltage = voltage;
         }
         
-        // Calculate boost from MAP (assuming 1 bar = 14.7 PSI at sea level)
-        self.boost = (self.map - 101.325) * 0.145038; // kPa to PSI, subtract atmospheric
+        // Boost stays in kPa; gauges convert to their configured display unit
+        self.boost = self.map - self.boost_reference_kpa();
+    }
+
+    /// Update every field the INI defines a channel for, using each channel's
+    /// own type, scale and translate. Fields without a channel keep their value
+    /// Every channel is also stored by name in `channels`
+    pub fn update_from_channels(&mut self, ms: &MegaSquirt, channels: &OutputChannels) {
+        self.channels.update(ms, channels);
+
+        let fields: [(&mut f32, &[&str]); 11] = [
+            (&mut self.rpm, &["rpm"]),
+            (&mut self.map, &["map"]),
+            (&mut self.tps, &["tps", "throttle"]),
+            (&mut self.coolant_temp, &["coolant", "clt"]),
+            (&mut self.intake_temp, &["mat", "iat"]),
+            (&mut self.battery_voltage, &["batteryVoltage", "battery"]),
+            (&mut self.vehicle_speed, &["vss1", "vss", "vehicleSpeed"]),
+            (&mut self.fuel_pressure, &["fuelPressure", "fuelpress1"]),
+            (&mut self.oil_pressure, &["oilPressure", "oilpress"]),
+            (&mut self.ignition_advance, &["advance", "ignitionAdvance"]),
+            (&mut self.injector_duty, &["dutyCycle1", "dutyCycle"]),
+        ];
+
+        for (field, names) in fields {
+            if let Some(value) = ms.get_channel_by_name(channels, names) {
+                *field = value;
+            }
+        }
+
+        // Firmware reports either AFR or lambda; remember which we got
+        if let Some(afr) = ms.get_channel_by_name(channels, &["afr1", "afr"]) {
+            self.afr = afr;
+            self.afr_unit = MixtureUnit::Afr;
+        } else if let Some(lambda) = ms.get_channel_by_name(channels, &["lambda1", "lambda"]) {
+            self.afr = lambda;
+            self.afr_unit = MixtureUnit::Lambda;
+        }
+
+        // A reading outside any real atmosphere means a faulty or unfitted sensor
+        self.baro_kpa = ms
+            .get_channel_by_name(channels, &["baro", "barometer"])
+            .filter(|kpa| (BARO_MIN_KPA..=BARO_MAX_KPA).contains(kpa));
+
+        self.boost = self.map - self.boost_reference_kpa();
+    }
+
+    /// Pressure boost is measured against: live baro if available, else the configured value
+    pub fn boost_reference_kpa(&self) -> f32 {
+        self.baro_kpa.unwrap_or(self.atmospheric_kpa)
+    }
+
+    /// Mixture reading in the requested representation
+    pub fn afr_as(&self, unit: MixtureUnit, stoich: f32) -> f32 {
+        convert_mixture(self.afr, self.afr_unit, unit, stoich)
     }
 }
 
@@ -237,4 +1072,163 @@ impl Default for ECUData {
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ts_ini_parser::parse_output_channel_line;
+
+    /// MegaSquirt holding `data` as its latest realtime packet
+    fn with_packet(data: &[u8]) -> MegaSquirt {
+        let mut ms = MegaSquirt::new();
+        ms.realtime_buffer[..data.len()].copy_from_slice(data);
+        ms.realtime_size = data.len();
+        ms
+    }
+
+    #[test]
+    fn rpm_decodes_in_both_byte_orders() {
+        // MS2 layout: RPM is the u16 at offset 6
+        let mut packet = [0u8; 20];
+        packet[6] = 0x0B;
+        packet[7] = 0xB8;
+
+        let mut ms = with_packet(&packet);
+        assert_eq!(ms.endian(), Endian::Big);
+        assert_eq!(ms.get_rpm(), Some(3000.0));
+        assert_eq!(ms.get_value_u16(6), Some(3000));
+
+        ms.set_endian(Endian::Little);
+        assert_eq!(ms.get_rpm(), Some(47115.0));
+        assert_eq!(ms.get_value_u16(6), Some(0xB80B));
+
+        // A little-endian ECU sends the same RPM low byte first
+        packet[6] = 0xB8;
+        packet[7] = 0x0B;
+        let mut ms = with_packet(&packet);
+        ms.set_endian(Endian::Little);
+        assert_eq!(ms.get_rpm(), Some(3000.0));
+    }
+
+    #[test]
+    fn boost_uses_configured_or_live_baro() {
+        // MAP 150.0 kPa (0.1 kPa units at offset 4), baro 90.0 kPa at offset 20
+        let mut packet = [0u8; 22];
+        packet[4..6].copy_from_slice(&1500u16.to_be_bytes());
+        packet[20..22].copy_from_slice(&900u16.to_be_bytes());
+        let ms = with_packet(&packet);
+
+        let mut data = ECUData::new();
+        data.update_from_ms(&ms);
+        assert!((data.boost - (150.0 - STANDARD_ATMOSPHERE_KPA)).abs() < 1e-3);
+
+        data.atmospheric_kpa = 90.0;
+        data.update_from_ms(&ms);
+        assert!((data.boost - 60.0).abs() < 1e-3);
+
+        // A live baro channel wins over the configured value
+        let mut channels = OutputChannels::new();
+        channels.add(parse_output_channel_line("map = scalar, U16, 4, \"kPa\", 0.1, 0").unwrap());
+        channels.add(parse_output_channel_line("baro = scalar, U16, 20, \"kPa\", 0.1, 0").unwrap());
+        let mut data = ECUData::new();
+        data.update_from_channels(&ms, &channels);
+        assert_eq!(data.baro_kpa, Some(90.0));
+        assert!((data.boost - 60.0).abs() < 1e-3);
+
+        // An implausible baro reading falls back to the configured value
+        packet[20..22].copy_from_slice(&0u16.to_be_bytes());
+        data.update_from_channels(&with_packet(&packet), &channels);
+        assert_eq!(data.baro_kpa, None);
+        assert!((data.boost - (150.0 - STANDARD_ATMOSPHERE_KPA)).abs() < 1e-3);
+    }
+
+    #[test]
+    fn offset_table_keeps_endian_after_replacement() {
+        let mut packet = [0u8; 20];
+        packet[6] = 0xB8;
+        packet[7] = 0x0B;
+        let mut ms = with_packet(&packet);
+        ms.set_endian(Endian::Little);
+        ms.set_offset_table(OffsetTable::ms2());
+        assert_eq!(ms.get_rpm(), Some(3000.0));
+    }
+
+    #[test]
+    fn ini_block_size_sets_the_packet_length() {
+        let mut parser = IniParser::new();
+        parser.feed("[OutputChannels]\nochBlockSize = 212\nrpm = scalar, U16, 6, \"RPM\", 1.000, 0.0\n");
+        let mut ms = MegaSquirt::new();
+        ms.configure_from_ini(&parser);
+        assert_eq!(ms.realtime_len(), 212);
+        assert_eq!(ms.expected_packet_len(), 212);
+
+        ms.checksum_mode = ChecksumMode::Crc32;
+        assert_eq!(ms.expected_packet_len(), 219);
+    }
 
+    /// A newserial reply: length, response code, data, CRC32 of code + data
+    fn framed(code: u8, data: &[u8]) -> ([u8; 64], usize) {
+        let mut reply = [0u8; 64];
+        let body_len = data.len() + 1;
+        reply[..2].copy_from_slice(&(body_len as u16).to_be_bytes());
+        reply[2] = code;
+        reply[3..3 + data.len()].copy_from_slice(data);
+        let crc = crc32(&reply[2..2 + body_len]);
+        reply[2 + body_len..6 + body_len].copy_from_slice(&crc.to_be_bytes());
+        (reply, body_len + 6)
+    }
+
+    fn receive(ms: &mut MegaSquirt, reply: &[u8]) -> bool {
+        ms.rx_buffer[..reply.len()].copy_from_slice(reply);
+        ms.rx_count = reply.len();
+        ms.commit_rx()
+    }
+
+    #[test]
+    fn crc32_matches_the_standard_check_value() {
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+    }
+
+    #[test]
+    fn newserial_reply_is_checked_and_unwrapped() {
+        let mut ms = MegaSquirt::new();
+        ms.checksum_mode = ChecksumMode::Crc32;
+        let data = [0x0B, 0xB8, 0x00, 0x2A];
+        let (reply, len) = framed(0x01, &data);
+
+        assert!(receive(&mut ms, &reply[..len]));
+        assert_eq!(ms.get_raw_buffer(), &data);
+
+        // Corrupt data fails the CRC and keeps the last good packet
+        let mut corrupt = reply;
+        corrupt[4] ^= 0xFF;
+        assert!(!receive(&mut ms, &corrupt[..len]));
+        assert_eq!(ms.link_stats().checksum_failures, 1);
+        assert_eq!(ms.get_raw_buffer(), &data);
+
+        // Truncated reply is a length error
+        assert!(!receive(&mut ms, &reply[..len - 1]));
+        assert_eq!(ms.link_stats().length_errors, 1);
+
+        // ECU-side error codes are rejected even with a good CRC
+        let (error, len) = framed(0x83, &[]);
+        assert!(!receive(&mut ms, &error[..len]));
+    }
+
+    #[test]
+    fn sum8_trailer_is_stripped_from_the_packet() {
+        let mut ms = MegaSquirt::new();
+        ms.checksum_mode = ChecksumMode::Sum8;
+        assert!(receive(&mut ms, &[1, 2, 3, 6]));
+        assert_eq!(ms.get_raw_buffer(), &[1, 2, 3]);
+        assert!(!receive(&mut ms, &[1, 2, 3, 7]));
+    }
+
+    #[test]
+    fn checksum_mode_parses_config_names() {
+        assert_eq!(ChecksumMode::from_str("newserial"), Some(ChecksumMode::Crc32));
+        assert_eq!(ChecksumMode::from_str(" CRC32 "), Some(ChecksumMode::Crc32));
+        assert_eq!(ChecksumMode::from_str("sum8"), Some(ChecksumMode::Sum8));
+        assert_eq!(ChecksumMode::from_str("none"), Some(ChecksumMode::None));
+        assert_eq!(ChecksumMode::from_str("md5"), None);
+    }
+}
@@ -12,6 +12,14 @@ const MAILBOX_WRITE: u32 = MAILBOX_BASE + 0x20;
 const MAILBOX_FULL: u32 = 0x80000000;
 const MAILBOX_EMPTY: u32 = 0x40000000;
 
+/// Channel for the ARM-to-GPU property tag interface
+pub const MAILBOX_CHANNEL_PROPERTY: u32 = 8;
+const PROPERTY_REQUEST_SUCCESS: u32 = 0x80000000;
+const PROPERTY_TAG_END: u32 = 0;
+
+/// Word count of a `PropertyBuffer`; enough for the framebuffer allocation
+const PROPERTY_BUFFER_WORDS: usize = 36;
+
 pub fn mmio_write(reg: u32, data: u32) {
     unsafe {
         ptr::write_volatile(reg as *mut u32, data);
@@ -24,9 +32,20 @@ pub fn mmio_read(reg: u32) -> u32 {
     }
 }
 
+/// Send a property buffer to the GPU and wait for its reply
+/// `buffer[0]` is the size in bytes and `buffer[1]` the request code; returns
+/// false without sending if the buffer is too short, its size word claims
+/// more than the slice holds, or it isn't 16-byte aligned (the low address
+/// bits carry the channel)
 pub fn mailbox_call(buffer: &mut [u32], channel: u32) -> bool {
+    if buffer.len() < 2 || buffer[0] as usize > buffer.len() * 4 {
+        return false;
+    }
     let addr = buffer.as_ptr() as u32;
-    let r = (addr & !0xF) | (channel & 0xF);
+    if addr & 0xF != 0 {
+        return false;
+    }
+    let r = addr | (channel & 0xF);
     
     // Wait for mailbox to be ready
     while (mmio_read(MAILBOX_STATUS) & MAILBOX_FULL) != 0 {}
@@ -38,7 +57,65 @@ pub fn mailbox_call(buffer: &mut [u32], channel: u32) -> bool {
     loop {
         while (mmio_read(MAILBOX_STATUS) & MAILBOX_EMPTY) != 0 {}
         if r == mmio_read(MAILBOX_READ) {
-            return buffer[1] == 0x80000000;
+            // The GPU wrote the response behind the compiler's back
+            return unsafe { ptr::read_volatile(&buffer[1]) } == PROPERTY_REQUEST_SUCCESS;
+        }
+    }
+}
+
+/// Builder for a property-interface request: tags are appended in order and
+/// the size word and end tag are filled in when the request is sent
+#[repr(C, align(16))]
+pub struct PropertyBuffer {
+    words: [u32; PROPERTY_BUFFER_WORDS],
+    len: usize,
+}
+
+impl PropertyBuffer {
+    pub fn new() -> Self {
+        // Words 0 and 1 are the total size and request code
+        PropertyBuffer { words: [0; PROPERTY_BUFFER_WORDS], len: 2 }
+    }
+
+    /// Append a tag with its request values (zeros reserve room for the
+    /// response); returns the index of its first value word for reading the
+    /// response, or None if the tag and end marker don't fit
+    pub fn push_tag(&mut self, tag: u32, values: &[u32]) -> Option<usize> {
+        let start = self.len + 3;
+        let end = start + values.len();
+        // One word stays free for the end tag
+        if end >= PROPERTY_BUFFER_WORDS {
+            return None;
+        }
+        self.words[self.len] = tag;
+        self.words[self.len + 1] = (values.len() * 4) as u32;
+        self.words[self.len + 2] = 0;
+        self.words[start..end].copy_from_slice(values);
+        self.len = end;
+        Some(start)
+    }
+
+    /// Terminate the request, send it and wait for the reply
+    /// Returns false if the GPU did not report success
+    pub fn call(&mut self) -> bool {
+        self.words[self.len] = PROPERTY_TAG_END;
+        self.words[0] = ((self.len + 1) * 4) as u32;
+        self.words[1] = 0;
+        let len = self.len + 1;
+        mailbox_call(&mut self.words[..len], MAILBOX_CHANNEL_PROPERTY)
+    }
+
+    /// Response word at an index returned by `push_tag`
+    pub fn value(&self, index: usize) -> u32 {
+        if index >= self.len {
+            return 0;
         }
+        unsafe { ptr::read_volatile(&self.words[index]) }
+    }
+}
+
+impl Default for PropertyBuffer {
+    fn default() -> Self {
+        Self::new()
     }
 }
@@ -4,10 +4,79 @@
 
 use core::f32::consts::PI;
 use crate::math::sin;
+use crate::units::{afr_to_lambda, convert_mixture, MixtureUnit, STOICH_GASOLINE};
 
 pub struct MockECU {
     pub time_ms: u32,
     pub frame_count: u32,
+    /// Representation `air_fuel_ratio` is reported in, to mimic either ECU type
+    pub afr_output: MixtureUnit,
+    /// Stoich used when reporting lambda
+    pub stoich: f32,
+    scenario: MockScenario,
+    overrides: [Option<f32>; MOCK_CHANNEL_COUNT],
+    /// Seed for the noise pattern; the same seed replays the same noise
+    pub seed: u32,
+    /// Relative jitter added to every channel (0 = none, 0.05 = +/-5%)
+    pub noise: f32,
+}
+
+/// Selectable driving pattern
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum MockScenario {
+    /// Startup, idle, acceleration and high load on an 18 second loop
+    Cycle,
+    /// Warm idle
+    Idle,
+    /// Repeated full-throttle pulls to 7000 RPM
+    WideOpenThrottle,
+    /// Idle with coolant rising into the danger zone
+    Overheat,
+    /// Cruise with the mixture drifting lean
+    Lean,
+    /// Every channel held at its default value
+    Flatline,
+}
+
+pub const MOCK_CHANNEL_COUNT: usize = 13;
+
+/// Channel of `MockECUData`, used to pin values with overrides
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum MockChannel {
+    Rpm,
+    MapPressure,
+    CoolantTemp,
+    IntakeTemp,
+    AirFuelRatio,
+    OilPressure,
+    FuelPressure,
+    BatteryVoltage,
+    ThrottlePosition,
+    BoostPressure,
+    IgnitionAdvance,
+    InjectorDuty,
+    VehicleSpeed,
+}
+
+impl MockChannel {
+    pub fn from_index(index: usize) -> Option<Self> {
+        const ALL: [MockChannel; MOCK_CHANNEL_COUNT] = [
+            MockChannel::Rpm,
+            MockChannel::MapPressure,
+            MockChannel::CoolantTemp,
+            MockChannel::IntakeTemp,
+            MockChannel::AirFuelRatio,
+            MockChannel::OilPressure,
+            MockChannel::FuelPressure,
+            MockChannel::BatteryVoltage,
+            MockChannel::ThrottlePosition,
+            MockChannel::BoostPressure,
+            MockChannel::IgnitionAdvance,
+            MockChannel::InjectorDuty,
+            MockChannel::VehicleSpeed,
+        ];
+        ALL.get(index).copied()
+    }
 }
 
 #[derive(Clone, Copy)]
@@ -45,6 +114,43 @@ impl MockECUData {
             vehicle_speed: 0.0,
         }
     }
+
+    pub fn get(&self, channel: MockChannel) -> f32 {
+        match channel {
+            MockChannel::Rpm => self.rpm,
+            MockChannel::MapPressure => self.map_pressure,
+            MockChannel::CoolantTemp => self.coolant_temp,
+            MockChannel::IntakeTemp => self.intake_temp,
+            MockChannel::AirFuelRatio => self.air_fuel_ratio,
+            MockChannel::OilPressure => self.oil_pressure,
+            MockChannel::FuelPressure => self.fuel_pressure,
+            MockChannel::BatteryVoltage => self.battery_voltage,
+            MockChannel::ThrottlePosition => self.throttle_position,
+            MockChannel::BoostPressure => self.boost_pressure,
+            MockChannel::IgnitionAdvance => self.ignition_advance,
+            MockChannel::InjectorDuty => self.injector_duty,
+            MockChannel::VehicleSpeed => self.vehicle_speed,
+        }
+    }
+
+    pub fn set(&mut self, channel: MockChannel, value: f32) {
+        let field = match channel {
+            MockChannel::Rpm => &mut self.rpm,
+            MockChannel::MapPressure => &mut self.map_pressure,
+            MockChannel::CoolantTemp => &mut self.coolant_temp,
+            MockChannel::IntakeTemp => &mut self.intake_temp,
+            MockChannel::AirFuelRatio => &mut self.air_fuel_ratio,
+            MockChannel::OilPressure => &mut self.oil_pressure,
+            MockChannel::FuelPressure => &mut self.fuel_pressure,
+            MockChannel::BatteryVoltage => &mut self.battery_voltage,
+            MockChannel::ThrottlePosition => &mut self.throttle_position,
+            MockChannel::BoostPressure => &mut self.boost_pressure,
+            MockChannel::IgnitionAdvance => &mut self.ignition_advance,
+            MockChannel::InjectorDuty => &mut self.injector_duty,
+            MockChannel::VehicleSpeed => &mut self.vehicle_speed,
+        };
+        *field = value;
+    }
 }
 
 impl MockECU {
@@ -52,171 +158,375 @@ impl MockECU {
         MockECU {
             time_ms: 0,
             frame_count: 0,
+            afr_output: MixtureUnit::Afr,
+            stoich: STOICH_GASOLINE,
+            scenario: MockScenario::Cycle,
+            overrides: [None; MOCK_CHANNEL_COUNT],
+            seed: 0,
+            noise: 0.0,
         }
     }
 
-    /// Update mock data with realistic patterns
-    /// Simulates engine startup, idle, acceleration, and load scenarios
+    /// Switch scenario and restart it from t = 0
+    pub fn set_scenario(&mut self, scenario: MockScenario) {
+        self.scenario = scenario;
+        self.reset();
+    }
+
+    pub fn scenario(&self) -> MockScenario {
+        self.scenario
+    }
+
+    /// Restart the current scenario; overrides are kept
+    pub fn reset(&mut self) {
+        self.time_ms = 0;
+        self.frame_count = 0;
+    }
+
+    /// Pin one channel to a fixed value (in output units) regardless of scenario
+    pub fn set_value_override(&mut self, channel: MockChannel, value: f32) {
+        self.overrides[channel as usize] = Some(value);
+    }
+
+    pub fn clear_value_override(&mut self, channel: MockChannel) {
+        self.overrides[channel as usize] = None;
+    }
+
+    pub fn clear_overrides(&mut self) {
+        self.overrides = [None; MOCK_CHANNEL_COUNT];
+    }
+
+    /// Advance time and return the scenario's data at the new time
     pub fn update(&mut self, delta_ms: u32) -> MockECUData {
         self.time_ms += delta_ms;
         self.frame_count += 1;
+        self.get_current()
+    }
 
+    /// Get current mock data without advancing time
+    /// The same time, scenario and seed always give the same data
+    pub fn get_current(&self) -> MockECUData {
         let t = (self.time_ms as f32) / 1000.0; // Convert to seconds
-        let mut data = MockECUData::new();
-
-        // Scenario: Simulate engine startup -> idle -> rev cycle
-        // Phase 1 (0-3s): Engine starts, RPM rises
-        // Phase 2 (3-8s): Idle at ~1000 RPM
-        // Phase 3 (8-13s): Acceleration to 5000 RPM
-        // Phase 4 (13-18s): High load at 6000 RPM
-        // Then repeat
-        let cycle_time = t % 18.0;
-
-        // RPM pattern: startup -> idle -> accel -> cruise -> repeat
-        data.rpm = if cycle_time < 3.0 {
-            // Startup phase: 0 -> 1200 RPM
-            (cycle_time / 3.0) * 1200.0 + sin(cycle_time * 3.0) * 100.0
-        } else if cycle_time < 8.0 {
-            // Idle phase: ~1000 RPM with small oscillations
-            1000.0 + sin((cycle_time - 3.0) * 2.0) * 50.0
-        } else if cycle_time < 13.0 {
-            // Acceleration: 1000 -> 5000 RPM
-            let accel_phase = (cycle_time - 8.0) / 5.0;
-            1000.0 + accel_phase * 4000.0 + sin(accel_phase * 4.0 * PI) * 200.0
-        } else {
-            // Cruise at 6000 RPM
-            6000.0 + sin((cycle_time - 13.0) * 1.5) * 150.0
+        let mut data = match self.scenario {
+            MockScenario::Cycle => cycle_data(t),
+            MockScenario::Idle => idle_data(t),
+            MockScenario::WideOpenThrottle => wot_data(t),
+            MockScenario::Overheat => overheat_data(t),
+            MockScenario::Lean => lean_data(t),
+            MockScenario::Flatline => MockECUData::new(),
         };
 
-        // MAP pressure (manifold absolute pressure) varies with load
-        let load_factor = (data.rpm / 6500.0).min(1.0);
-        data.map_pressure = 20.0 + load_factor * 80.0 + sin(t * 0.5) * 5.0;
+        if self.noise > 0.0 {
+            self.apply_noise(&mut data);
+        }
+        clamp_data(&mut data);
+        self.apply_afr_output(&mut data);
 
-        // Coolant temperature: gradual warmup from 70°F to 190°F
-        let warmup = if t < 20.0 { t / 20.0 } else { 1.0 };
-        data.coolant_temp = 70.0 + warmup * 120.0 + sin(t * 0.3) * 3.0;
+        for (i, value) in self.overrides.iter().enumerate() {
+            if let (Some(value), Some(channel)) = (value, MockChannel::from_index(i)) {
+                data.set(channel, *value);
+            }
+        }
+        data
+    }
+
+    /// Convert the simulated AFR to the configured output representation
+    /// The patterns are gasoline AFRs, so go through lambda to suit any fuel
+    fn apply_afr_output(&self, data: &mut MockECUData) {
+        let lambda = afr_to_lambda(data.air_fuel_ratio, STOICH_GASOLINE);
+        data.air_fuel_ratio = convert_mixture(lambda, MixtureUnit::Lambda, self.afr_output, self.stoich);
+    }
+
+    /// Add +/- `noise` relative jitter to every channel
+    /// Derived from seed, time and channel only, so a run is reproducible
+    fn apply_noise(&self, data: &mut MockECUData) {
+        for i in 0..MOCK_CHANNEL_COUNT {
+            if let Some(channel) = MockChannel::from_index(i) {
+                let r = noise_hash(self.seed, self.time_ms, i as u32);
+                let value = data.get(channel);
+                data.set(channel, value * (1.0 + self.noise * r));
+            }
+        }
+    }
+}
 
-        // Intake temperature: follows coolant with offset
-        data.intake_temp = data.coolant_temp - 5.0 + sin(t * 0.7) * 2.0;
+/// Original startup -> idle -> accel -> cruise loop
+fn cycle_data(t: f32) -> MockECUData {
+    let mut data = MockECUData::new();
 
-        // Air/fuel ratio: lean at cruise, rich under load
-        let afr_base = 14.7 + (1.0 - load_factor) * 1.0; // Leaner at higher load
-        data.air_fuel_ratio = afr_base + sin(t * 1.2) * 0.3;
+    // Scenario: Simulate engine startup -> idle -> rev cycle
+    // Phase 1 (0-3s): Engine starts, RPM rises
+    // Phase 2 (3-8s): Idle at ~1000 RPM
+    // Phase 3 (8-13s): Acceleration to 5000 RPM
+    // Phase 4 (13-18s): High load at 6000 RPM
+    // Then repeat
+    let cycle_time = t % 18.0;
 
-        // Oil pressure: increases with RPM
-        let oil_rpm_factor = (data.rpm / 7000.0).min(1.0);
-        data.oil_pressure = 20.0 + oil_rpm_factor * 50.0 + sin(t * 0.4) * 2.0;
+    // RPM pattern: startup -> idle -> accel -> cruise -> repeat
+    data.rpm = if cycle_time < 3.0 {
+        // Startup phase: 0 -> 1200 RPM
+        (cycle_time / 3.0) * 1200.0 + sin(cycle_time * 3.0) * 100.0
+    } else if cycle_time < 8.0 {
+        // Idle phase: ~1000 RPM with small oscillations
+        1000.0 + sin((cycle_time - 3.0) * 2.0) * 50.0
+    } else if cycle_time < 13.0 {
+        // Acceleration: 1000 -> 5000 RPM
+        let accel_phase = (cycle_time - 8.0) / 5.0;
+        1000.0 + accel_phase * 4000.0 + sin(accel_phase * 4.0 * PI) * 200.0
+    } else {
+        // Cruise at 6000 RPM
+        6000.0 + sin((cycle_time - 13.0) * 1.5) * 150.0
+    };
 
-        // Fuel pressure: varies slightly with load
-        data.fuel_pressure = 40.0 + load_factor * 10.0 + sin(t * 1.5) * 1.0;
+    // MAP pressure (manifold absolute pressure) varies with load
+    let load_factor = (data.rpm / 6500.0).min(1.0);
+    data.map_pressure = 20.0 + load_factor * 80.0 + sin(t * 0.5) * 5.0;
 
-        // Battery voltage: drops under heavy load, recovers at idle
-        data.battery_voltage = 13.5 - load_factor * 0.8 + sin(t * 0.2) * 0.1;
+    // Coolant temperature: gradual warmup from 70°F to 190°F
+    let warmup = if t < 20.0 { t / 20.0 } else { 1.0 };
+    data.coolant_temp = 70.0 + warmup * 120.0 + sin(t * 0.3) * 3.0;
 
-        // Throttle position: cycles through 0-100%
-        data.throttle_position = if cycle_time < 8.0 {
-            5.0 + sin((cycle_time - 3.0) * 0.5) * 2.0 // Small oscillations at idle
-        } else if cycle_time < 13.0 {
-            let accel = (cycle_time - 8.0) / 5.0;
-            accel * 100.0
-        } else {
-            70.0 + sin((cycle_time - 13.0) * 2.0) * 10.0
-        };
+    // Intake temperature: follows coolant with offset
+    data.intake_temp = data.coolant_temp - 5.0 + sin(t * 0.7) * 2.0;
 
-        // Boost pressure (for turbocharged): only at high load
-        if load_factor > 0.6 {
-            data.boost_pressure = (load_factor - 0.6) * 25.0 + sin(t * 1.0) * 1.0;
-        } else {
-            data.boost_pressure = sin(t * 0.5) * 0.5; // Slight vacuum at cruise
-        }
+    // Air/fuel ratio: lean at cruise, rich under load
+    let afr_base = 14.7 + (1.0 - load_factor) * 1.0; // Leaner at higher load
+    data.air_fuel_ratio = afr_base + sin(t * 1.2) * 0.3;
 
-        // Ignition advance: varies with load
-        data.ignition_advance = 15.0 + (1.0 - load_factor) * 10.0 + sin(t * 0.8) * 1.0;
-
-        // Injector duty cycle: proportional to load
-        data.injector_duty = load_factor * 95.0 + sin(t * 2.0) * 3.0;
-
-        // Vehicle speed: proportional to RPM and throttle
-        let speed_factor = (data.rpm / 7000.0) * (data.throttle_position / 100.0);
-        data.vehicle_speed = speed_factor * 150.0 + sin(t * 0.3) * 2.0;
-
-        // Clamp values to realistic ranges
-        data.rpm = data.rpm.max(0.0).min(8500.0);
-        data.map_pressure = data.map_pressure.max(0.0).min(250.0);
-        data.coolant_temp = data.coolant_temp.max(60.0).min(250.0);
-        data.intake_temp = data.intake_temp.max(50.0).min(200.0);
-        data.air_fuel_ratio = data.air_fuel_ratio.max(10.0).min(20.0);
-        data.oil_pressure = data.oil_pressure.max(0.0).min(100.0);
-        data.fuel_pressure = data.fuel_pressure.max(0.0).min(100.0);
-        data.battery_voltage = data.battery_voltage.max(10.0).min(16.0);
-        data.throttle_position = data.throttle_position.max(0.0).min(100.0);
-        data.boost_pressure = data.boost_pressure.max(-15.0).min(30.0);
-        data.ignition_advance = data.ignition_advance.max(-10.0).min(50.0);
-        data.injector_duty = data.injector_duty.max(0.0).min(100.0);
-        data.vehicle_speed = data.vehicle_speed.max(0.0).min(200.0);
+    // Oil pressure: increases with RPM
+    let oil_rpm_factor = (data.rpm / 7000.0).min(1.0);
+    data.oil_pressure = 20.0 + oil_rpm_factor * 50.0 + sin(t * 0.4) * 2.0;
 
-        data
+    // Fuel pressure: varies slightly with load
+    data.fuel_pressure = 40.0 + load_factor * 10.0 + sin(t * 1.5) * 1.0;
+
+    // Battery voltage: drops under heavy load, recovers at idle
+    data.battery_voltage = 13.5 - load_factor * 0.8 + sin(t * 0.2) * 0.1;
+
+    // Throttle position: cycles through 0-100%
+    data.throttle_position = if cycle_time < 8.0 {
+        5.0 + sin((cycle_time - 3.0) * 0.5) * 2.0 // Small oscillations at idle
+    } else if cycle_time < 13.0 {
+        let accel = (cycle_time - 8.0) / 5.0;
+        accel * 100.0
+    } else {
+        70.0 + sin((cycle_time - 13.0) * 2.0) * 10.0
+    };
+
+    // Boost pressure (for turbocharged): only at high load
+    if load_factor > 0.6 {
+        data.boost_pressure = (load_factor - 0.6) * 25.0 + sin(t * 1.0) * 1.0;
+    } else {
+        data.boost_pressure = sin(t * 0.5) * 0.5; // Slight vacuum at cruise
     }
 
-    /// Get current mock data without advancing time
-    pub fn get_current(&self) -> MockECUData {
-        let t = (self.time_ms as f32) / 1000.0;
-        let cycle_time = t % 18.0;
-
-        let mut data = MockECUData::new();
-
-        // RPM calculation (same as update)
-        data.rpm = if cycle_time < 3.0 {
-            (cycle_time / 3.0) * 1200.0 + sin(cycle_time * 3.0) * 100.0
-        } else if cycle_time < 8.0 {
-            1000.0 + sin((cycle_time - 3.0) * 2.0) * 50.0
-        } else if cycle_time < 13.0 {
-            let accel_phase = (cycle_time - 8.0) / 5.0;
-            1000.0 + accel_phase * 4000.0 + sin(accel_phase * 4.0 * PI) * 200.0
-        } else {
-            6000.0 + sin((cycle_time - 13.0) * 1.5) * 150.0
-        };
+    // Ignition advance: varies with load
+    data.ignition_advance = 15.0 + (1.0 - load_factor) * 10.0 + sin(t * 0.8) * 1.0;
 
-        let load_factor = (data.rpm / 6500.0).min(1.0);
-        data.map_pressure = 20.0 + load_factor * 80.0 + sin(t * 0.5) * 5.0;
-        data.coolant_temp = 70.0 + if t < 20.0 { t / 20.0 } else { 1.0 } * 120.0 + sin(t * 0.3) * 3.0;
-        data.intake_temp = data.coolant_temp - 5.0 + sin(t * 0.7) * 2.0;
-        data.air_fuel_ratio = 14.7 + (1.0 - load_factor) * 1.0 + sin(t * 1.2) * 0.3;
-        data.oil_pressure = 20.0 + load_factor * 50.0 + sin(t * 0.4) * 2.0;
-        data.fuel_pressure = 40.0 + load_factor * 10.0 + sin(t * 1.5) * 1.0;
-        data.battery_voltage = 13.5 - load_factor * 0.8 + sin(t * 0.2) * 0.1;
-        data.throttle_position = if cycle_time < 8.0 {
-            5.0 + sin((cycle_time - 3.0) * 0.5) * 2.0
-        } else if cycle_time < 13.0 {
-            ((cycle_time - 8.0) / 5.0) * 100.0
-        } else {
-            70.0 + sin((cycle_time - 13.0) * 2.0) * 10.0
-        };
-        
-        if load_factor > 0.6 {
-            data.boost_pressure = (load_factor - 0.6) * 25.0 + sin(t * 1.0) * 1.0;
+    // Injector duty cycle: proportional to load
+    data.injector_duty = load_factor * 95.0 + sin(t * 2.0) * 3.0;
+
+    // Vehicle speed: proportional to RPM and throttle
+    let speed_factor = (data.rpm / 7000.0) * (data.throttle_position / 100.0);
+    data.vehicle_speed = speed_factor * 150.0 + sin(t * 0.3) * 2.0;
+
+    data
+}
+
+/// Warm engine idling in neutral
+fn idle_data(t: f32) -> MockECUData {
+    let mut data = MockECUData::new();
+    data.rpm = 850.0 + sin(t * 2.0) * 30.0;
+    data.map_pressure = 35.0 + sin(t * 0.5) * 2.0;
+    data.coolant_temp = 185.0 + sin(t * 0.1) * 2.0;
+    data.intake_temp = 95.0;
+    data.air_fuel_ratio = 14.7 + sin(t * 1.2) * 0.2;
+    data.oil_pressure = 25.0;
+    data.fuel_pressure = 43.0;
+    data.battery_voltage = 14.1;
+    data.throttle_position = 2.0;
+    data.boost_pressure = -9.5;
+    data.ignition_advance = 15.0;
+    data.injector_duty = 3.0;
+    data
+}
+
+/// Repeated full-throttle pulls: 4 s from 3000 to 7000 RPM, 2 s lift-off
+fn wot_data(t: f32) -> MockECUData {
+    let mut data = MockECUData::new();
+    let cycle_time = t % 6.0;
+    if cycle_time < 4.0 {
+        let ramp = cycle_time / 4.0;
+        data.rpm = 3000.0 + ramp * 4000.0;
+        data.map_pressure = 100.0 + ramp * 100.0;
+        data.throttle_position = 100.0;
+        data.air_fuel_ratio = 12.0;
+        data.boost_pressure = ramp * 15.0;
+        data.injector_duty = 60.0 + ramp * 30.0;
+        data.ignition_advance = 20.0 - ramp * 6.0;
+        data.vehicle_speed = 40.0 + ramp * 80.0;
+    } else {
+        let lift = (cycle_time - 4.0) / 2.0;
+        data.rpm = 7000.0 - lift * 4000.0;
+        data.map_pressure = 25.0;
+        data.throttle_position = 0.0;
+        data.air_fuel_ratio = 18.0;
+        data.boost_pressure = -11.0;
+        data.injector_duty = 0.0;
+        data.ignition_advance = 30.0;
+        data.vehicle_speed = 120.0 - lift * 80.0;
+    }
+    data.coolant_temp = 195.0;
+    data.intake_temp = 110.0;
+    data.oil_pressure = 20.0 + data.rpm / 7000.0 * 50.0;
+    data.fuel_pressure = 58.0;
+    data.battery_voltage = 13.8;
+    data
+}
+
+/// Idle with coolant climbing 2°F per second from 190°F until it pegs
+fn overheat_data(t: f32) -> MockECUData {
+    let mut data = idle_data(t);
+    data.coolant_temp = 190.0 + t * 2.0;
+    data.intake_temp = 100.0 + t;
+    data
+}
+
+/// Steady cruise with the mixture drifting lean from 16 to 19 AFR over 30 s
+fn lean_data(t: f32) -> MockECUData {
+    let mut data = MockECUData::new();
+    data.rpm = 3000.0 + sin(t * 0.5) * 50.0;
+    data.map_pressure = 60.0;
+    data.coolant_temp = 190.0;
+    data.intake_temp = 100.0;
+    data.air_fuel_ratio = 16.0 + (t / 30.0).min(1.0) * 3.0;
+    data.oil_pressure = 45.0;
+    data.fuel_pressure = 35.0;
+    data.battery_voltage = 13.9;
+    data.throttle_position = 25.0;
+    data.boost_pressure = -6.0;
+    data.ignition_advance = 32.0;
+    data.injector_duty = 25.0;
+    data.vehicle_speed = 60.0;
+    data
+}
+
+/// Clamp values to realistic ranges
+fn clamp_data(data: &mut MockECUData) {
+    data.rpm = data.rpm.max(0.0).min(8500.0);
+    data.map_pressure = data.map_pressure.max(0.0).min(250.0);
+    data.coolant_temp = data.coolant_temp.max(60.0).min(250.0);
+    data.intake_temp = data.intake_temp.max(50.0).min(200.0);
+    data.air_fuel_ratio = data.air_fuel_ratio.max(10.0).min(20.0);
+    data.oil_pressure = data.oil_pressure.max(0.0).min(100.0);
+    data.fuel_pressure = data.fuel_pressure.max(0.0).min(100.0);
+    data.battery_voltage = data.battery_voltage.max(10.0).min(16.0);
+    data.throttle_position = data.throttle_position.max(0.0).min(100.0);
+    data.boost_pressure = data.boost_pressure.max(-15.0).min(30.0);
+    data.ignition_advance = data.ignition_advance.max(-10.0).min(50.0);
+    data.injector_duty = data.injector_duty.max(0.0).min(100.0);
+    data.vehicle_speed = data.vehicle_speed.max(0.0).min(200.0);
+}
+
+/// Stateless pseudo-random value in -1.0..1.0
+fn noise_hash(seed: u32, time_ms: u32, channel: u32) -> f32 {
+    let mut x = seed ^ time_ms.wrapping_mul(0x9E37_79B9) ^ channel.wrapping_mul(0x85EB_CA6B);
+    // xorshift-multiply finalizer
+    x ^= x >> 16;
+    x = x.wrapping_mul(0x7FEB_352D);
+    x ^= x >> 15;
+    x = x.wrapping_mul(0x846C_A68B);
+    x ^= x >> 16;
+    (x >> 8) as f32 / (1u32 << 23) as f32 - 1.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::colors::{GaugeStatus, Theme};
+    use crate::ts_gauge::{TSGauge, TSGaugeStyle};
+    use crate::ts_ini_parser::GaugeConfig;
+
+    /// Coolant gauge in °F: warning from 220, danger from 235
+    fn coolant_gauge() -> TSGauge {
+        let mut config = GaugeConfig::new();
+        config.lo = 60.0;
+        config.hi = 260.0;
+        config.lo_danger = 80.0;
+        config.lo_warning = 100.0;
+        config.hi_warning = 220.0;
+        config.hi_danger = 235.0;
+        TSGauge::new(config, TSGaugeStyle::HorizontalBar, 0, 0, 200, 40)
+    }
+
+    #[test]
+    fn overheat_drives_coolant_gauge_to_danger() {
+        let mut ecu = MockECU::new();
+        ecu.set_scenario(MockScenario::Overheat);
+        let mut gauge = coolant_gauge();
+
+        gauge.set_value(ecu.get_current().coolant_temp);
+        assert_eq!(gauge.get_status(), GaugeStatus::Normal);
+
+        // 2°F per second from 190°F passes 235°F after 22.5 s
+        for _ in 0..30 {
+            gauge.set_value(ecu.update(1000).coolant_temp);
         }
+        assert_eq!(gauge.get_status(), GaugeStatus::Danger);
+        assert_eq!(gauge.get_color().to_u32(), Theme::DARK.danger.to_u32());
+    }
 
-        data.ignition_advance = 15.0 + (1.0 - load_factor) * 10.0 + sin(t * 0.8) * 1.0;
-        data.injector_duty = load_factor * 95.0 + sin(t * 2.0) * 3.0;
-        let speed_factor = (data.rpm / 7000.0) * (data.throttle_position / 100.0);
-        data.vehicle_speed = speed_factor * 150.0 + sin(t * 0.3) * 2.0;
-
-        // Clamp values
-        data.rpm = data.rpm.max(0.0).min(8500.0);
-        data.map_pressure = data.map_pressure.max(0.0).min(250.0);
-        data.coolant_temp = data.coolant_temp.max(60.0).min(250.0);
-        data.intake_temp = data.intake_temp.max(50.0).min(200.0);
-        data.air_fuel_ratio = data.air_fuel_ratio.max(10.0).min(20.0);
-        data.oil_pressure = data.oil_pressure.max(0.0).min(100.0);
-        data.fuel_pressure = data.fuel_pressure.max(0.0).min(100.0);
-        data.battery_voltage = data.battery_voltage.max(10.0).min(16.0);
-        data.throttle_position = data.throttle_position.max(0.0).min(100.0);
-        data.boost_pressure = data.boost_pressure.max(-15.0).min(30.0);
-        data.ignition_advance = data.ignition_advance.max(-10.0).min(50.0);
-        data.injector_duty = data.injector_duty.max(0.0).min(100.0);
-        data.vehicle_speed = data.vehicle_speed.max(0.0).min(200.0);
+    #[test]
+    fn override_pins_one_channel() {
+        let mut ecu = MockECU::new();
+        ecu.set_scenario(MockScenario::Idle);
+        ecu.set_value_override(MockChannel::CoolantTemp, 245.0);
 
-        data
+        let data = ecu.update(5000);
+        assert_eq!(data.coolant_temp, 245.0);
+        // Other channels still follow the scenario
+        assert!(data.rpm > 800.0 && data.rpm < 900.0);
+
+        let mut gauge = coolant_gauge();
+        gauge.set_value(data.coolant_temp);
+        assert_eq!(gauge.get_status(), GaugeStatus::Danger);
+
+        ecu.clear_value_override(MockChannel::CoolantTemp);
+        assert!(ecu.get_current().coolant_temp < 190.0);
+    }
+
+    #[test]
+    fn same_seed_replays_same_data() {
+        let mut a = MockECU::new();
+        let mut b = MockECU::new();
+        for ecu in [&mut a, &mut b] {
+            ecu.set_scenario(MockScenario::WideOpenThrottle);
+            ecu.seed = 1234;
+            ecu.noise = 0.05;
+        }
+        for _ in 0..50 {
+            let (da, db) = (a.update(100), b.update(100));
+            for i in 0..MOCK_CHANNEL_COUNT {
+                let channel = MockChannel::from_index(i).unwrap();
+                assert_eq!(da.get(channel), db.get(channel));
+            }
+        }
+
+        b.seed = 99;
+        assert_ne!(a.get_current().rpm, b.get_current().rpm);
+    }
+
+    #[test]
+    fn flatline_holds_defaults_and_reset_restarts() {
+        let mut ecu = MockECU::new();
+        ecu.set_scenario(MockScenario::Flatline);
+        let data = ecu.update(60_000);
+        assert_eq!(data.coolant_temp, MockECUData::new().coolant_temp);
+        assert_eq!(data.rpm, 0.0);
+
+        ecu.set_scenario(MockScenario::Lean);
+        assert_eq!(ecu.time_ms, 0);
+        assert_eq!(ecu.scenario(), MockScenario::Lean);
+        assert!(ecu.update(30_000).air_fuel_ratio > 18.5);
     }
 }
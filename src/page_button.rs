@@ -0,0 +1,109 @@
+use crate::mmio::{mmio_read, mmio_write};
+use crate::timer::delay_us;
+
+const GPIO_BASE: u32 = 0x3F200000;
+const GPFSEL0: u32 = GPIO_BASE;
+const GPLEV0: u32 = GPIO_BASE + 0x34;
+const GPPUD: u32 = GPIO_BASE + 0x94;
+const GPPUDCLK0: u32 = GPIO_BASE + 0x98;
+
+/// Highest BCM pin number brought out on the header
+pub const MAX_PIN: u32 = 27;
+
+/// Pins the debug UART uses, which can't double as a button
+const UART_PINS: [u32; 2] = [14, 15];
+
+/// How long the pin must read low before a press counts
+const DEBOUNCE_MS: u32 = 30;
+
+/// Momentary push button that steps through the dashboard pages
+/// Wired between a GPIO pin on the 40-pin header and ground; the pin's
+/// internal pull-up holds it high until the button is pressed.
+pub struct PageButton {
+    pin: u32,
+    pressed: bool,
+    low_ms: u32,
+}
+
+impl PageButton {
+    /// Set `pin` up as an input with pull-up
+    /// None if the pin isn't on the header or is taken by the UART
+    pub fn new(pin: u32) -> Option<Self> {
+        if !Self::usable_pin(pin) {
+            return None;
+        }
+
+        // Function select: 3 bits per pin, 000 = input
+        let fsel = GPFSEL0 + (pin / 10) * 4;
+        let shift = (pin % 10) * 3;
+        mmio_write(fsel, mmio_read(fsel) & !(7 << shift));
+
+        // Pull-up: set the control, clock it into the pin, then release
+        mmio_write(GPPUD, 2);
+        delay_us(5);
+        mmio_write(GPPUDCLK0, 1 << pin);
+        delay_us(5);
+        mmio_write(GPPUD, 0);
+        mmio_write(GPPUDCLK0, 0);
+
+        Some(Self::debounced(pin))
+    }
+
+    /// Whether `pin` can be configured as the page button
+    pub fn usable_pin(pin: u32) -> bool {
+        pin <= MAX_PIN && !UART_PINS.contains(&pin)
+    }
+
+    fn debounced(pin: u32) -> Self {
+        PageButton { pin, pressed: false, low_ms: 0 }
+    }
+
+    /// Read the pin; true once per press
+    pub fn poll(&mut self, dt_ms: u32) -> bool {
+        let low = mmio_read(GPLEV0) & (1 << self.pin) == 0;
+        self.update(low, dt_ms)
+    }
+
+    /// Debounce a pin reading taken `dt_ms` after the previous one
+    /// Returns true when the pin has been low for `DEBOUNCE_MS`; the button
+    /// must then be released before it can fire again
+    fn update(&mut self, low: bool, dt_ms: u32) -> bool {
+        if !low {
+            self.low_ms = 0;
+            self.pressed = false;
+            return false;
+        }
+        self.low_ms = self.low_ms.saturating_add(dt_ms);
+        if !self.pressed && self.low_ms >= DEBOUNCE_MS {
+            self.pressed = true;
+            return true;
+        }
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn press_fires_once_after_debounce() {
+        let mut button = PageButton::debounced(17);
+        assert!(!button.update(true, DEBOUNCE_MS / 2));
+        // A bounce back high restarts the debounce
+        assert!(!button.update(false, 1));
+        assert!(!button.update(true, DEBOUNCE_MS - 1));
+        assert!(button.update(true, 1));
+        assert!(!button.update(true, 1000));
+        assert!(!button.update(false, 1));
+        assert!(!button.update(true, DEBOUNCE_MS - 1));
+        assert!(button.update(true, 1));
+    }
+
+    #[test]
+    fn uart_and_off_header_pins_are_rejected() {
+        assert!(PageButton::usable_pin(17));
+        assert!(!PageButton::usable_pin(14));
+        assert!(!PageButton::usable_pin(MAX_PIN + 1));
+    }
+}
@@ -0,0 +1,88 @@
+/// Full-screen panic indicator, so a crash is obvious instead of looking
+/// like a frozen frame or a lost ECU
+/// Only the framebuffer geometry is kept (in atomics, set once at boot); the
+/// panic handler fills the screen with direct volatile writes and draws the
+/// text through a fresh `Framebuffer`, never the main loop's possibly
+/// half-updated one
+
+use core::panic::PanicInfo;
+use core::ptr;
+use core::sync::atomic::{AtomicU32, Ordering};
+use crate::colors::colors;
+use crate::digit_renderer::{draw_str, format_u32, str_width};
+use crate::framebuffer::Framebuffer;
+use crate::uart;
+
+static FB_ADDRESS: AtomicU32 = AtomicU32::new(0);
+static FB_WIDTH: AtomicU32 = AtomicU32::new(0);
+static FB_HEIGHT: AtomicU32 = AtomicU32::new(0);
+static FB_PITCH: AtomicU32 = AtomicU32::new(0);
+
+const PANIC_BACKGROUND: u32 = 0xB00000;
+
+/// Pixel scale of the "PANIC" text and of the location line below it
+const TITLE_SCALE: u32 = 12;
+const DETAIL_SCALE: u32 = 3;
+
+/// Remember where the screen is; call once the framebuffer is allocated
+pub fn register(address: u32, width: u32, height: u32, pitch: u32) {
+    FB_WIDTH.store(width, Ordering::Relaxed);
+    FB_HEIGHT.store(height, Ordering::Relaxed);
+    FB_PITCH.store(pitch, Ordering::Relaxed);
+    // Address last: a non-zero address means the geometry is complete
+    FB_ADDRESS.store(address, Ordering::Release);
+}
+
+/// Report the panic on the UART and, if a screen was registered, paint it
+pub fn show(info: &PanicInfo) {
+    uart::uart_puts("\n*** PANIC");
+    if let Some(location) = info.location() {
+        uart::uart_puts(" at ");
+        uart::uart_puts(location.file());
+        uart::uart_puts(":");
+        uart::uart_put_dec(location.line());
+    }
+    uart::uart_puts(" ***\n");
+
+    let address = FB_ADDRESS.load(Ordering::Acquire);
+    let width = FB_WIDTH.load(Ordering::Relaxed);
+    let height = FB_HEIGHT.load(Ordering::Relaxed);
+    // Same rule as Framebuffer::with_pitch for an unreported pitch
+    let pitch = FB_PITCH.load(Ordering::Relaxed).max(width * 4);
+    if address == 0 || width == 0 || height == 0 {
+        return;
+    }
+
+    for y in 0..height {
+        let row = (address + y * pitch) as *mut u32;
+        for x in 0..width {
+            unsafe {
+                ptr::write_volatile(row.add(x as usize), PANIC_BACKGROUND);
+            }
+        }
+    }
+
+    let mut fb = Framebuffer::with_pitch(address, width, height, pitch);
+    let title = "PANIC";
+    let title_scale = TITLE_SCALE.min(width / str_width(title, 1)).max(1);
+    let title_y = (height / 2).saturating_sub(7 * title_scale);
+    draw_str(
+        &mut fb,
+        title,
+        width.saturating_sub(str_width(title, title_scale)) / 2,
+        title_y,
+        title_scale,
+        colors::WHITE,
+    );
+
+    if let Some(location) = info.location() {
+        let detail_y = title_y + 7 * title_scale + 8 * DETAIL_SCALE;
+        let file_width = str_width(location.file(), DETAIL_SCALE) + str_width(":", DETAIL_SCALE);
+        let mut digits = [0u8; 10];
+        let line = format_u32(location.line(), &mut digits);
+        let x = width.saturating_sub(file_width + str_width(line, DETAIL_SCALE)) / 2;
+        let x = x + draw_str(&mut fb, location.file(), x, detail_y, DETAIL_SCALE, colors::WHITE);
+        let x = x + draw_str(&mut fb, ":", x, detail_y, DETAIL_SCALE, colors::WHITE);
+        draw_str(&mut fb, line, x, detail_y, DETAIL_SCALE, colors::WHITE);
+    }
+}
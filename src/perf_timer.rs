@@ -0,0 +1,125 @@
+/// Standing-start performance timer (0-60 mph, 0-100 km/h, ...)
+/// Times from leaving a standstill until the target speed is reached
+
+/// Common targets, in the unit `vehicle_speed` is reported in
+pub const TARGET_60_MPH: f32 = 60.0;
+pub const TARGET_100_KPH: f32 = 100.0;
+
+/// Speeds at or below this count as standing still
+const DEFAULT_START_THRESHOLD: f32 = 1.0;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PerfTimerState {
+    /// Waiting for a standing start
+    Idle,
+    /// Timing a run
+    Running,
+    /// Target reached; result latched until `reset`
+    Done,
+}
+
+pub struct PerfTimer {
+    /// Speed that ends the run
+    pub target_speed: f32,
+    /// Speed above which the car is considered moving
+    pub start_threshold: f32,
+    state: PerfTimerState,
+    elapsed_ms: u32,
+    /// Seen at a standstill since the last run, so a rolling start isn't timed
+    armed: bool,
+}
+
+impl PerfTimer {
+    pub fn new(target_speed: f32) -> Self {
+        PerfTimer {
+            target_speed,
+            start_threshold: DEFAULT_START_THRESHOLD,
+            state: PerfTimerState::Idle,
+            elapsed_ms: 0,
+            armed: false,
+        }
+    }
+
+    /// Feed the latest speed and the time since the previous update
+    pub fn update(&mut self, speed: f32, dt_ms: u32) {
+        let stopped = speed <= self.start_threshold;
+
+        match self.state {
+            PerfTimerState::Idle => {
+                if stopped {
+                    self.armed = true;
+                } else if self.armed {
+                    // The car started moving during this frame, so it counts
+                    self.state = PerfTimerState::Running;
+                    self.elapsed_ms = dt_ms;
+                    if speed >= self.target_speed {
+                        self.state = PerfTimerState::Done;
+                    }
+                }
+            }
+            PerfTimerState::Running => {
+                self.elapsed_ms = self.elapsed_ms.saturating_add(dt_ms);
+                if speed >= self.target_speed {
+                    self.state = PerfTimerState::Done;
+                } else if stopped {
+                    // Aborted run; already at a standstill so ready for another try
+                    self.state = PerfTimerState::Idle;
+                    self.elapsed_ms = 0;
+                    self.armed = true;
+                }
+            }
+            PerfTimerState::Done => {}
+        }
+    }
+
+    pub fn state(&self) -> PerfTimerState {
+        self.state
+    }
+
+    /// Time of the current run, or the latched result once `Done`
+    pub fn elapsed_ms(&self) -> u32 {
+        self.elapsed_ms
+    }
+
+    /// Clear a latched result and wait for the next standing start
+    pub fn reset(&mut self) {
+        self.state = PerfTimerState::Idle;
+        self.elapsed_ms = 0;
+        self.armed = false;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_the_frame_the_run_starts() {
+        let mut timer = PerfTimer::new(TARGET_60_MPH);
+        timer.update(0.0, 20);
+        timer.update(5.0, 20);
+        assert_eq!(timer.state(), PerfTimerState::Running);
+        assert_eq!(timer.elapsed_ms(), 20);
+        timer.update(30.0, 20);
+        timer.update(60.0, 20);
+        assert_eq!(timer.state(), PerfTimerState::Done);
+        assert_eq!(timer.elapsed_ms(), 60);
+    }
+
+    #[test]
+    fn target_reached_on_the_starting_frame() {
+        let mut timer = PerfTimer::new(10.0);
+        timer.update(0.0, 20);
+        timer.update(12.0, 50);
+        assert_eq!(timer.state(), PerfTimerState::Done);
+        assert_eq!(timer.elapsed_ms(), 50);
+    }
+
+    #[test]
+    fn rolling_start_is_not_timed() {
+        let mut timer = PerfTimer::new(TARGET_60_MPH);
+        timer.update(20.0, 20);
+        timer.update(70.0, 20);
+        assert_eq!(timer.state(), PerfTimerState::Idle);
+    }
+}
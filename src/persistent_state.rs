@@ -0,0 +1,198 @@
+/// Values kept across power cycles in STATE.BIN on the boot partition
+///
+/// File layout (STATE_FILE_SIZE bytes, little-endian):
+///   0  magic        "LDST"
+///   4  version      u16 (STATE_FORMAT_VERSION)
+///   6  payload_len  u16
+///   8  peak_rpm     f32
+///   12 trip         f32 (trip meter display unit)
+///   16 best_run_ms  u32 (0 = no run recorded)
+///   20 crc32        u32 over bytes 0..20
+///
+/// A file with the wrong magic, version, length or checksum (e.g. power lost
+/// mid-write) is ignored and the defaults used instead.
+
+use crate::fatfs::SDCard;
+use crate::megasquirt::crc32;
+
+const STATE_FILE_NAME: &str = "STATE.BIN";
+const STATE_MAGIC: &[u8; 4] = b"LDST";
+pub const STATE_FORMAT_VERSION: u16 = 1;
+const HEADER_SIZE: usize = 8;
+const PAYLOAD_SIZE: usize = 12;
+const STATE_FILE_SIZE: usize = HEADER_SIZE + PAYLOAD_SIZE + 4;
+
+/// Minimum time between writes, to spare the card while values change constantly
+const SAVE_INTERVAL_MS: u32 = 30_000;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct SavedValues {
+    pub peak_rpm: f32,
+    /// Trip distance in the trip meter's display unit
+    pub trip_distance: f32,
+    /// Best standing-start time from the performance timer
+    pub best_run_ms: Option<u32>,
+}
+
+impl SavedValues {
+    fn encode(&self) -> [u8; STATE_FILE_SIZE] {
+        let mut data = [0u8; STATE_FILE_SIZE];
+        data[0..4].copy_from_slice(STATE_MAGIC);
+        data[4..6].copy_from_slice(&STATE_FORMAT_VERSION.to_le_bytes());
+        data[6..8].copy_from_slice(&(PAYLOAD_SIZE as u16).to_le_bytes());
+        data[8..12].copy_from_slice(&self.peak_rpm.to_le_bytes());
+        data[12..16].copy_from_slice(&self.trip_distance.to_le_bytes());
+        data[16..20].copy_from_slice(&self.best_run_ms.unwrap_or(0).to_le_bytes());
+        let crc = crc32(&data[..STATE_FILE_SIZE - 4]);
+        data[20..24].copy_from_slice(&crc.to_le_bytes());
+        data
+    }
+
+    /// None unless the header and checksum are valid
+    fn decode(data: &[u8]) -> Option<Self> {
+        if data.len() < STATE_FILE_SIZE || &data[0..4] != STATE_MAGIC {
+            return None;
+        }
+        let read_u32 = |offset: usize| {
+            u32::from_le_bytes([data[offset], data[offset + 1], data[offset + 2], data[offset + 3]])
+        };
+        let version = u16::from_le_bytes([data[4], data[5]]);
+        let payload_len = u16::from_le_bytes([data[6], data[7]]) as usize;
+        if version != STATE_FORMAT_VERSION || payload_len != PAYLOAD_SIZE {
+            return None;
+        }
+        if crc32(&data[..STATE_FILE_SIZE - 4]) != read_u32(20) {
+            return None;
+        }
+
+        let peak_rpm = f32::from_bits(read_u32(8));
+        let trip_distance = f32::from_bits(read_u32(12));
+        let best_run_ms = read_u32(16);
+        // A valid checksum over NaN still isn't a usable value
+        if !peak_rpm.is_finite() || !trip_distance.is_finite() {
+            return None;
+        }
+        Some(SavedValues {
+            peak_rpm,
+            trip_distance,
+            best_run_ms: if best_run_ms == 0 { None } else { Some(best_run_ms) },
+        })
+    }
+}
+
+pub struct PersistentState {
+    values: SavedValues,
+    /// Values changed since the last successful save
+    dirty: bool,
+    since_save_ms: u32,
+    /// Cleared after a failed write (read-only or failing card) so the main
+    /// loop isn't stalled retrying; values are still tracked in memory
+    writable: bool,
+}
+
+impl PersistentState {
+    pub fn new() -> Self {
+        PersistentState {
+            values: SavedValues::default(),
+            dirty: false,
+            since_save_ms: 0,
+            writable: true,
+        }
+    }
+
+    /// Load the saved values; defaults if there is no card, no file or the
+    /// file is invalid
+    pub fn load(card: Option<&mut SDCard>) -> Self {
+        let mut state = PersistentState::new();
+        let card = match card {
+            Some(card) => card,
+            None => return state,
+        };
+        let entry = match card.find_file(STATE_FILE_NAME) {
+            Some(entry) => entry,
+            None => return state,
+        };
+
+        let mut buffer = [0u8; STATE_FILE_SIZE];
+        let len = card.read_file(&entry, &mut buffer);
+        if let Some(values) = SavedValues::decode(&buffer[..len]) {
+            state.values = values;
+        }
+        state
+    }
+
+    pub fn values(&self) -> &SavedValues {
+        &self.values
+    }
+
+    /// Record an RPM reading; only a new peak marks the state changed
+    pub fn set_peak_rpm(&mut self, rpm: f32) {
+        if rpm > self.values.peak_rpm {
+            self.values.peak_rpm = rpm;
+            self.dirty = true;
+        }
+    }
+
+    pub fn set_trip_distance(&mut self, distance: f32) {
+        if distance != self.values.trip_distance {
+            self.values.trip_distance = distance;
+            self.dirty = true;
+        }
+    }
+
+    /// Record a finished performance run; kept only if it beats the best
+    pub fn record_run(&mut self, elapsed_ms: u32) {
+        if elapsed_ms == 0 {
+            return;
+        }
+        if self.values.best_run_ms.is_none_or(|best| elapsed_ms < best) {
+            self.values.best_run_ms = Some(elapsed_ms);
+            self.dirty = true;
+        }
+    }
+
+    /// Clear all saved values; written out on the next save
+    pub fn reset(&mut self) {
+        self.values = SavedValues::default();
+        self.dirty = true;
+    }
+
+    /// Advance the save timer and write if values changed and the interval
+    /// has passed. Returns false only when a write was attempted and failed
+    pub fn update(&mut self, dt_ms: u32, card: Option<&mut SDCard>) -> bool {
+        self.since_save_ms = self.since_save_ms.saturating_add(dt_ms);
+        if !self.dirty || !self.writable || self.since_save_ms < SAVE_INTERVAL_MS {
+            return true;
+        }
+        match card {
+            Some(card) => self.save(card),
+            None => true,
+        }
+    }
+
+    /// Write the values now (e.g. before a controlled shutdown)
+    pub fn save(&mut self, card: &mut SDCard) -> bool {
+        if !self.writable {
+            return false;
+        }
+        self.since_save_ms = 0;
+        if card.write_file(STATE_FILE_NAME, &self.values.encode()) {
+            self.dirty = false;
+            true
+        } else {
+            self.writable = false;
+            false
+        }
+    }
+
+    /// False once a write has failed; saving is not retried after that
+    pub fn is_writable(&self) -> bool {
+        self.writable
+    }
+}
+
+impl Default for PersistentState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
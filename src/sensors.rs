@@ -0,0 +1,297 @@
+/// Conversions for raw analog sensor readings
+/// Thermistors use the Steinhart-Hart equation:
+///   1/T = A + B*ln(R) + C*ln(R)^3   (T in Kelvin)
+
+use crate::math::ln;
+
+const KELVIN_OFFSET: f32 = 273.15;
+
+/// Steinhart-Hart coefficients for one thermistor
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SteinhartHart {
+    pub a: f32,
+    pub b: f32,
+    pub c: f32,
+}
+
+impl SteinhartHart {
+    pub const fn new(a: f32, b: f32, c: f32) -> Self {
+        SteinhartHart { a, b, c }
+    }
+
+    /// Look up a preset by the name used in config ("gm_clt", "gm_iat")
+    pub fn preset(name: &str) -> Option<Self> {
+        match name.trim() {
+            "gm_clt" | "gm" => Some(GM_CLT),
+            "gm_iat" => Some(GM_IAT),
+            _ => None,
+        }
+    }
+}
+
+/// GM coolant sensor, fitted to -40°C/100700Ω, 30°C/2238Ω, 100°C/177Ω
+pub const GM_CLT: SteinhartHart = SteinhartHart::new(1.471561e-3, 2.306238e-4, 1.051556e-7);
+
+/// GM intake air sensor (same resistance curve as the coolant sensor)
+pub const GM_IAT: SteinhartHart = GM_CLT;
+
+/// Convert thermistor resistance to temperature in °C
+/// Returns None for non-positive resistance (open/shorted sensor)
+pub fn resistance_to_temp(ohms: f32, coeffs: SteinhartHart) -> Option<f32> {
+    if ohms.is_nan() || ohms <= 0.0 {
+        return None;
+    }
+    let l = ln(ohms);
+    let inv_kelvin = coeffs.a + coeffs.b * l + coeffs.c * l * l * l;
+    if inv_kelvin <= 0.0 {
+        return None;
+    }
+    Some(1.0 / inv_kelvin - KELVIN_OFFSET)
+}
+
+/// Thermistor read through a pull-up (bias) resistor divider into an ADC
+#[derive(Clone, Copy, Debug)]
+pub struct ThermistorInput {
+    pub coeffs: SteinhartHart,
+    /// Pull-up resistor between the reference voltage and the sensor
+    pub bias_ohms: f32,
+    /// ADC count at the reference voltage (e.g. 1023 for 10-bit)
+    pub adc_max: u32,
+}
+
+impl ThermistorInput {
+    pub fn new(coeffs: SteinhartHart, bias_ohms: f32, adc_max: u32) -> Self {
+        ThermistorInput { coeffs, bias_ohms, adc_max }
+    }
+
+    /// Sensor resistance from an ADC count; None when the input is pegged
+    pub fn adc_to_resistance(&self, adc: u32) -> Option<f32> {
+        if adc == 0 || adc >= self.adc_max {
+            return None;
+        }
+        Some(self.bias_ohms * adc as f32 / (self.adc_max - adc) as f32)
+    }
+
+    /// Temperature in °C from an ADC count
+    pub fn adc_to_temp(&self, adc: u32) -> Option<f32> {
+        resistance_to_temp(self.adc_to_resistance(adc)?, self.coeffs)
+    }
+}
+
+/// Maximum number of breakpoints in a `LookupTable`
+pub const MAX_TABLE_POINTS: usize = 32;
+
+/// Piecewise-linear transfer curve for sensors without a formula (e.g. a MAF)
+/// Inputs must be strictly increasing; outputs may have any shape
+#[derive(Clone, Copy, Debug)]
+pub struct LookupTable {
+    /// Name a channel refers to the table by
+    pub name: [u8; 32],
+    inputs: [f32; MAX_TABLE_POINTS],
+    outputs: [f32; MAX_TABLE_POINTS],
+    len: usize,
+}
+
+impl LookupTable {
+    pub fn new(name: &str) -> Self {
+        let mut table = LookupTable {
+            name: [0; 32],
+            inputs: [0.0; MAX_TABLE_POINTS],
+            outputs: [0.0; MAX_TABLE_POINTS],
+            len: 0,
+        };
+        let bytes = name.trim().as_bytes();
+        let len = bytes.len().min(table.name.len() - 1);
+        table.name[..len].copy_from_slice(&bytes[..len]);
+        table
+    }
+
+    pub fn name_str(&self) -> &str {
+        let len = self.name.iter().position(|&b| b == 0).unwrap_or(self.name.len());
+        core::str::from_utf8(&self.name[..len]).unwrap_or("")
+    }
+
+    /// Append a breakpoint; rejected (false) when full or when `input` isn't
+    /// above the previous breakpoint's
+    pub fn push(&mut self, input: f32, output: f32) -> bool {
+        if self.len >= MAX_TABLE_POINTS || !input.is_finite() || !output.is_finite() {
+            return false;
+        }
+        if self.len > 0 && input <= self.inputs[self.len - 1] {
+            return false;
+        }
+        self.inputs[self.len] = input;
+        self.outputs[self.len] = output;
+        self.len += 1;
+        true
+    }
+
+    /// Add breakpoints from a config value like "0:0, 1.0:12.5, 2.5:60"
+    /// Stops at the first malformed or rejected pair; returns the number added
+    pub fn parse_points(&mut self, text: &str) -> usize {
+        let mut added = 0;
+        for pair in text.split(',') {
+            let pair = pair.trim();
+            if pair.is_empty() {
+                continue;
+            }
+            let colon = match pair.find(':') {
+                Some(colon) => colon,
+                None => break,
+            };
+            let input = match pair[..colon].trim().parse::<f32>() {
+                Ok(value) => value,
+                Err(_) => break,
+            };
+            let output = match pair[colon + 1..].trim().parse::<f32>() {
+                Ok(value) => value,
+                Err(_) => break,
+            };
+            if !self.push(input, output) {
+                break;
+            }
+            added += 1;
+        }
+        added
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Map a raw input through the table
+    /// Linear between breakpoints, clamped to the end outputs outside the
+    /// table; an empty table passes the input through unchanged
+    pub fn interpolate(&self, x: f32) -> f32 {
+        if self.len == 0 {
+            return x;
+        }
+        let last = self.len - 1;
+        if x.is_nan() || x <= self.inputs[0] {
+            return self.outputs[0];
+        }
+        if x >= self.inputs[last] {
+            return self.outputs[last];
+        }
+
+        // Binary search for the first breakpoint above x; inputs[0] < x < inputs[last]
+        let (mut lo, mut hi) = (0, last);
+        while hi - lo > 1 {
+            let mid = (lo + hi) / 2;
+            if self.inputs[mid] <= x {
+                lo = mid;
+            } else {
+                hi = mid;
+            }
+        }
+
+        let (x0, x1) = (self.inputs[lo], self.inputs[hi]);
+        let (y0, y1) = (self.outputs[lo], self.outputs[hi]);
+        y0 + (y1 - y0) * (x - x0) / (x1 - x0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_near(actual: f32, expected: f32, tolerance: f32) {
+        assert!((actual - expected).abs() <= tolerance, "{} not within {} of {}", actual, tolerance, expected);
+    }
+
+    #[test]
+    fn gm_clt_matches_published_curve() {
+        // GM coolant/intake sensor chart (ohms at °C)
+        let published = [
+            (100700.0, -40.0),
+            (52700.0, -30.0),
+            (28680.0, -20.0),
+            (16180.0, -10.0),
+            (9420.0, 0.0),
+            (5670.0, 10.0),
+            (3520.0, 20.0),
+            (2238.0, 30.0),
+            (1459.0, 40.0),
+            (973.0, 50.0),
+            (667.0, 60.0),
+            (467.0, 70.0),
+            (332.0, 80.0),
+            (241.0, 90.0),
+            (177.0, 100.0),
+        ];
+        for (ohms, celsius) in published {
+            assert_near(resistance_to_temp(ohms, GM_CLT).unwrap(), celsius, 1.0);
+        }
+        assert_near(resistance_to_temp(2238.0, GM_IAT).unwrap(), 30.0, 0.2);
+    }
+
+    #[test]
+    fn presets_by_name() {
+        assert_eq!(SteinhartHart::preset("gm_clt"), Some(GM_CLT));
+        assert_eq!(SteinhartHart::preset(" gm "), Some(GM_CLT));
+        assert_eq!(SteinhartHart::preset("gm_iat"), Some(GM_IAT));
+        assert_eq!(SteinhartHart::preset("bosch"), None);
+    }
+
+    #[test]
+    fn faulted_sensor_has_no_temperature() {
+        assert_eq!(resistance_to_temp(0.0, GM_CLT), None);
+        assert_eq!(resistance_to_temp(-5.0, GM_CLT), None);
+        assert_eq!(resistance_to_temp(f32::NAN, GM_CLT), None);
+    }
+
+    #[test]
+    fn adc_divider_reading() {
+        // 2.49k bias on a 10-bit ADC; a count of 485 is ~2238 ohms
+        let input = ThermistorInput::new(GM_CLT, 2490.0, 1023);
+        assert_near(input.adc_to_resistance(485).unwrap(), 2244.6, 0.5);
+        assert_near(input.adc_to_temp(485).unwrap(), 30.0, 0.5);
+        assert_eq!(input.adc_to_temp(0), None);
+        assert_eq!(input.adc_to_temp(1023), None);
+    }
+
+    fn maf_table() -> LookupTable {
+        let mut table = LookupTable::new("maf");
+        assert_eq!(table.parse_points("0:0, 1.0:10, 2.0:40, 4.0:100, 5.0:200"), 5);
+        table
+    }
+
+    #[test]
+    fn lookup_interpolates_between_breakpoints() {
+        let table = maf_table();
+        assert_eq!(table.name_str(), "maf");
+        assert_eq!(table.len(), 5);
+        assert_near(table.interpolate(0.5), 5.0, 1e-4);
+        assert_near(table.interpolate(1.5), 25.0, 1e-4);
+        assert_near(table.interpolate(3.0), 70.0, 1e-4);
+        assert_near(table.interpolate(4.5), 150.0, 1e-4);
+        // Exact breakpoints
+        assert_near(table.interpolate(1.0), 10.0, 1e-4);
+        assert_near(table.interpolate(4.0), 100.0, 1e-4);
+    }
+
+    #[test]
+    fn lookup_clamps_out_of_range() {
+        let table = maf_table();
+        assert_eq!(table.interpolate(-3.0), 0.0);
+        assert_eq!(table.interpolate(9.0), 200.0);
+        assert_eq!(table.interpolate(f32::NAN), 0.0);
+        assert_eq!(LookupTable::new("empty").interpolate(3.5), 3.5);
+    }
+
+    #[test]
+    fn lookup_rejects_non_increasing_inputs() {
+        let mut table = LookupTable::new("bad");
+        assert!(table.push(1.0, 1.0));
+        assert!(!table.push(1.0, 2.0));
+        assert!(!table.push(0.5, 2.0));
+        // Parsing stops at the first malformed or rejected pair
+        assert_eq!(table.parse_points("2:4, 3:x, 4:8"), 1);
+        assert_eq!(table.parse_points("5:10, 4:8"), 1);
+        assert_eq!(table.len(), 3);
+    }
+}
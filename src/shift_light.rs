@@ -0,0 +1,125 @@
+/// Sequential shift light rendered as a row of LEDs across the top of the screen
+/// LEDs light progressively from green to red as RPM approaches the shift
+/// point, and the whole bar flashes once RPM reaches the flash point
+
+use crate::framebuffer::Framebuffer;
+use crate::colors::{Color, colors, interpolate_color};
+
+/// Default number of LEDs in the bar
+const DEFAULT_LED_COUNT: u32 = 10;
+
+/// Default on/off period of the over-rev flash
+const DEFAULT_FLASH_PERIOD_MS: u32 = 100;
+
+/// Height of the LED row in pixels
+const LED_HEIGHT: u32 = 24;
+
+/// Gap between LEDs and around the bar in pixels
+const LED_GAP: u32 = 6;
+
+pub struct ShiftLight {
+    /// RPM at which the first LED lights
+    pub start_rpm: f32,
+    /// RPM at which every LED is lit
+    pub shift_rpm: f32,
+    /// RPM at which the whole bar flashes
+    pub flash_rpm: f32,
+    /// Number of LEDs across the bar
+    pub led_count: u32,
+    /// Time each flash state (on or off) is held
+    pub flash_period_ms: u32,
+    rpm: f32,
+    flash_timer_ms: u32,
+    flash_on: bool,
+}
+
+impl ShiftLight {
+    /// First LED lights at 60% of `shift_rpm`
+    pub fn new(shift_rpm: f32, flash_rpm: f32) -> Self {
+        ShiftLight {
+            start_rpm: shift_rpm * 0.6,
+            shift_rpm,
+            flash_rpm,
+            led_count: DEFAULT_LED_COUNT,
+            flash_period_ms: DEFAULT_FLASH_PERIOD_MS,
+            rpm: 0.0,
+            flash_timer_ms: 0,
+            flash_on: false,
+        }
+    }
+
+    /// Feed the latest RPM and advance the flash timer by elapsed time
+    pub fn update(&mut self, rpm: f32, dt_ms: u32) {
+        self.rpm = rpm;
+
+        if !self.is_flashing() {
+            self.flash_timer_ms = 0;
+            self.flash_on = true;
+            return;
+        }
+
+        self.flash_timer_ms += dt_ms;
+        if self.flash_period_ms > 0 {
+            while self.flash_timer_ms >= self.flash_period_ms {
+                self.flash_timer_ms -= self.flash_period_ms;
+                self.flash_on = !self.flash_on;
+            }
+        }
+    }
+
+    /// Check if RPM is at or above the flash point
+    pub fn is_flashing(&self) -> bool {
+        self.rpm >= self.flash_rpm
+    }
+
+    /// Number of LEDs lit at the current RPM
+    pub fn lit_count(&self) -> u32 {
+        if self.rpm <= self.start_rpm {
+            return 0;
+        }
+        if self.rpm >= self.shift_rpm || self.shift_rpm <= self.start_rpm {
+            return self.led_count;
+        }
+
+        let progress = (self.rpm - self.start_rpm) / (self.shift_rpm - self.start_rpm);
+        ((progress * self.led_count as f32) as u32 + 1).min(self.led_count)
+    }
+
+    /// Color of an LED by position: green, through yellow, to red
+    fn led_color(&self, index: u32) -> Color {
+        if self.led_count <= 1 {
+            return colors::RED;
+        }
+        let t = index as f32 / (self.led_count - 1) as f32;
+        if t < 0.5 {
+            interpolate_color(colors::GREEN, colors::YELLOW, t * 2.0)
+        } else {
+            interpolate_color(colors::YELLOW, colors::RED, (t - 0.5) * 2.0)
+        }
+    }
+
+    /// Draw the LED row across the top of the screen
+    pub fn render(&self, fb: &mut Framebuffer) {
+        if self.led_count == 0 {
+            return;
+        }
+
+        let usable = fb.width().saturating_sub(LED_GAP * (self.led_count + 1));
+        let led_width = usable / self.led_count;
+        let lit = self.lit_count();
+        let flashing = self.is_flashing();
+
+        for i in 0..self.led_count {
+            let color = if flashing {
+                if self.flash_on { colors::RED } else { colors::DARK_GRAY }
+            } else if i < lit {
+                self.led_color(i)
+            } else {
+                colors::DARK_GRAY
+            };
+
+            let x = LED_GAP + i * (led_width + LED_GAP);
+            fb.draw_filled_rect(x, LED_GAP, led_width, LED_HEIGHT, color.to_u32());
+        }
+    }
+}
@@ -0,0 +1,90 @@
+/// Overall data state shown on screen, so simulated numbers are never
+/// mistaken for real ones. The main loop decides the state after the
+/// config load and ECU sweep, and again as the ECU link comes and goes;
+/// the renderer only reflects it
+
+use crate::framebuffer::Framebuffer;
+use crate::colors::Theme;
+use crate::digit_renderer::{draw_str, str_width};
+
+/// Size of the square status indicator in the top-right corner
+const INDICATOR_SIZE: u32 = 16;
+
+/// Pixel scale of the banner text
+const BANNER_SCALE: u32 = 3;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SystemState {
+    /// Connected to an ECU; values are real
+    LiveEcu,
+    /// No ECU answered; values come from the mock generator
+    Simulation,
+    /// No ECU answered and the mock is off; gauges hold their last values
+    NoEcu,
+    /// The configuration could not be used; defaults are shown
+    ConfigError,
+}
+
+impl SystemState {
+    /// Pick the state from the config check and where values come from
+    pub fn from_link(ecu_connected: bool, mock_active: bool, config_ok: bool) -> Self {
+        if !config_ok {
+            SystemState::ConfigError
+        } else if ecu_connected {
+            SystemState::LiveEcu
+        } else if mock_active {
+            SystemState::Simulation
+        } else {
+            SystemState::NoEcu
+        }
+    }
+
+    /// Banner text; None while live (no banner needed)
+    pub fn message(&self) -> Option<&'static str> {
+        match self {
+            SystemState::LiveEcu => None,
+            SystemState::Simulation => Some("NO ECU - SIMULATION"),
+            SystemState::NoEcu => Some("NO ECU - SEARCHING"),
+            SystemState::ConfigError => Some("CONFIG ERROR - DEFAULTS"),
+        }
+    }
+
+    /// Draw the status indicator, plus a centered banner unless live
+    /// Call after the gauges so the banner stays on top
+    pub fn render(&self, fb: &mut Framebuffer, theme: &Theme) {
+        let indicator = match self {
+            SystemState::LiveEcu => theme.normal,
+            SystemState::Simulation | SystemState::NoEcu | SystemState::ConfigError => theme.danger,
+        };
+        let x = fb.width().saturating_sub(INDICATOR_SIZE + 4);
+        fb.draw_filled_rect(x, 4, INDICATOR_SIZE, INDICATOR_SIZE, indicator.to_u32());
+
+        if let Some(message) = self.message() {
+            let width = str_width(message, BANNER_SCALE);
+            let banner_x = fb.width().saturating_sub(width) / 2;
+            let banner_y = 4 + INDICATOR_SIZE / 2;
+            let pad = BANNER_SCALE * 2;
+            fb.draw_filled_rect(
+                banner_x.saturating_sub(pad),
+                banner_y.saturating_sub(pad),
+                width + pad * 2,
+                7 * BANNER_SCALE + pad * 2,
+                theme.background.to_u32(),
+            );
+            draw_str(fb, message, banner_x, banner_y, BANNER_SCALE, theme.danger);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn idle_gauges_are_not_flagged_as_simulation() {
+        assert_eq!(SystemState::from_link(true, false, true), SystemState::LiveEcu);
+        assert_eq!(SystemState::from_link(false, true, true), SystemState::Simulation);
+        assert_eq!(SystemState::from_link(false, false, true), SystemState::NoEcu);
+        assert_eq!(SystemState::from_link(true, false, false), SystemState::ConfigError);
+    }
+}
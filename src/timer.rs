@@ -0,0 +1,94 @@
+/// BCM2835 system timer: a free-running 64-bit counter ticking at 1 MHz
+/// Independent of the CPU clock, so timeouts and delays mean the same
+/// thing on every Pi model and under QEMU
+
+use crate::mmio::mmio_read;
+
+const SYSTEM_TIMER_BASE: u32 = 0x3F003000;
+const SYSTEM_TIMER_CLO: u32 = SYSTEM_TIMER_BASE + 0x04;
+const SYSTEM_TIMER_CHI: u32 = SYSTEM_TIMER_BASE + 0x08;
+
+/// Microseconds since the timer started (power-on)
+pub fn now_us() -> u64 {
+    // Re-read if the low word wrapped between the two reads
+    loop {
+        let high = mmio_read(SYSTEM_TIMER_CHI);
+        let low = mmio_read(SYSTEM_TIMER_CLO);
+        if mmio_read(SYSTEM_TIMER_CHI) == high {
+            return ((high as u64) << 32) | low as u64;
+        }
+    }
+}
+
+/// Milliseconds since the timer started
+pub fn now_ms() -> u64 {
+    now_us() / 1000
+}
+
+/// Microseconds elapsed since an earlier `now_us` reading
+pub fn elapsed_us(since: u64) -> u64 {
+    now_us().saturating_sub(since)
+}
+
+/// Busy-wait for at least `us` microseconds
+pub fn delay_us(us: u32) {
+    let start = now_us();
+    while elapsed_us(start) < us as u64 {}
+}
+
+pub fn delay_ms(ms: u32) {
+    delay_us(ms.saturating_mul(1000));
+}
+
+/// Frame rate the main loop is paced to unless configured otherwise
+pub const DEFAULT_FPS: u32 = 60;
+
+/// Paces a render loop to a fixed frame interval and reports the real
+/// time between frames, for animation, alarms and trip integration
+pub struct FramePacer {
+    interval_us: u32,
+    /// Scheduled time of the last frame
+    last_frame_us: u64,
+    /// Sub-millisecond remainder carried over so `dt_ms` doesn't drift
+    carry_us: u64,
+}
+
+impl FramePacer {
+    pub fn new(fps: u32) -> Self {
+        FramePacer {
+            interval_us: 1_000_000 / fps.max(1),
+            last_frame_us: now_us(),
+            carry_us: 0,
+        }
+    }
+
+    pub fn set_fps(&mut self, fps: u32) {
+        self.interval_us = 1_000_000 / fps.max(1);
+    }
+
+    pub fn interval_us(&self) -> u32 {
+        self.interval_us
+    }
+
+    /// Sleep until one interval after the previous frame, then return the
+    /// milliseconds since it. Returns at once if the frame ran long
+    pub fn wait(&mut self) -> u32 {
+        let target = self.last_frame_us + self.interval_us as u64;
+        while now_us() < target {}
+        let now = now_us();
+
+        let elapsed = now - self.last_frame_us + self.carry_us;
+        self.carry_us = elapsed % 1000;
+
+        // Keep a steady cadence; after an overrun of a whole interval or
+        // more, restart from now instead of rushing frames to catch up
+        self.last_frame_us = if now - target < self.interval_us as u64 { target } else { now };
+        (elapsed / 1000) as u32
+    }
+}
+
+impl Default for FramePacer {
+    fn default() -> Self {
+        Self::new(DEFAULT_FPS)
+    }
+}
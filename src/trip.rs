@@ -0,0 +1,113 @@
+/// Trip meter integrating vehicle speed over elapsed time
+/// There is no RTC, so distance relies entirely on the `dt_ms` passed in
+
+use crate::framebuffer::Framebuffer;
+use crate::colors::Color;
+use crate::digit_renderer::{draw_float, draw_str, str_width};
+use crate::units::{DistanceUnit, convert_distance};
+
+const MS_PER_HOUR: f64 = 3_600_000.0;
+
+/// Whole-number digits in the readout (up to 9999.9)
+const INTEGER_DIGITS: u32 = 4;
+
+pub struct TripMeter {
+    /// Unit of the speed passed to `update` (per hour)
+    pub speed_unit: DistanceUnit,
+    /// Unit `distance` reports and `render` shows
+    pub display_unit: DistanceUnit,
+    /// Accumulated distance in `speed_unit`
+    /// f64 so per-frame increments aren't lost once the total gets large
+    distance: f64,
+}
+
+impl TripMeter {
+    pub const fn new(speed_unit: DistanceUnit, display_unit: DistanceUnit) -> Self {
+        TripMeter {
+            speed_unit,
+            display_unit,
+            distance: 0.0,
+        }
+    }
+
+    /// Add the distance covered at `speed` over `dt_ms`
+    pub fn update(&mut self, speed: f32, dt_ms: u32) {
+        // Ignore negative or garbage readings
+        if speed.is_nan() || speed <= 0.0 {
+            return;
+        }
+        self.distance += speed as f64 * dt_ms as f64 / MS_PER_HOUR;
+    }
+
+    /// Distance since the last reset, in `display_unit`
+    pub fn distance(&self) -> f32 {
+        convert_distance(self.distance as f32, self.speed_unit, self.display_unit)
+    }
+
+    pub fn reset(&mut self) {
+        self.distance = 0.0;
+    }
+
+    /// Continue from a previously saved distance, given in `display_unit`
+    pub fn restore(&mut self, distance: f32) {
+        if distance > 0.0 {
+            self.distance = convert_distance(distance, self.display_unit, self.speed_unit) as f64;
+        }
+    }
+
+    /// Draw as a digital readout with one decimal, followed by the unit label
+    pub fn render(&self, fb: &mut Framebuffer, x: u32, y: u32, digit_size: u32, color: Color) {
+        draw_float(fb, self.distance(), INTEGER_DIGITS, 1, x, y, digit_size, color);
+
+        let scale = label_scale(digit_size);
+        let label_y = y + digit_height(digit_size) - 7 * scale;
+        draw_str(fb, self.display_unit.label(), x + label_offset(digit_size), label_y, scale, color);
+    }
+
+    /// Width and height `render` covers at `digit_size`
+    pub fn readout_size(&self, digit_size: u32) -> (u32, u32) {
+        let label_width = str_width(self.display_unit.label(), label_scale(digit_size));
+        (label_offset(digit_size) + label_width, digit_height(digit_size))
+    }
+}
+
+/// Offset of the unit label, using the same layout arithmetic as draw_float:
+/// integer cells, point, one decimal
+fn label_offset(digit_size: u32) -> u32 {
+    let digit_width = digit_size + digit_size / 2;
+    INTEGER_DIGITS * digit_width + digit_width / 2 + digit_width + digit_size / 2
+}
+
+fn label_scale(digit_size: u32) -> u32 {
+    (digit_size / 4).max(1)
+}
+
+/// Seven-segment digit height: two segments plus the bar thickness
+fn digit_height(digit_size: u32) -> u32 {
+    digit_size * 2 + digit_size / 2
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::colors::colors;
+    use crate::framebuffer::host_framebuffer;
+
+    #[test]
+    fn readout_stays_within_its_reported_size() {
+        let mut trip = TripMeter::new(DistanceUnit::Miles, DistanceUnit::Kilometers);
+        trip.restore(8888.8);
+        let (width, height) = trip.readout_size(8);
+
+        let mut fb = host_framebuffer(width + 20, height + 20);
+        trip.render(&mut fb, 10, 10, 8, colors::WHITE);
+        for y in 0..fb.height() {
+            for x in 0..fb.width() {
+                let inside = (10..10 + width).contains(&x) && (10..10 + height).contains(&y);
+                if !inside {
+                    assert_eq!(fb.get_pixel(x, y), Some(0), "pixel drawn at {x},{y}");
+                }
+            }
+        }
+    }
+}
@@ -4,16 +4,153 @@
 
 use crate::framebuffer::Framebuffer;
 use crate::ts_ini_parser::GaugeConfig;
-use crate::colors::{Color, get_gauge_color, colors};
-use crate::math::sin;
+use crate::colors::{Color, GaugeStatus, Theme, cold_band_status, get_gauge_color_smooth, get_gauge_status, get_gauge_status_hysteresis};
+use crate::math::{sin, cos, exp, ln, abs, floor, sqrt};
+use crate::units::UnitConversion;
+use crate::digit_renderer::{char_advance, draw_str, str_width};
 use core::f32::consts::PI;
 
+/// Space reserved below each gauge for its title (2px gap + 10px strip)
+const TITLE_HEIGHT: u32 = 12;
+
+/// Why a gauge placement was rejected
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum LayoutError {
+    /// Width or height is zero
+    EmptyArea,
+    /// The gauge, including its title strip, extends past the screen edge
+    OutOfBounds,
+    /// Two gauges share pixels (indices into the layout being checked)
+    Overlap { first: usize, second: usize },
+}
+
+impl LayoutError {
+    /// Short description for on-screen and UART error reports
+    pub fn message(&self) -> &'static str {
+        match self {
+            LayoutError::EmptyArea => "GAUGE HAS NO AREA",
+            LayoutError::OutOfBounds => "GAUGE OFF SCREEN",
+            LayoutError::Overlap { .. } => "GAUGES OVERLAP",
+        }
+    }
+}
+
 #[derive(Clone, Copy, Debug)]
 pub enum TSGaugeStyle {
     Circular,       // Analog needle gauge
     HorizontalBar,  // Left-to-right bar
     VerticalBar,    // Bottom-to-top bar
     Digital,        // Large numeric display
+    Arc,            // Ring that fills along the sweep
+}
+
+impl TSGaugeStyle {
+    /// Parse a layout style keyword ("circular", "hbar", "vbar", "digital", "arc")
+    pub fn from_str(s: &str) -> Option<Self> {
+        let s = s.trim();
+        if s.eq_ignore_ascii_case("circular") {
+            Some(TSGaugeStyle::Circular)
+        } else if s.eq_ignore_ascii_case("hbar") {
+            Some(TSGaugeStyle::HorizontalBar)
+        } else if s.eq_ignore_ascii_case("vbar") {
+            Some(TSGaugeStyle::VerticalBar)
+        } else if s.eq_ignore_ascii_case("digital") {
+            Some(TSGaugeStyle::Digital)
+        } else if s.eq_ignore_ascii_case("arc") {
+            Some(TSGaugeStyle::Arc)
+        } else {
+            None
+        }
+    }
+}
+
+/// How bar gauges show the filled portion
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BarStyle {
+    /// One continuous fill in the current zone color
+    Solid,
+    /// Discrete LED-style blocks, each colored by the zone it sits in
+    Segmented,
+}
+
+impl BarStyle {
+    /// Parse a bar style keyword ("solid", "segmented"/"led")
+    pub fn from_str(s: &str) -> Option<Self> {
+        let s = s.trim();
+        if s.eq_ignore_ascii_case("solid") {
+            Some(BarStyle::Solid)
+        } else if s.eq_ignore_ascii_case("segmented") || s.eq_ignore_ascii_case("led") {
+            Some(BarStyle::Segmented)
+        } else {
+            None
+        }
+    }
+}
+
+/// Horizontal alignment of the gauge title
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TitleAlign {
+    Left,
+    Center,
+    Right,
+}
+
+impl TitleAlign {
+    /// Parse "left", "center"/"centre" or "right"
+    pub fn from_str(s: &str) -> Option<Self> {
+        let s = s.trim();
+        if s.eq_ignore_ascii_case("left") {
+            Some(TitleAlign::Left)
+        } else if s.eq_ignore_ascii_case("center") || s.eq_ignore_ascii_case("centre") {
+            Some(TitleAlign::Center)
+        } else if s.eq_ignore_ascii_case("right") {
+            Some(TitleAlign::Right)
+        } else {
+            None
+        }
+    }
+}
+
+/// Where the title sits relative to the gauge
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TitlePosition {
+    /// Strip above the gauge (counts toward its bounds)
+    Above,
+    /// Strip below the gauge (counts toward its bounds)
+    Below,
+    /// Along the top edge inside the gauge; takes no extra space
+    Inside,
+}
+
+impl TitlePosition {
+    /// Parse "above", "below" or "inside"
+    pub fn from_str(s: &str) -> Option<Self> {
+        let s = s.trim();
+        if s.eq_ignore_ascii_case("above") {
+            Some(TitlePosition::Above)
+        } else if s.eq_ignore_ascii_case("below") {
+            Some(TitlePosition::Below)
+        } else if s.eq_ignore_ascii_case("inside") {
+            Some(TitlePosition::Inside)
+        } else {
+            None
+        }
+    }
+}
+
+/// Inset of the title text from the gauge's left/right edge
+const TITLE_PADDING: u32 = 2;
+
+/// Gap between segments of a segmented bar, in pixels
+const SEGMENT_GAP: u32 = 2;
+
+/// Mapping from value to scale position
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ScaleType {
+    Linear,
+    /// More resolution at the low end. Ranges with `lo <= 0` are shifted so
+    /// `lo` maps to ln(1) (i.e. `ln(1 + value - lo)`)
+    Logarithmic,
 }
 
 pub struct TSGauge {
@@ -27,10 +164,88 @@ pub struct TSGauge {
     pub last_rendered_value: f32,
     pub animation_progress: f32,
     pub dirty: bool,
+    /// Number of labelled major divisions on circular gauges
+    pub major_ticks: u8,
+    /// Number of minor divisions between each pair of major ticks
+    pub minor_ticks: u8,
+    /// Needle angle at `config.lo`, in degrees (0° = 3 o'clock, negative = counter-clockwise)
+    pub start_angle: f32,
+    /// Total needle travel from `config.lo` to `config.hi`, in degrees
+    pub sweep_angle: f32,
+    /// Second value on the same scale (e.g. target boost), drawn as a thin
+    /// needle on circular gauges; set with `set_secondary_value`
+    pub secondary_value: Option<f32>,
+    /// Highest value seen since the last `reset_peak`
+    pub peak_value: f32,
+    /// Lowest value seen this session
+    pub session_min: f32,
+    /// Highest value seen this session
+    pub session_max: f32,
+    /// Show session min/max as small numbers in the top corners
+    pub show_minmax: bool,
+    /// Draw the numeric value over bar, circular and arc gauges
+    pub show_value: bool,
+    /// Draw the threshold zones as a colored band on circular dials
+    pub show_zones: bool,
+    /// Horizontal alignment of the title text
+    pub title_align: TitleAlign,
+    /// Title above, below or inside the gauge
+    pub title_position: TitlePosition,
+    /// Solid or segmented fill on bar gauges
+    pub bar_style: BarStyle,
+    /// Rounded corners on bar and digital gauge frames
+    pub rounded_frame: bool,
+    /// Number of blocks in a segmented bar (reduced if the bar is too short)
+    pub bar_segments: u8,
+    /// Anti-alias the needle and arc edges (costs a read-modify-write per edge pixel)
+    pub smooth_edges: bool,
+    /// Redraw at most every Nth frame (1 = every frame), so slow channels
+    /// such as coolant don't cost a redraw each frame; see `render_on_frame`
+    pub refresh_divider: u32,
+    /// Time taken to animate from the previous value to a new one
+    pub animation_duration_ms: u32,
+    /// Blend between zone colors instead of switching at thresholds
+    pub smooth_colors: bool,
+    /// Color by position on the scale (blue=lo to red=hi) instead of threshold zones
+    pub heat_colors: bool,
+    /// Show the low warning zone as a blue "cold" band (status `Cold`)
+    /// instead of a warning; meant for temperature gauges
+    pub cold_band: bool,
+    /// Color by distance from `target` instead of threshold zones: normal
+    /// within `tolerance`, warning within twice that, danger beyond
+    /// (e.g. closed-loop AFR against the ECU's target). Needs a target set
+    pub target_colors: bool,
+    /// Value the gauge is compared against; set with `set_target`
+    pub target: Option<f32>,
+    /// Allowed deviation from `target`, in display units (converted along
+    /// with the scale by `set_display_unit`)
+    pub tolerance: f32,
+    /// Value-to-position mapping used by needle, fill and ticks
+    pub scale_type: ScaleType,
+    /// Conversion applied to incoming values; set with `set_display_unit`
+    pub display_unit: Option<UnitConversion>,
+    /// `config` as given, in the source unit, while a display unit is set
+    source_config: GaugeConfig,
+    /// Colors for zones, background, text and highlights
+    pub theme: Theme,
+    /// Zone used for coloring; only leaves a zone once the value is back past
+    /// the threshold by `status_hysteresis`
+    pub current_status: GaugeStatus,
+    /// Hysteresis margin as a fraction of the range (0 = switch exactly at thresholds)
+    pub status_hysteresis: f32,
+    /// Smallest change (in display units) that triggers a redraw; defaults to 1% of the range
+    pub update_threshold: f32,
+    /// Exponential moving average on incoming values (0 = off, toward 1 = heavy)
+    /// Filters the signal itself, unlike `animation_duration_ms` which only eases the needle
+    pub smoothing: f32,
+    filtered_value: Option<f32>,
 }
 
 impl TSGauge {
-    pub fn new(
+    /// Create a gauge. Inverted scales aren't supported: a config with
+    /// `hi <= lo` (including `hi == lo`) builds an error gauge that ignores
+    /// values and draws a "BAD RANGE" placeholder; see `has_valid_range`
+    pub const fn new(
         config: GaugeConfig,
         style: TSGaugeStyle,
         x: u32,
@@ -38,6 +253,8 @@ impl TSGauge {
         width: u32,
         height: u32,
     ) -> Self {
+        let peak_value = config.lo;
+        let (session_min, session_max) = (config.hi, config.lo);
         TSGauge {
             config,
             style,
@@ -49,11 +266,111 @@ impl TSGauge {
             last_rendered_value: 0.0,
             animation_progress: 0.0,
             dirty: true,
+            major_ticks: 10,
+            minor_ticks: 5,
+            start_angle: -180.0,
+            sweep_angle: 180.0,
+            secondary_value: None,
+            peak_value,
+            session_min,
+            session_max,
+            show_minmax: false,
+            show_value: true,
+            show_zones: true,
+            smooth_edges: false,
+            title_align: TitleAlign::Center,
+            title_position: TitlePosition::Below,
+            bar_style: BarStyle::Solid,
+            bar_segments: 20,
+            rounded_frame: false,
+            refresh_divider: 1,
+            animation_duration_ms: 200,
+            smooth_colors: false,
+            heat_colors: false,
+            cold_band: false,
+            target_colors: false,
+            target: None,
+            tolerance: 0.0,
+            scale_type: ScaleType::Linear,
+            display_unit: None,
+            source_config: config,
+            theme: Theme::DARK,
+            current_status: GaugeStatus::Normal,
+            status_hysteresis: 0.02,
+            update_threshold: (config.hi - config.lo) * 0.01,
+            smoothing: 0.0,
+            filtered_value: None,
         }
     }
 
-    /// Set gauge value and mark as dirty if changed
+    /// Set the needle sweep for circular gauges (e.g. `with_sweep(-225.0, 270.0)` for a tachometer)
+    pub fn with_sweep(mut self, start_angle: f32, sweep_angle: f32) -> Self {
+        self.start_angle = start_angle;
+        self.sweep_angle = sweep_angle;
+        self
+    }
+
+    /// Display values in a different unit than they are supplied in.
+    /// The scale and thresholds are converted along with the value so color
+    /// zones keep matching; `config` must still be in the source unit.
+    /// Calling it again replaces the conversion: everything is converted
+    /// from the source unit, never from the previous display unit
+    pub fn set_display_unit(&mut self, conversion: UnitConversion) {
+        if self.display_unit == Some(conversion) {
+            return;
+        }
+
+        match self.display_unit {
+            Some(previous) => {
+                self.convert_state(previous.inverse(), self.config.lo);
+                self.config = self.source_config;
+            }
+            None => self.source_config = self.config,
+        }
+        self.convert_state(conversion, self.config.lo);
+
+        self.config.convert_scale(&conversion);
+        self.config.set_units(conversion.label());
+
+        self.display_unit = Some(conversion);
+        // Filter history is in the old unit
+        self.filtered_value = None;
+        self.dirty = true;
+    }
+
+    /// Convert every stored value with `conversion`; `lo` is the bottom of
+    /// the scale in the unit being converted from
+    fn convert_state(&mut self, conversion: UnitConversion, lo: f32) {
+        // Keep the dead-band the same physical size in the new unit
+        self.update_threshold = abs(conversion.apply(lo + self.update_threshold) - conversion.apply(lo));
+        self.tolerance = abs(conversion.apply(lo + self.tolerance) - conversion.apply(lo));
+
+        self.current_value = conversion.apply(self.current_value);
+        self.last_rendered_value = conversion.apply(self.last_rendered_value);
+        self.peak_value = conversion.apply(self.peak_value);
+        self.secondary_value = self.secondary_value.map(|value| conversion.apply(value));
+        self.target = self.target.map(|value| conversion.apply(value));
+        self.session_min = conversion.apply(self.session_min);
+        self.session_max = conversion.apply(self.session_max);
+    }
+
+    /// False when the scale is empty or inverted (`hi <= lo`, or NaN)
+    pub fn has_valid_range(&self) -> bool {
+        self.config.hi > self.config.lo
+    }
+
+    /// Set gauge value (in the source unit) and mark as dirty if changed
+    /// Ignored on a gauge without a valid range
     pub fn set_value(&mut self, value: f32) {
+        if !self.has_valid_range() {
+            return;
+        }
+        let value = match self.display_unit {
+            Some(conversion) => conversion.apply(value),
+            None => value,
+        };
+        let value = self.filter(value);
+
         // Clamp to min/max range
         let clamped = if value < self.config.lo {
             self.config.lo
@@ -63,16 +380,154 @@ impl TSGauge {
             value
         };
 
-        // Mark dirty if value changed significantly (>1% of range)
-        let range = self.config.hi - self.config.lo;
-        let change_threshold = range * 0.01;
-        
-        if (clamped - self.current_value).abs() > change_threshold {
+        // Mark dirty only if the value moved beyond the dead-band
+        if (clamped - self.current_value).abs() > self.update_threshold {
+            // Restart animation from wherever the needle currently is
+            self.last_rendered_value = self.get_animated_value();
             self.dirty = true;
             self.animation_progress = 0.0;
         }
 
+        // Track peak-hold value
+        if clamped > self.peak_value {
+            self.peak_value = clamped;
+            self.dirty = true;
+        }
+
+        // Track session extremes
+        if clamped < self.session_min {
+            self.session_min = clamped;
+            self.dirty |= self.show_minmax;
+        }
+        if clamped > self.session_max {
+            self.session_max = clamped;
+            self.dirty |= self.show_minmax;
+        }
+
         self.current_value = clamped;
+        self.update_status();
+    }
+
+    /// Set or clear the secondary value (in the source unit, like `set_value`)
+    /// Not filtered or animated; it is usually a target that moves in steps
+    pub fn set_secondary_value(&mut self, value: Option<f32>) {
+        let value = value.map(|value| {
+            let value = match self.display_unit {
+                Some(conversion) => conversion.apply(value),
+                None => value,
+            };
+            value.max(self.config.lo).min(self.config.hi)
+        });
+
+        let changed = match (value, self.secondary_value) {
+            (Some(new), Some(old)) => (new - old).abs() > self.update_threshold,
+            (None, None) => false,
+            _ => true,
+        };
+        if changed {
+            self.secondary_value = value;
+            self.dirty = true;
+        }
+    }
+
+    /// Set or clear the target (in the source unit, like `set_value`) used by
+    /// `target_colors`
+    pub fn set_target(&mut self, target: Option<f32>) {
+        let target = target.map(|value| match self.display_unit {
+            Some(conversion) => conversion.apply(value),
+            None => value,
+        });
+        if target != self.target {
+            self.target = target;
+            self.dirty |= self.target_colors;
+        }
+    }
+
+    /// Re-evaluate `current_status` for the current value, with hysteresis
+    fn update_status(&mut self) {
+        let margin = (self.config.hi - self.config.lo) * self.status_hysteresis;
+        let status = get_gauge_status_hysteresis(
+            self.current_value,
+            self.config.lo_danger,
+            self.config.lo_warning,
+            self.config.hi_warning,
+            self.config.hi_danger,
+            self.current_status,
+            margin,
+        );
+        let status = if self.cold_band {
+            cold_band_status(status, self.current_value, self.config.lo_warning, self.config.hi_warning)
+        } else {
+            status
+        };
+        if status != self.current_status {
+            self.current_status = status;
+            self.dirty = true;
+        }
+    }
+
+    /// Low-pass the incoming signal; passes values straight through when smoothing is 0
+    fn filter(&mut self, value: f32) -> f32 {
+        if self.smoothing <= 0.0 {
+            self.filtered_value = None;
+            return value;
+        }
+        // Keep at least a little of each new sample so the output can't freeze
+        let alpha = 1.0 - self.smoothing.min(0.99);
+        let filtered = match self.filtered_value {
+            Some(previous) => previous + alpha * (value - previous),
+            None => value,
+        };
+        self.filtered_value = Some(filtered);
+        filtered
+    }
+
+    /// Show the startup sweep: lo to hi over progress 0.0-0.5, back to lo by 1.0
+    /// Drives the displayed value directly, so peak, min/max and smoothing
+    /// are untouched; the next `set_value` takes over from wherever it stopped
+    pub fn run_selftest(&mut self, progress: f32) {
+        let progress = progress.max(0.0).min(1.0);
+        let position = if progress < 0.5 { progress * 2.0 } else { (1.0 - progress) * 2.0 };
+        let value = self.value_at_normalized(position);
+
+        self.current_value = value;
+        self.last_rendered_value = value;
+        self.animation_progress = 1.0;
+        // Zone colors follow the sweep so the warning/danger colors get tested too
+        self.update_status();
+        self.dirty = true;
+    }
+
+    /// Start a new min/max session
+    pub fn reset_session(&mut self) {
+        self.session_min = self.config.hi;
+        self.session_max = self.config.lo;
+        self.dirty = true;
+    }
+
+    /// Clear the peak-hold value (e.g. between track runs)
+    pub fn reset_peak(&mut self) {
+        self.peak_value = self.current_value;
+        self.dirty = true;
+    }
+
+    /// Advance animation by elapsed time so motion speed is independent of frame rate
+    pub fn update_animation(&mut self, dt_ms: u32) {
+        if self.animation_progress >= 1.0 {
+            return;
+        }
+
+        if self.animation_duration_ms == 0 {
+            self.animation_progress = 1.0;
+        } else {
+            self.animation_progress += dt_ms as f32 / self.animation_duration_ms as f32;
+        }
+
+        if self.animation_progress >= 1.0 {
+            self.animation_progress = 1.0;
+            self.last_rendered_value = self.current_value;
+        }
+        self.dirty = true;
     }
 
     /// Get interpolated value for animation (0.0 to 1.0 progress)
@@ -84,28 +539,192 @@ impl TSGauge {
 
     /// Get gauge color based on current value
     pub fn get_color(&self) -> Color {
-        get_gauge_color(
+        if self.heat_colors {
+            return Color::from_heat(self.get_normalized_value(self.current_value));
+        }
+        if self.target_colors {
+            if let Some(status) = self.target_status() {
+                return self.theme.status_color(status);
+            }
+        }
+
+        // The cold band is a solid color even with smooth colors
+        if !self.smooth_colors || self.current_status == GaugeStatus::Cold {
+            return self.theme.status_color(self.current_status);
+        }
+        get_gauge_color_smooth(
             self.current_value,
             self.config.lo_danger,
             self.config.lo_warning,
             self.config.hi_warning,
             self.config.hi_danger,
+            &self.theme,
         )
     }
 
+    /// Zone by deviation from the target; None without a target
+    pub fn target_status(&self) -> Option<GaugeStatus> {
+        let deviation = abs(self.current_value - self.target?);
+        let tolerance = self.tolerance.max(0.0);
+        Some(if deviation <= tolerance {
+            GaugeStatus::Normal
+        } else if deviation <= tolerance * 2.0 {
+            GaugeStatus::Warning
+        } else {
+            GaugeStatus::Danger
+        })
+    }
+
+    /// Threshold zone of the current value, with hysteresis applied
+    pub fn get_status(&self) -> GaugeStatus {
+        self.current_status
+    }
+
+    /// Threshold zone of the current value without hysteresis
+    pub fn get_raw_status(&self) -> GaugeStatus {
+        let status = get_gauge_status(
+            self.current_value,
+            self.config.lo_danger,
+            self.config.lo_warning,
+            self.config.hi_warning,
+            self.config.hi_danger,
+        );
+        if self.cold_band {
+            cold_band_status(status, self.current_value, self.config.lo_warning, self.config.hi_warning)
+        } else {
+            status
+        }
+    }
+
     /// Normalize value to 0.0-1.0 range based on min/max
     pub fn get_normalized_value(&self, value: f32) -> f32 {
         let range = self.config.hi - self.config.lo;
         if range <= 0.0 {
             return 0.0;
         }
-        ((value - self.config.lo) / range).max(0.0).min(1.0)
+
+        let normalized = match self.scale_type {
+            ScaleType::Linear => (value - self.config.lo) / range,
+            ScaleType::Logarithmic => {
+                let lo = self.log_position(self.config.lo);
+                let hi = self.log_position(self.config.hi);
+                (self.log_position(value) - lo) / (hi - lo)
+            }
+        };
+        normalized.max(0.0).min(1.0)
+    }
+
+    /// Inverse of `get_normalized_value`: the value at a scale position
+    pub fn value_at_normalized(&self, normalized: f32) -> f32 {
+        let lo = self.config.lo;
+        let hi = self.config.hi;
+        match self.scale_type {
+            ScaleType::Linear => lo + (hi - lo) * normalized,
+            ScaleType::Logarithmic => {
+                let position = self.log_position(lo)
+                    + normalized * (self.log_position(hi) - self.log_position(lo));
+                if lo > 0.0 {
+                    exp(position)
+                } else {
+                    lo + exp(position) - 1.0
+                }
+            }
+        }
+    }
+
+    /// Position of a value on the logarithmic scale
+    fn log_position(&self, value: f32) -> f32 {
+        let lo = self.config.lo;
+        if lo > 0.0 {
+            ln(value.max(lo))
+        } else {
+            ln(1.0 + (value - lo).max(0.0))
+        }
+    }
+
+    /// Screen area the gauge draws into, as (x, y, width, height)
+    /// Includes the title strip above or below the gauge
+    pub fn bounds(&self) -> (u32, u32, u32, u32) {
+        match self.title_position {
+            TitlePosition::Below => (self.x, self.y, self.width, self.height + TITLE_HEIGHT),
+            TitlePosition::Above => {
+                let top = self.y.saturating_sub(TITLE_HEIGHT);
+                (self.x, top, self.width, self.height + (self.y - top))
+            }
+            TitlePosition::Inside => (self.x, self.y, self.width, self.height),
+        }
+    }
+
+    /// Whether a screen point falls on the gauge, including its title strip
+    pub fn contains(&self, px: u32, py: u32) -> bool {
+        let (x, y, width, height) = self.bounds();
+        // u64 so unvalidated gauges near u32::MAX can't wrap
+        let (px, py) = (px as u64, py as u64);
+        px >= x as u64 && px < x as u64 + width as u64 && py >= y as u64 && py < y as u64 + height as u64
+    }
+
+    /// Check the gauge fits on a `fb_width` x `fb_height` screen
+    pub fn validate(&self, fb_width: u32, fb_height: u32) -> Result<(), LayoutError> {
+        let (x, y, width, height) = self.bounds();
+        if self.width == 0 || self.height == 0 {
+            return Err(LayoutError::EmptyArea);
+        }
+        // A title above a gauge at the top edge would be cut off
+        if self.title_position == TitlePosition::Above && self.y < TITLE_HEIGHT {
+            return Err(LayoutError::OutOfBounds);
+        }
+        // checked_add so huge coordinates from a config file can't wrap around
+        let right = x.checked_add(width).ok_or(LayoutError::OutOfBounds)?;
+        let bottom = y.checked_add(height).ok_or(LayoutError::OutOfBounds)?;
+        if right > fb_width || bottom > fb_height {
+            return Err(LayoutError::OutOfBounds);
+        }
+        Ok(())
+    }
+
+    /// Check whether two gauges' bounds share any pixels
+    pub fn overlaps(&self, other: &TSGauge) -> bool {
+        let (ax, ay, aw, ah) = self.bounds();
+        let (bx, by, bw, bh) = other.bounds();
+        // u64 so the edges can't overflow even for unvalidated gauges
+        let (ax, ay, aw, ah) = (ax as u64, ay as u64, aw as u64, ah as u64);
+        let (bx, by, bw, bh) = (bx as u64, by as u64, bw as u64, bh as u64);
+        ax < bx + bw && bx < ax + aw && ay < by + bh && by < ay + ah
+    }
+
+    /// True while the value changed or the needle is still moving
+    pub fn needs_render(&self) -> bool {
+        self.dirty || self.animation_progress < 1.0
+    }
+
+    /// True when the gauge needs a redraw and `frame` falls on its refresh divider
+    pub fn due_on_frame(&self, frame: u32) -> bool {
+        self.needs_render() && (self.refresh_divider <= 1 || frame.is_multiple_of(self.refresh_divider))
+    }
+
+    /// Render only if `due_on_frame`; for loops that offer every gauge a
+    /// redraw each frame. Changes on skipped frames are drawn on the next due one
+    pub fn render_on_frame(&mut self, fb: &mut Framebuffer, frame: u32) {
+        if self.due_on_frame(frame) {
+            self.render(fb);
+        }
     }
 
     /// Render gauge to framebuffer
+    /// Drawing only - call `update_animation` to advance needle motion
     pub fn render(&mut self, fb: &mut Framebuffer) {
-        if !self.dirty && self.animation_progress >= 1.0 {
-            return; // Nothing to render
+        if !self.needs_render() {
+            return; // Nothing to render, leave the previous frame in place
+        }
+
+        // Clear only our own area so neighbouring gauges aren't touched
+        let (x, y, width, height) = self.bounds();
+        fb.draw_filled_rect(x, y, width, height, self.theme.background.to_u32());
+
+        if !self.has_valid_range() {
+            self.render_range_error(fb);
+            self.dirty = false;
+            return;
         }
 
         match self.style {
@@ -113,11 +732,15 @@ impl TSGauge {
             TSGaugeStyle::HorizontalBar => self.render_horizontal_bar(fb),
             TSGaugeStyle::VerticalBar => self.render_vertical_bar(fb),
             TSGaugeStyle::Digital => self.render_digital(fb),
+            TSGaugeStyle::Arc => self.render_arc(fb),
         }
 
-        self.animation_progress += 0.5; // Advance animation
+        if self.show_minmax {
+            self.draw_minmax(fb);
+        }
+
+        // Stay dirty until the animation has settled on the final value
         if self.animation_progress >= 1.0 {
-            self.last_rendered_value = self.current_value;
             self.dirty = false;
         }
     }
@@ -135,59 +758,366 @@ impl TSGauge {
 
         // Draw background circle
         if radius > 5.0 {
-            self.draw_circle(fb, center_x, center_y, (radius - 5.0) as u32, colors::DARK_GRAY.to_u32());
+            self.draw_circle(fb, center_x, center_y, (radius - 5.0) as u32, self.theme.track.to_u32());
         }
 
-        // Calculate needle angle: -180° to 0° for typical gauge
-        let normalized = self.get_normalized_value(self.get_animated_value());
-        let angle_degrees = -180.0 + (normalized * 180.0);
-        let angle_rad = angle_degrees * PI / 180.0;
+        if self.show_zones {
+            self.render_zones(fb, center_x as f32, center_y as f32, radius);
+        }
+
+        // Draw scale ticks and labels
+        self.draw_scale(fb, center_x, center_y, radius, color);
 
-        // Draw needle using sine/cosine
-        let needle_length = (radius * 0.75) as i32;
-        let cos_angle = sin(angle_rad + PI / 2.0); // cos = sin(x + π/2)
-        let sin_angle = sin(angle_rad);
-        
-        let needle_end_x = center_x as i32 + (cos_angle * needle_length as f32) as i32;
-        let needle_end_y = center_y as i32 + (sin_angle * needle_length as f32) as i32;
+        // Secondary needle first so the main needle stays on top where they cross
+        if let Some(secondary) = self.secondary_value {
+            let (dx, dy) = self.needle_endpoint(secondary, radius * 0.65);
+            self.draw_line_aa(
+                fb,
+                center_x as f32,
+                center_y as f32,
+                (center_x as i32 + dx) as f32,
+                (center_y as i32 + dy) as f32,
+                self.theme.accent,
+            );
+        }
+
+        // Calculate needle tip from the configured sweep
+        let needle_length = radius * 0.75;
+        let (dx, dy) = self.needle_endpoint(self.get_animated_value(), needle_length);
+        let needle_end_x = center_x as i32 + dx;
+        let needle_end_y = center_y as i32 + dy;
 
+        if self.smooth_edges {
+            self.draw_thick_line_aa(
+                fb,
+                center_x as f32,
+                center_y as f32,
+                needle_end_x as f32,
+                needle_end_y as f32,
+                color,
+            );
+        } else {
+            self.draw_line(
+                fb,
+                center_x as i32,
+                center_y as i32,
+                needle_end_x,
+                needle_end_y,
+                color.to_u32(),
+            );
+        }
+
+        // Draw peak-hold marker on the outer ring
+        let (px0, py0) = self.needle_endpoint(self.peak_value, radius - 10.0);
+        let (px1, py1) = self.needle_endpoint(self.peak_value, radius);
         self.draw_line(
             fb,
-            center_x as i32,
-            center_y as i32,
-            needle_end_x,
-            needle_end_y,
-            color.to_u32(),
+            center_x as i32 + px0,
+            center_y as i32 + py0,
+            center_x as i32 + px1,
+            center_y as i32 + py1,
+            self.theme.accent.to_u32(),
         );
 
         // Draw center dot
-        fb.draw_filled_rect(center_x - 3, center_y - 3, 6, 6, color.to_u32());
+        fb.draw_filled_rect(center_x.saturating_sub(3), center_y.saturating_sub(3), 6, 6, color.to_u32());
+
+        // Value readout below the needle pivot
+        if self.show_value {
+            let text_y = center_y + (radius * 0.4) as u32;
+            let digit_size = (self.width.min(self.height) / 30).max(2);
+            self.draw_value_text(fb, center_x, text_y, digit_size, self.theme.text);
+        }
 
         // Draw title below gauge
         self.draw_title(fb, color);
     }
 
+    /// Draw the normal/warning/danger ranges as a fixed colored band inside the rim
+    /// Drawn from the thresholds, so the redline shows even at low values
+    fn render_zones(&self, fb: &mut Framebuffer, center_x: f32, center_y: f32, radius: f32) {
+        let band_radius = radius - 4.0;
+        if band_radius <= 2.0 {
+            return;
+        }
+
+        // Threshold positions on the dial; strict comparisons below so a
+        // threshold sitting at lo or hi doesn't paint the very end
+        let lo_danger = self.get_normalized_value(self.config.lo_danger);
+        let lo_warning = self.get_normalized_value(self.config.lo_warning);
+        let hi_warning = self.get_normalized_value(self.config.hi_warning);
+        let hi_danger = self.get_normalized_value(self.config.hi_danger);
+
+        // Painted in increasing severity so danger wins where zones overlap
+        let (cx, cy) = (center_x as i32, center_y as i32);
+        let outer = (band_radius + 2.0) as u32;
+        let thickness = 4;
+        let zones = [
+            (0.0, 1.0, self.theme.normal),
+            (0.0, lo_warning, if self.cold_band { self.theme.cold } else { self.theme.warning }),
+            (hi_warning, 1.0, self.theme.warning),
+            (0.0, lo_danger, self.theme.danger),
+            (hi_danger, 1.0, self.theme.danger),
+        ];
+        for &(from, to, zone_color) in zones.iter() {
+            if to > from {
+                self.draw_scale_arc(fb, cx, cy, outer, from, to, thickness, zone_color);
+            }
+        }
+    }
+
+    /// Draw the part of the dial between two normalized scale positions as
+    /// an arc `thickness` pixels deep inside `radius`
+    fn draw_scale_arc(
+        &self,
+        fb: &mut Framebuffer,
+        cx: i32,
+        cy: i32,
+        radius: u32,
+        from: f32,
+        to: f32,
+        thickness: u32,
+        color: Color,
+    ) {
+        let a = self.normalized_to_angle(from);
+        let b = self.normalized_to_angle(to);
+        // A negative sweep runs counter-clockwise, which is the clockwise arc from b to a
+        let (start, end) = if a <= b { (a, b) } else { (b, a) };
+        fb.draw_arc(cx, cy, radius, start, end, thickness, color.to_u32());
+    }
+
+    /// Render arc gauge: the ring fills from the start angle up to the value
+    fn render_arc(&mut self, fb: &mut Framebuffer) {
+        let center_x = (self.x + self.width / 2) as f32;
+        let center_y = (self.y + self.height / 2) as f32;
+        let radius = (self.width.min(self.height) / 2) as f32 * 0.85;
+        let thickness = (radius * 0.15).max(2.0);
+        let ring_radius = radius - thickness / 2.0;
+        if ring_radius <= 0.0 {
+            return;
+        }
+
+        let color = self.get_color();
+        let filled = self.get_normalized_value(self.get_animated_value());
+
+        let (cx, cy) = (center_x as i32, center_y as i32);
+        let outer = (ring_radius + thickness / 2.0) as u32;
+        let depth = thickness as u32;
+        if filled < 1.0 {
+            self.draw_scale_arc(fb, cx, cy, outer, filled, 1.0, depth, self.theme.track);
+        }
+        if filled > 0.0 {
+            self.draw_scale_arc(fb, cx, cy, outer, 0.0, filled, depth, color);
+        }
+
+        // Feather the inner and outer edges of the ring
+        if self.smooth_edges {
+            // Short chords (about 2px) follow the curve closely enough
+            let sweep_radians = abs(self.sweep_angle) * PI / 180.0;
+            let steps = ((sweep_radians * (ring_radius + thickness / 2.0) / 2.0) as u32).max(1);
+            let edges = [ring_radius - thickness / 2.0, ring_radius + thickness / 2.0];
+            for &edge in edges.iter() {
+                let mut prev = (center_x + cos(self.normalized_to_angle(0.0)) * edge,
+                                center_y + sin(self.normalized_to_angle(0.0)) * edge);
+                for i in 1..=steps {
+                    let normalized = i as f32 / steps as f32;
+                    let angle = self.normalized_to_angle(normalized);
+                    let next = (center_x + cos(angle) * edge, center_y + sin(angle) * edge);
+                    let edge_color = if normalized <= filled && filled > 0.0 { color } else { self.theme.track };
+                    self.draw_line_aa(fb, prev.0, prev.1, next.0, next.1, edge_color);
+                    prev = next;
+                }
+            }
+        }
+
+        if self.show_value {
+            let digit_size = (self.width.min(self.height) / 30).max(2);
+            self.draw_value_text(fb, center_x as u32, center_y as u32, digit_size, self.theme.text);
+        }
+
+        // Draw title below gauge
+        self.draw_title(fb, color);
+    }
+
+    /// Convert a normalized value (0.0-1.0) to a needle angle in radians
+    /// Sweeps clockwise from `start_angle` (lo) through `sweep_angle` degrees (hi)
+    fn normalized_to_angle(&self, normalized: f32) -> f32 {
+        let angle_degrees = self.start_angle + (normalized * self.sweep_angle);
+        angle_degrees * PI / 180.0
+    }
+
+    /// Get the needle tip position for a value, relative to the gauge center
+    pub fn needle_endpoint(&self, value: f32, length: f32) -> (i32, i32) {
+        let angle = self.normalized_to_angle(self.get_normalized_value(value));
+        ((cos(angle) * length) as i32, (sin(angle) * length) as i32)
+    }
+
+    /// Draw tick marks around the dial and numeric labels at major ticks
+    fn draw_scale(&self, fb: &mut Framebuffer, center_x: u32, center_y: u32, radius: f32, color: Color) {
+        if self.major_ticks == 0 {
+            return;
+        }
+
+        let outer = radius - 8.0;
+        let major_len = radius * 0.12;
+        let minor_len = major_len * 0.5;
+        let minor_per_major = self.minor_ticks.max(1) as u32;
+        let total_ticks = self.major_ticks as u32 * minor_per_major;
+
+        for i in 0..=total_ticks {
+            let normalized = i as f32 / total_ticks as f32;
+            let angle = self.normalized_to_angle(normalized);
+            let (c, s) = (cos(angle), sin(angle));
+            let is_major = i % minor_per_major == 0;
+            let inner = if is_major { outer - major_len } else { outer - minor_len };
+
+            let x0 = center_x as i32 + (c * inner) as i32;
+            let y0 = center_y as i32 + (s * inner) as i32;
+            let x1 = center_x as i32 + (c * outer) as i32;
+            let y1 = center_y as i32 + (s * outer) as i32;
+            let tick_color = if is_major { self.theme.text } else { self.theme.muted_text() };
+            self.draw_line(fb, x0, y0, x1, y1, tick_color.to_u32());
+
+            if is_major {
+                let value = self.value_at_normalized(normalized);
+                self.draw_scale_label(fb, center_x, center_y, outer - major_len * 2.5, angle, value, color);
+            }
+        }
+    }
+
+    /// Draw a numeric scale label centered at the given angle and radius
+    fn draw_scale_label(
+        &self,
+        fb: &mut Framebuffer,
+        center_x: u32,
+        center_y: u32,
+        label_radius: f32,
+        angle: f32,
+        value: f32,
+        color: Color,
+    ) {
+        let digit_size = (self.width.min(self.height) / 60).max(2);
+        let decimals = self.config.value_decimals as u32;
+        let integer_digits = count_integer_digits(value);
+
+        // Approximate rendered width so the label is centered on its tick
+        let label_width = float_label_width(value, decimals, digit_size);
+        let label_height = digit_size * 2 + digit_size / 2;
+
+        let lx = center_x as i32 + (cos(angle) * label_radius) as i32 - (label_width / 2) as i32;
+        let ly = center_y as i32 + (sin(angle) * label_radius) as i32 - (label_height / 2) as i32;
+        if lx < 0 || ly < 0 {
+            return;
+        }
+
+        crate::digit_renderer::draw_float(
+            fb,
+            value,
+            integer_digits,
+            decimals,
+            lx as u32,
+            ly as u32,
+            digit_size,
+            color,
+        );
+    }
+
+    /// Draw the animated value centered on a point, using `config.value_decimals`
+    fn draw_value_text(&self, fb: &mut Framebuffer, center_x: u32, center_y: u32, digit_size: u32, color: Color) {
+        let value = self.get_animated_value();
+        let decimals = self.config.value_decimals as u32;
+        let text_width = float_label_width(value, decimals, digit_size);
+        let text_height = digit_size * 2 + digit_size / 2;
+
+        crate::digit_renderer::draw_float(
+            fb,
+            value,
+            count_integer_digits(value),
+            decimals,
+            center_x.saturating_sub(text_width / 2),
+            center_y.saturating_sub(text_height / 2),
+            digit_size,
+            color,
+        );
+    }
+
+    /// Draw session min (top-left) and max (top-right) in small digits
+    fn draw_minmax(&self, fb: &mut Framebuffer) {
+        // Nothing recorded yet
+        if self.session_min > self.session_max {
+            return;
+        }
+
+        let digit_size = (self.width.min(self.height) / 80).max(2);
+        let decimals = self.config.value_decimals as u32;
+        let inset = 6;
+
+        let min = self.session_min;
+        crate::digit_renderer::draw_float(
+            fb,
+            min,
+            count_integer_digits(min),
+            decimals,
+            self.x + inset,
+            self.y + inset,
+            digit_size,
+            self.theme.muted_text(),
+        );
+
+        let max = self.session_max;
+        let max_x = (self.x + self.width)
+            .saturating_sub(inset + float_label_width(max, decimals, digit_size))
+            .max(self.x);
+        crate::digit_renderer::draw_float(
+            fb,
+            max,
+            count_integer_digits(max),
+            decimals,
+            max_x,
+            self.y + inset,
+            digit_size,
+            self.theme.muted_text(),
+        );
+    }
+
     /// Render horizontal bar gauge
     fn render_horizontal_bar(&mut self, fb: &mut Framebuffer) {
         let color = self.get_color();
         let normalized = self.get_normalized_value(self.get_animated_value());
-        let fill_width = (self.width as f32 * normalized) as u32;
+        // Fill and peak marker share one mapping onto the inside of the border
+        let inner_w = self.width.saturating_sub(4);
+        let inner_h = self.height.saturating_sub(4);
+        let fill_width = (inner_w as f32 * normalized) as u32;
 
         // Draw border
-        fb.draw_rect(self.x, self.y, self.width, self.height, color.to_u32());
+        self.draw_frame(fb, 0, color);
 
         // Draw background
-        fb.draw_filled_rect(self.x + 2, self.y + 2, self.width - 4, self.height - 4, colors::DARK_GRAY.to_u32());
+        self.fill_frame(fb, 2, self.theme.track);
 
         // Draw fill
-        if fill_width > 0 {
-            fb.draw_filled_rect(
-                self.x + 2,
-                self.y + 2,
-                fill_width.saturating_sub(4),
-                self.height - 4,
-                color.to_u32(),
-            );
+        if self.bar_style == BarStyle::Segmented {
+            self.render_bar_segments(fb, true, normalized);
+        } else if fill_width > 0 {
+            fb.draw_filled_rect(self.x + 2, self.y + 2, fill_width, inner_h, color.to_u32());
+        }
+
+        // Draw peak-hold marker, ending where a fill to the peak would end
+        let peak_x = (inner_w as f32 * self.get_normalized_value(self.peak_value)) as u32;
+        fb.draw_filled_rect(
+            self.x + 2 + peak_x.saturating_sub(2),
+            self.y + 2,
+            2.min(inner_w),
+            inner_h,
+            self.theme.accent.to_u32(),
+        );
+
+        // Dark text where it sits over the fill, light text over the background
+        if self.show_value {
+            let center_x = self.x + self.width / 2;
+            let text_color = if fill_width > inner_w / 2 { self.theme.background } else { self.theme.text };
+            let digit_size = (self.width.min(self.height) / 4).clamp(2, 12);
+            self.draw_value_text(fb, center_x, self.y + self.height / 2, digit_size, text_color);
         }
 
         // Draw title
@@ -198,70 +1128,214 @@ impl TSGauge {
     fn render_vertical_bar(&mut self, fb: &mut Framebuffer) {
         let color = self.get_color();
         let normalized = self.get_normalized_value(self.get_animated_value());
-        let fill_height = (self.height as f32 * normalized) as u32;
+        let inner_w = self.width.saturating_sub(4);
+        let inner_h = self.height.saturating_sub(4);
+        let fill_height = (inner_h as f32 * normalized) as u32;
 
         // Draw border
-        fb.draw_rect(self.x, self.y, self.width, self.height, color.to_u32());
+        self.draw_frame(fb, 0, color);
 
         // Draw background
-        fb.draw_filled_rect(self.x + 2, self.y + 2, self.width - 4, self.height - 4, colors::DARK_GRAY.to_u32());
+        self.fill_frame(fb, 2, self.theme.track);
 
         // Draw fill from bottom up
-        if fill_height > 0 {
-            let fill_y = self.y + self.height.saturating_sub(2).saturating_sub(fill_height);
-            fb.draw_filled_rect(
-                self.x + 2,
-                fill_y,
-                self.width - 4,
-                fill_height.saturating_sub(4),
-                color.to_u32(),
-            );
+        if self.bar_style == BarStyle::Segmented {
+            self.render_bar_segments(fb, false, normalized);
+        } else if fill_height > 0 {
+            let fill_y = self.y + 2 + inner_h - fill_height;
+            fb.draw_filled_rect(self.x + 2, fill_y, inner_w, fill_height, color.to_u32());
+        }
+
+        if self.show_value {
+            let center_y = self.y + self.height / 2;
+            let text_color = if fill_height > inner_h / 2 { self.theme.background } else { self.theme.text };
+            let digit_size = (self.width.min(self.height) / 4).clamp(2, 12);
+            self.draw_value_text(fb, self.x + self.width / 2, center_y, digit_size, text_color);
         }
 
         // Draw title
         self.draw_title(fb, color);
     }
 
+    /// Corner radius for rounded frames, scaled to the gauge size
+    fn frame_radius(&self) -> u32 {
+        if self.rounded_frame {
+            (self.width.min(self.height) / 8).clamp(2, 12)
+        } else {
+            0
+        }
+    }
+
+    /// Outline of the gauge area shrunk by `inset` on every side
+    fn draw_frame(&self, fb: &mut Framebuffer, inset: u32, color: Color) {
+        let radius = self.frame_radius().saturating_sub(inset);
+        fb.draw_round_rect(
+            self.x + inset,
+            self.y + inset,
+            self.width.saturating_sub(inset * 2),
+            self.height.saturating_sub(inset * 2),
+            radius,
+            color.to_u32(),
+        );
+    }
+
+    /// Fill the gauge area shrunk by `inset` on every side
+    fn fill_frame(&self, fb: &mut Framebuffer, inset: u32, color: Color) {
+        let radius = self.frame_radius().saturating_sub(inset);
+        fb.draw_filled_round_rect(
+            self.x + inset,
+            self.y + inset,
+            self.width.saturating_sub(inset * 2),
+            self.height.saturating_sub(inset * 2),
+            radius,
+            color.to_u32(),
+        );
+    }
+
+    /// Draw the lit blocks of a segmented bar inside the border
+    /// Each block takes the zone color of the value at its center, so the
+    /// blocks past the warning threshold always light up yellow/red
+    fn render_bar_segments(&self, fb: &mut Framebuffer, horizontal: bool, normalized: f32) {
+        let inner_w = self.width.saturating_sub(4);
+        let inner_h = self.height.saturating_sub(4);
+        let length = if horizontal { inner_w } else { inner_h };
+
+        // Every block needs at least one pixel besides its gap
+        let max_segments = (length + SEGMENT_GAP) / (1 + SEGMENT_GAP);
+        let segments = (self.bar_segments as u32).min(max_segments);
+        if segments == 0 {
+            return;
+        }
+
+        let lit = (normalized * segments as f32 + 0.5) as u32;
+        for i in 0..lit.min(segments) {
+            let start = i * length / segments;
+            let mut end = (i + 1) * length / segments;
+            if i + 1 < segments {
+                end = end.saturating_sub(SEGMENT_GAP).max(start + 1);
+            }
+
+            let center = (i as f32 + 0.5) / segments as f32;
+            let color = if self.heat_colors {
+                Color::from_heat(center)
+            } else {
+                let status = get_gauge_status(
+                    self.value_at_normalized(center),
+                    self.config.lo_danger,
+                    self.config.lo_warning,
+                    self.config.hi_warning,
+                    self.config.hi_danger,
+                );
+                self.theme.status_color(status)
+            };
+
+            if horizontal {
+                fb.draw_filled_rect(self.x + 2 + start, self.y + 2, end - start, inner_h, color.to_u32());
+            } else {
+                // Segment 0 sits at the bottom
+                let y = self.y + 2 + inner_h - end;
+                fb.draw_filled_rect(self.x + 2, y, inner_w, end - start, color.to_u32());
+            }
+        }
+    }
+
     /// Render digital numeric display with colored border
     fn render_digital(&mut self, fb: &mut Framebuffer) {
         let color = self.get_color();
         let value = self.get_animated_value();
 
         // Draw colored border frame
-        fb.draw_rect(self.x, self.y, self.width, self.height, color.to_u32());
-        fb.draw_rect(
-            self.x + 1,
-            self.y + 1,
-            self.width.saturating_sub(2),
-            self.height.saturating_sub(2),
-            color.to_u32(),
-        );
+        self.draw_frame(fb, 0, color);
+        self.draw_frame(fb, 1, color);
 
         // Draw dark background
-        fb.draw_filled_rect(
-            self.x + 4,
-            self.y + 4,
-            self.width.saturating_sub(8),
-            self.height.saturating_sub(8),
-            colors::DARK_GRAY.to_u32(),
-        );
+        self.fill_frame(fb, 4, self.theme.track);
+
+        // Number followed by a smaller units label; shrink the digits until
+        // both fit, and give the number the full width when there are no units
+        let units = self.config.units_str();
+        // Integer field sized from the scale so the decimal point stays put as
+        // the value changes; widened only if the value runs past the scale
+        let decimals = self.config.value_decimals as u32;
+        let integer_digits = count_integer_digits(self.config.lo)
+            .max(count_integer_digits(self.config.hi))
+            .max(count_integer_digits(value));
+        let available = self.width.saturating_sub(20);
+        let mut digit_size = (self.height / 3).min(20);
+        let (mut units_scale, mut units_width) = (0, 0);
+        while digit_size > 2 {
+            units_scale = (digit_size / 5).max(1);
+            units_width = if units.is_empty() { 0 } else { str_width(units, units_scale) + digit_size / 2 };
+            if digital_number_width(value, integer_digits, decimals, digit_size) + units_width <= available {
+                break;
+            }
+            digit_size -= 1;
+        }
 
-        // Draw numeric value using digit renderer
-        let digit_size = (self.height / 3).min(20);
         let text_x = self.x + 10;
         let text_y = self.y + (self.height.saturating_sub(digit_size * 2)) / 2;
+        crate::digit_renderer::draw_float(fb, value, integer_digits, decimals, text_x, text_y, digit_size, color);
 
-        crate::digit_renderer::draw_float(fb, value, 4, 1, text_x, text_y, digit_size, color);
+        if units_width > 0 {
+            // Bottom-aligned with the digits
+            let units_x = text_x + digital_number_width(value, integer_digits, decimals, digit_size) + digit_size / 2;
+            let units_y = (text_y + digit_size * 2).saturating_sub(7 * units_scale);
+            draw_str(fb, units, units_x, units_y, units_scale, color);
+        }
 
         // Draw title
         self.draw_title(fb, color);
     }
 
-    /// Draw gauge title text (simplified - using rectangles as placeholder)
+    /// Placeholder for a gauge whose config has `hi <= lo`, so the mistake
+    /// is visible instead of a needle stuck at one end
+    fn render_range_error(&self, fb: &mut Framebuffer) {
+        let color = self.theme.danger;
+        self.draw_frame(fb, 0, color);
+        self.draw_frame(fb, 1, color);
+
+        let message = "BAD RANGE";
+        let scale = (self.width.saturating_sub(8) / str_width(message, 1)).clamp(1, 3);
+        let text_x = self.x + self.width.saturating_sub(str_width(message, scale)) / 2;
+        let text_y = self.y + self.height.saturating_sub(7 * scale) / 2;
+        draw_str(fb, message, text_x, text_y, scale, color);
+
+        self.draw_title(fb, color);
+    }
+
+    /// Draw the gauge title in its zone color, aligned and placed per
+    /// `title_align`/`title_position`; text too wide for the gauge is cut off
     fn draw_title(&self, fb: &mut Framebuffer, color: Color) {
-        // Placeholder: Draw a small rectangle below gauge for title area
-        let title_y = self.y + self.height + 2;
-        fb.draw_filled_rect(self.x, title_y, self.width, 10, colors::BLACK.to_u32());
+        let strip_height = TITLE_HEIGHT - 2;
+        let strip_y = match self.title_position {
+            TitlePosition::Below => self.y + self.height + 2,
+            TitlePosition::Above => self.y.saturating_sub(TITLE_HEIGHT),
+            TitlePosition::Inside => self.y + 4,
+        };
+        // Inside titles sit on the gauge face, which render() already cleared
+        if self.title_position != TitlePosition::Inside {
+            fb.draw_filled_rect(self.x, strip_y, self.width, strip_height, self.theme.background.to_u32());
+        }
+
+        let title = self.config.title_str();
+        let max_chars = (self.width.saturating_sub(TITLE_PADDING * 2) / char_advance(1)) as usize;
+        let title = match title.char_indices().nth(max_chars) {
+            Some((end, _)) => &title[..end],
+            None => title,
+        };
+        if title.is_empty() {
+            return;
+        }
+
+        let text_width = str_width(title, 1);
+        let text_x = match self.title_align {
+            TitleAlign::Left => self.x + TITLE_PADDING,
+            TitleAlign::Center => self.x + self.width.saturating_sub(text_width) / 2,
+            TitleAlign::Right => (self.x + self.width).saturating_sub(text_width + TITLE_PADDING),
+        };
+        // 7px font centered in the strip
+        let text_y = strip_y + (strip_height - 7) / 2;
+        draw_str(fb, title, text_x, text_y, 1, color);
     }
 
     /// Draw circle using Bresenham-style algorithm
@@ -277,8 +1351,8 @@ impl TSGauge {
 
         while x >= y {
             // Draw 8 symmetric points
-            self.draw_circle_point(fb, cx, cy, x as u32, y as u32, color);
-            self.draw_circle_point(fb, cx, cy, y as u32, x as u32, color);
+            self.draw_circle_point(fb, cx as i32, cy as i32, x, y, color);
+            self.draw_circle_point(fb, cx as i32, cy as i32, y, x, color);
 
             if decision_parameter <= 0 {
                 decision_parameter = decision_parameter + 4 * y + 6;
@@ -291,7 +1365,8 @@ impl TSGauge {
     }
 
     /// Draw circle points in 8 symmetric positions
-    fn draw_circle_point(&self, fb: &mut Framebuffer, cx: u32, cy: u32, x: u32, y: u32, color: u32) {
+    /// Signed so circles crossing the left/top edge clip instead of wrapping
+    fn draw_circle_point(&self, fb: &mut Framebuffer, cx: i32, cy: i32, x: i32, y: i32, color: u32) {
         let points = [
             (cx + x, cy + y),
             (cx - x, cy + y),
@@ -303,13 +1378,54 @@ impl TSGauge {
             (cx - y, cy - x),
         ];
 
-        for (px, py) in &points {
-            if *px < 1280 && *py < 720 {
-                fb.draw_filled_rect(*px - 1, *py - 1, 2, 2, color);
-            }
+        for &(px, py) in &points {
+            plot_block(fb, px - 1, py - 1, color);
         }
     }
 
+    /// Draw an anti-aliased line using Wu's algorithm
+    /// Each step covers two pixels across the line, weighted by coverage
+    fn draw_line_aa(&self, fb: &mut Framebuffer, x0: f32, y0: f32, x1: f32, y1: f32, color: Color) {
+        let steep = abs(y1 - y0) > abs(x1 - x0);
+        // Work along the major axis; swap back when plotting
+        let (mut x0, mut y0, mut x1, mut y1) = if steep { (y0, x0, y1, x1) } else { (x0, y0, x1, y1) };
+        if x0 > x1 {
+            core::mem::swap(&mut x0, &mut x1);
+            core::mem::swap(&mut y0, &mut y1);
+        }
+
+        let dx = x1 - x0;
+        let gradient = if dx == 0.0 { 1.0 } else { (y1 - y0) / dx };
+
+        let x_start = floor(x0 + 0.5) as i32;
+        let x_end = floor(x1 + 0.5) as i32;
+        let mut intery = y0 + gradient * (x_start as f32 - x0);
+
+        for x in x_start..=x_end {
+            let y = floor(intery);
+            let coverage = intery - y;
+            let y = y as i32;
+            let (a, b) = if steep { ((y, x), (y + 1, x)) } else { ((x, y), (x, y + 1)) };
+            plot_aa(fb, a.0, a.1, color, 1.0 - coverage);
+            plot_aa(fb, b.0, b.1, color, coverage);
+            intery += gradient;
+        }
+    }
+
+    /// Anti-aliased line about 2px wide: two AA lines offset across its direction
+    fn draw_thick_line_aa(&self, fb: &mut Framebuffer, x0: f32, y0: f32, x1: f32, y1: f32, color: Color) {
+        let (dx, dy) = (x1 - x0, y1 - y0);
+        let length = sqrt(dx * dx + dy * dy);
+        if length == 0.0 {
+            plot_aa(fb, x0 as i32, y0 as i32, color, 1.0);
+            return;
+        }
+        // Unit normal scaled to half a pixel
+        let (nx, ny) = (-dy / length * 0.5, dx / length * 0.5);
+        self.draw_line_aa(fb, x0 + nx, y0 + ny, x1 + nx, y1 + ny, color);
+        self.draw_line_aa(fb, x0 - nx, y0 - ny, x1 - nx, y1 - ny, color);
+    }
+
     /// Draw line using Bresenham algorithm
     fn draw_line(&self, fb: &mut Framebuffer, x0: i32, y0: i32, x1: i32, y1: i32, color: u32) {
         let dx = (x1 - x0).abs();
@@ -321,9 +1437,7 @@ impl TSGauge {
         let mut y = y0;
 
         loop {
-            if x >= 0 && x < 1280 && y >= 0 && y < 720 {
-                fb.draw_filled_rect(x as u32, y as u32, 2, 2, color);
-            }
+            plot_block(fb, x, y, color);
 
             if x == x1 && y == y1 {
                 break;
@@ -341,3 +1455,207 @@ impl TSGauge {
         }
     }
 }
+
+/// Draw a 2x2 block at a signed position, clipped to the framebuffer
+fn plot_block(fb: &mut Framebuffer, x: i32, y: i32, color: u32) {
+    if x < -1 || y < -1 || x >= fb.width() as i32 || y >= fb.height() as i32 {
+        return;
+    }
+    // A block hanging off the left/top edge keeps its on-screen pixel
+    let (px, w) = if x < 0 { (0, 1) } else { (x as u32, 2) };
+    let (py, h) = if y < 0 { (0, 1) } else { (y as u32, 2) };
+    fb.draw_filled_rect(px, py, w, h, color);
+}
+
+/// Blend one pixel at a signed position with coverage 0.0-1.0
+fn plot_aa(fb: &mut Framebuffer, x: i32, y: i32, color: Color, coverage: f32) {
+    if x < 0 || y < 0 || coverage <= 0.0 {
+        return;
+    }
+    let alpha = (coverage.min(1.0) * 255.0) as u8;
+    fb.draw_pixel_blend(x as u32, y as u32, color.to_u32(), alpha);
+}
+
+/// Count digits in the integer part of a value (at least 1)
+fn count_integer_digits(value: f32) -> u32 {
+    let mut int_part = (if value < 0.0 { -value } else { value }) as u32;
+    let mut digits = 1;
+    while int_part >= 10 {
+        int_part /= 10;
+        digits += 1;
+    }
+    digits
+}
+
+/// Width `draw_float` uses with a fixed number of integer cells
+fn digital_number_width(value: f32, integer_digits: u32, decimals: u32, digit_size: u32) -> u32 {
+    let digit_width = digit_size + digit_size / 2;
+    let mut width = integer_digits * digit_width;
+    if value < 0.0 {
+        width += digit_width;
+    }
+    if decimals > 0 {
+        width += digit_width / 2 + decimals * digit_width;
+    }
+    width
+}
+
+/// Approximate width of a value drawn with `digit_renderer::draw_float`
+fn float_label_width(value: f32, decimals: u32, digit_size: u32) -> u32 {
+    digital_number_width(value, count_integer_digits(value), decimals, digit_size)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::framebuffer::host_framebuffer;
+    use crate::units::PressureUnit;
+
+    /// Config with the given scale and thresholds at 10/20/80/90% of it
+    fn config(lo: f32, hi: f32) -> GaugeConfig {
+        let mut config = GaugeConfig::new();
+        let span = hi - lo;
+        config.lo = lo;
+        config.hi = hi;
+        config.lo_danger = lo + span * 0.1;
+        config.lo_warning = lo + span * 0.2;
+        config.hi_warning = lo + span * 0.8;
+        config.hi_danger = lo + span * 0.9;
+        config
+    }
+
+    /// Pixel positions agree to within truncation
+    fn assert_near(actual: (i32, i32), expected: (i32, i32)) {
+        let close = (actual.0 - expected.0).abs() <= 1 && (actual.1 - expected.1).abs() <= 1;
+        assert!(close, "{:?} != {:?}", actual, expected);
+    }
+
+    fn pressure(to: PressureUnit) -> UnitConversion {
+        UnitConversion::Pressure { from: PressureUnit::Kpa, to }
+    }
+
+    #[test]
+    fn needle_endpoint_follows_270_degree_sweep() {
+        let gauge = TSGauge::new(config(0.0, 100.0), TSGaugeStyle::Circular, 0, 0, 200, 200)
+            .with_sweep(-225.0, 270.0);
+        // Midpoint points straight up, ends sit at lower left and lower right
+        assert_near(gauge.needle_endpoint(50.0, 100.0), (0, -100));
+        assert_near(gauge.needle_endpoint(0.0, 100.0), (-70, 70));
+        assert_near(gauge.needle_endpoint(100.0, 100.0), (70, 70));
+    }
+
+    #[test]
+    fn default_sweep_is_180_degrees() {
+        let gauge = TSGauge::new(config(0.0, 100.0), TSGaugeStyle::Circular, 0, 0, 200, 200);
+        assert_near(gauge.needle_endpoint(0.0, 100.0), (-100, 0));
+        assert_near(gauge.needle_endpoint(50.0, 100.0), (0, -100));
+        assert_near(gauge.needle_endpoint(100.0, 100.0), (100, 0));
+    }
+
+    /// Whether any pixel in columns `x_range` was drawn on a fresh framebuffer
+    fn any_drawn(fb: &Framebuffer, x_range: core::ops::Range<u32>) -> bool {
+        x_range.into_iter().any(|x| (0..fb.height()).any(|y| fb.get_pixel(x, y) != Some(0)))
+    }
+
+    #[test]
+    fn circular_gauge_near_left_edge_clips() {
+        let mut fb = host_framebuffer(1280, 720);
+        // 118 px square gives a 50 px dial radius
+        let mut gauge = TSGauge::new(config(0.0, 100.0), TSGaugeStyle::Circular, 10, 300, 118, 118);
+        gauge.set_value(30.0);
+        gauge.render(&mut fb);
+        assert!(any_drawn(&fb, 0..200));
+        assert!(!any_drawn(&fb, 1200..1280));
+    }
+
+    #[test]
+    fn circular_gauge_past_right_edge_does_not_wrap() {
+        let mut fb = host_framebuffer(1280, 720);
+        let mut gauge = TSGauge::new(config(0.0, 100.0), TSGaugeStyle::Circular, 1230, 300, 118, 118);
+        gauge.set_value(90.0);
+        gauge.render(&mut fb);
+        assert!(any_drawn(&fb, 1230..1280));
+        assert!(!any_drawn(&fb, 0..1100));
+    }
+
+    #[test]
+    fn equal_lo_and_hi_is_an_error_gauge() {
+        let mut gauge = TSGauge::new(config(50.0, 50.0), TSGaugeStyle::HorizontalBar, 0, 0, 200, 100);
+        assert!(!gauge.has_valid_range());
+        gauge.set_value(60.0);
+        assert_eq!(gauge.current_value, 0.0);
+        assert_eq!(gauge.get_normalized_value(60.0), 0.0);
+
+        // Rendered as a danger-colored placeholder instead of a bar
+        let mut fb = host_framebuffer(200, 100);
+        gauge.render(&mut fb);
+        let danger = gauge.theme.danger.to_u32();
+        let has_danger = (0..100).any(|y| (0..200).any(|x| fb.get_pixel(x, y) == Some(danger)));
+        assert!(has_danger);
+
+        let inverted = TSGauge::new(config(100.0, 0.0), TSGaugeStyle::Digital, 0, 0, 200, 100);
+        assert!(!inverted.has_valid_range());
+        let narrow = TSGauge::new(config(50.0, 50.001), TSGaugeStyle::Digital, 0, 0, 200, 100);
+        assert!(narrow.has_valid_range());
+    }
+
+    #[test]
+    fn peak_marker_ends_with_the_fill() {
+        let mut fb = host_framebuffer(120, 40);
+        let mut gauge = TSGauge::new(config(0.0, 100.0), TSGaugeStyle::HorizontalBar, 0, 0, 104, 20);
+        gauge.show_value = false;
+        gauge.set_value(50.0);
+        gauge.update_animation(10_000);
+        gauge.render(&mut fb);
+
+        // Inside of the border is x = 2..102, so 50% fills x = 2..52
+        let accent = gauge.theme.accent.to_u32();
+        let fill = gauge.get_color().to_u32();
+        assert_eq!(fb.get_pixel(49, 10), Some(fill));
+        assert_eq!(fb.get_pixel(50, 10), Some(accent));
+        assert_eq!(fb.get_pixel(51, 10), Some(accent));
+        assert_eq!(fb.get_pixel(52, 10), Some(gauge.theme.track.to_u32()));
+    }
+
+    #[test]
+    fn tiny_bars_render_without_underflow() {
+        let mut fb = host_framebuffer(20, 20);
+        for (width, height) in [(3, 3), (4, 2), (1, 1), (10, 0)] {
+            for style in [TSGaugeStyle::HorizontalBar, TSGaugeStyle::VerticalBar] {
+                let mut gauge = TSGauge::new(config(0.0, 100.0), style, 0, 0, width, height);
+                gauge.set_value(60.0);
+                gauge.update_animation(10_000);
+                gauge.render(&mut fb);
+            }
+        }
+    }
+
+    #[test]
+    fn tiny_circular_gauge_in_the_corner_renders() {
+        let mut fb = host_framebuffer(20, 20);
+        for size in [1, 4, 6] {
+            let mut gauge = TSGauge::new(config(0.0, 100.0), TSGaugeStyle::Circular, 0, 0, size, size);
+            gauge.set_value(60.0);
+            gauge.render(&mut fb);
+        }
+    }
+
+    #[test]
+    fn second_display_unit_converts_from_source() {
+        let mut direct = TSGauge::new(config(0.0, 300.0), TSGaugeStyle::Digital, 0, 0, 100, 100);
+        direct.set_display_unit(pressure(PressureUnit::Bar));
+        direct.set_value(150.0);
+
+        let mut gauge = TSGauge::new(config(0.0, 300.0), TSGaugeStyle::Digital, 0, 0, 100, 100);
+        gauge.set_display_unit(pressure(PressureUnit::Psi));
+        gauge.set_value(150.0);
+        gauge.set_display_unit(pressure(PressureUnit::Bar));
+
+        assert_eq!(gauge.config.hi, direct.config.hi);
+        assert_eq!(gauge.config.hi_danger, direct.config.hi_danger);
+        assert_eq!(gauge.config.units_str(), direct.config.units_str());
+        assert!((gauge.current_value - direct.current_value).abs() < 1e-4);
+        assert!((gauge.peak_value - direct.peak_value).abs() < 1e-4);
+        assert!((gauge.update_threshold - direct.update_threshold).abs() < 1e-4);
+    }
+}
@@ -2,10 +2,20 @@
 // Based on TunerStudio ECU Definition file specification
 
 use core::str;
+use crate::units::UnitConversion;
 
 /// Maximum number of gauge configurations
 pub const MAX_GAUGE_CONFIGS: usize = 64;
 
+/// Maximum number of output channel definitions
+pub const MAX_OUTPUT_CHANNELS: usize = 128;
+
+/// Maximum number of plain `key = value` settings kept by `IniParser`
+pub const MAX_INI_SETTINGS: usize = 32;
+
+/// Maximum number of [Layout] entries
+pub const MAX_LAYOUT_ENTRIES: usize = 16;
+
 /// Gauge configuration from TunerStudio INI [GaugeConfigurations] section
 /// Format: name = var, "title", "units", lo, hi, loD, loW, hiW, hiD, vd, ld
 #[derive(Copy, Clone, Debug)]
@@ -37,7 +47,7 @@ pub struct GaugeConfig {
 }
 
 impl GaugeConfig {
-    pub fn new() -> Self {
+    pub const fn new() -> Self {
         GaugeConfig {
             name: [0; 64],
             var: [0; 64],
@@ -73,6 +83,21 @@ impl GaugeConfig {
     pub fn units_str(&self) -> &str {
         str_from_bytes(&self.units)
     }
+
+    /// Replace the units label
+    pub fn set_units(&mut self, units: &str) {
+        copy_str_to_bytes(&mut self.units, units);
+    }
+
+    /// Convert the scale and every threshold; the units label is left alone
+    pub fn convert_scale(&mut self, conversion: &UnitConversion) {
+        self.lo = conversion.apply(self.lo);
+        self.hi = conversion.apply(self.hi);
+        self.lo_danger = conversion.apply(self.lo_danger);
+        self.lo_warning = conversion.apply(self.lo_warning);
+        self.hi_warning = conversion.apply(self.hi_warning);
+        self.hi_danger = conversion.apply(self.hi_danger);
+    }
 }
 
 impl Default for GaugeConfig {
@@ -98,18 +123,45 @@ fn copy_str_to_bytes(dest: &mut [u8], src: &str) {
     }
 }
 
-/// Parse a simple float from string (bare metal, no std)
+/// Drop a trailing `; comment` and surrounding whitespace, so
+/// `hi = 8000 ; redline` leaves `hi = 8000`
+/// A ';' inside double quotes is kept; a line that is only a comment (';' or
+/// '#' first) becomes empty
+pub fn strip_comment(line: &str) -> &str {
+    let line = line.trim();
+    if line.starts_with('#') {
+        return "";
+    }
+    let mut in_quotes = false;
+    for (i, b) in line.bytes().enumerate() {
+        match b {
+            b'"' => in_quotes = !in_quotes,
+            b';' if !in_quotes => return line[..i].trim_end(),
+            _ => {}
+        }
+    }
+    line
+}
+
+/// Parse a decimal number from string (bare metal, no std)
+/// A sign is only accepted as the first character, and the point may lead or
+/// trail ("-.5", "+12.0", "3.", ".25"). Parsing stops at the first character
+/// that can't continue the number, so "12.3.4" reads as 12.3
 fn parse_f32(s: &str) -> f32 {
     let s = s.trim();
+    let (is_negative, digits) = match s.as_bytes().first() {
+        Some(b'-') => (true, &s[1..]),
+        Some(b'+') => (false, &s[1..]),
+        _ => (false, s),
+    };
+
     let mut result: f32 = 0.0;
-    let mut is_negative = false;
     let mut is_fraction = false;
     let mut fraction_divisor: f32 = 10.0;
-    
-    for c in s.chars() {
+
+    for c in digits.chars() {
         match c {
-            '-' => is_negative = true,
-            '.' => is_fraction = true,
+            '.' if !is_fraction => is_fraction = true,
             '0'..='9' => {
                 let digit = (c as u8 - b'0') as f32;
                 if is_fraction {
@@ -122,7 +174,7 @@ fn parse_f32(s: &str) -> f32 {
             _ => break,
         }
     }
-    
+
     if is_negative {
         -result
     } else {
@@ -130,29 +182,39 @@ fn parse_f32(s: &str) -> f32 {
     }
 }
 
-/// Parse a simple u8 from string
-fn parse_u8(s: &str) -> u8 {
+/// Parse a numeric field, rejecting anything that isn't entirely a decimal
+/// number (empty, non-numeric such as `{expr}`, or trailing junk)
+pub fn parse_number(s: &str) -> Option<f32> {
     let s = s.trim();
-    let mut result: u8 = 0;
-    
-    for c in s.chars() {
-        if let Some(digit) = c.to_digit(10) {
-            result = result * 10 + digit as u8;
-        } else {
-            break;
+    let digits = s.strip_prefix(|c| c == '-' || c == '+').unwrap_or(s);
+    let mut seen_digit = false;
+    let mut seen_point = false;
+    for c in digits.chars() {
+        match c {
+            '0'..='9' => seen_digit = true,
+            '.' if !seen_point => seen_point = true,
+            _ => return None,
         }
     }
-    
-    result
+    if seen_digit {
+        Some(parse_f32(s))
+    } else {
+        None
+    }
 }
 
+/// Fields a gauge line must have (var through hiD); the decimal counts are optional
+const GAUGE_REQUIRED_FIELDS: usize = 9;
+
 /// Parse a gauge configuration line from INI file
 /// Format: name = var, "title", "units", lo, hi, loD, loW, hiW, hiD, vd, ld
+/// Returns None for lines with too few fields, a non-numeric number field or
+/// an unterminated quote. Trailing commas and extra whitespace are accepted
 pub fn parse_gauge_line(line: &str) -> Option<GaugeConfig> {
-    let line = line.trim();
+    let line = strip_comment(line);
     
     // Skip comments and empty lines
-    if line.is_empty() || line.starts_with(';') || line.starts_with('#') {
+    if line.is_empty() {
         return None;
     }
     
@@ -170,8 +232,12 @@ pub fn parse_gauge_line(line: &str) -> Option<GaugeConfig> {
         return None;
     }
     
+    let name = parts[0].0.trim();
+    if name.is_empty() {
+        return None;
+    }
     let mut config = GaugeConfig::new();
-    copy_str_to_bytes(&mut config.name, parts[0].0.trim());
+    copy_str_to_bytes(&mut config.name, name);
     
     // Parse right side (comma-separated)
     let values = parts[1].0;
@@ -183,7 +249,9 @@ pub fn parse_gauge_line(line: &str) -> Option<GaugeConfig> {
         if c == '"' {
             in_quotes = !in_quotes;
         } else if c == ',' && !in_quotes {
-            process_field(&mut config, field_index, current_field.as_str());
+            if !process_field(&mut config, field_index, current_field.as_str()) {
+                return None;
+            }
             field_index += 1;
             current_field.clear();
         } else if !in_quotes || c != '"' {
@@ -191,9 +259,22 @@ pub fn parse_gauge_line(line: &str) -> Option<GaugeConfig> {
         }
     }
     
-    // Process last field
-    process_field(&mut config, field_index, current_field.as_str());
-    
+    if in_quotes {
+        return None;
+    }
+
+    // Process last field (empty after a trailing comma)
+    let last = current_field.as_str().trim();
+    if !last.is_empty() || field_index < GAUGE_REQUIRED_FIELDS {
+        if !process_field(&mut config, field_index, last) {
+            return None;
+        }
+        field_index += 1;
+    }
+    if field_index < GAUGE_REQUIRED_FIELDS {
+        return None;
+    }
+
     Some(config)
 }
 
@@ -227,22 +308,317 @@ impl<const N: usize> String<N> {
     }
 }
 
-fn process_field(config: &mut GaugeConfig, index: usize, value: &str) {
+/// Store one gauge field; returns false if the field is invalid
+fn process_field(config: &mut GaugeConfig, index: usize, value: &str) -> bool {
     let value = value.trim();
-    
+
+    // Decimal counts are optional: an empty one keeps the default
+    if index >= 9 && value.is_empty() {
+        return true;
+    }
+
+    let number = |target: &mut f32| match parse_number(value) {
+        Some(number) => {
+            *target = number;
+            true
+        }
+        None => false,
+    };
+
+    match index {
+        0 => {
+            copy_str_to_bytes(&mut config.var, value);
+            !value.is_empty()
+        }
+        1 => {
+            copy_str_to_bytes(&mut config.title, value);
+            true
+        }
+        2 => {
+            copy_str_to_bytes(&mut config.units, value);
+            true
+        }
+        3 => number(&mut config.lo),
+        4 => number(&mut config.hi),
+        5 => number(&mut config.lo_danger),
+        6 => number(&mut config.lo_warning),
+        7 => number(&mut config.hi_warning),
+        8 => number(&mut config.hi_danger),
+        9 => match value.parse::<u8>() {
+            Ok(decimals) => {
+                config.value_decimals = decimals;
+                true
+            }
+            Err(_) => false,
+        },
+        10 => match value.parse::<u8>() {
+            Ok(decimals) => {
+                config.label_decimals = decimals;
+                true
+            }
+            Err(_) => false,
+        },
+        _ => true,
+    }
+}
+
+/// Raw storage type of an output channel in the realtime packet
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DataType {
+    U08,
+    S08,
+    U16,
+    S16,
+    U32,
+    S32,
+}
+
+impl DataType {
+    /// Parse a TunerStudio type token (e.g. "U16", "S08")
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s.trim() {
+            "U08" => Some(DataType::U08),
+            "S08" => Some(DataType::S08),
+            "U16" => Some(DataType::U16),
+            "S16" => Some(DataType::S16),
+            "U32" => Some(DataType::U32),
+            "S32" => Some(DataType::S32),
+            _ => None,
+        }
+    }
+
+    /// Type with the given width in bytes and signedness
+    pub fn from_size(size: usize, signed: bool) -> Option<Self> {
+        match (size, signed) {
+            (1, false) => Some(DataType::U08),
+            (1, true) => Some(DataType::S08),
+            (2, false) => Some(DataType::U16),
+            (2, true) => Some(DataType::S16),
+            (4, false) => Some(DataType::U32),
+            (4, true) => Some(DataType::S32),
+            _ => None,
+        }
+    }
+
+    /// Width in bytes
+    pub fn size(&self) -> usize {
+        match self {
+            DataType::U08 | DataType::S08 => 1,
+            DataType::U16 | DataType::S16 => 2,
+            DataType::U32 | DataType::S32 => 4,
+        }
+    }
+
+    pub fn is_signed(&self) -> bool {
+        matches!(self, DataType::S08 | DataType::S16 | DataType::S32)
+    }
+}
+
+/// Output channel from TunerStudio INI [OutputChannels] section
+/// Format: name = scalar, type, offset, "units", scale, translate
+/// Engineering value = raw * scale + translate
+#[derive(Copy, Clone, Debug)]
+pub struct OutputChannel {
+    /// Channel name, matched against `GaugeConfig::var`
+    pub name: [u8; 32],
+    /// Raw storage type
+    pub data_type: DataType,
+    /// Byte offset in the realtime packet
+    pub offset: u16,
+    /// Units label
+    pub units: [u8; 16],
+    /// Multiplier from raw units
+    pub scale: f32,
+    /// Offset added after scaling
+    pub translate: f32,
+}
+
+impl OutputChannel {
+    pub fn new() -> Self {
+        OutputChannel {
+            name: [0; 32],
+            data_type: DataType::U08,
+            offset: 0,
+            units: [0; 16],
+            scale: 1.0,
+            translate: 0.0,
+        }
+    }
+
+    /// Get name as string slice
+    pub fn name_str(&self) -> &str {
+        str_from_bytes(&self.name)
+    }
+
+    /// Get units as string slice
+    pub fn units_str(&self) -> &str {
+        str_from_bytes(&self.units)
+    }
+}
+
+impl Default for OutputChannel {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Parse an output channel line from INI file
+/// Only `scalar` channels are supported; `bits` and computed
+/// `{ expression }` channels return None
+pub fn parse_output_channel_line(line: &str) -> Option<OutputChannel> {
+    let line = strip_comment(line);
+
+    // Skip comments and empty lines
+    if line.is_empty() {
+        return None;
+    }
+
+    let eq_pos = line.find('=')?;
+    let name = line[..eq_pos].trim();
+    let values = line[eq_pos + 1..].trim();
+    if name.is_empty() || values.starts_with('{') {
+        return None;
+    }
+
+    let mut channel = OutputChannel::new();
+    copy_str_to_bytes(&mut channel.name, name);
+
+    let mut field_index = 0;
+    let mut in_quotes = false;
+    let mut current_field = String::<64>::new();
+    let mut valid = true;
+
+    for c in values.chars() {
+        if c == '"' {
+            in_quotes = !in_quotes;
+        } else if c == ',' && !in_quotes {
+            valid &= process_channel_field(&mut channel, field_index, current_field.as_str());
+            field_index += 1;
+            current_field.clear();
+        } else {
+            let _ = current_field.push(c);
+        }
+    }
+
+    // Process last field
+    valid &= process_channel_field(&mut channel, field_index, current_field.as_str());
+
+    // Need at least class, type and offset
+    if valid && field_index >= 2 {
+        Some(channel)
+    } else {
+        None
+    }
+}
+
+fn process_channel_field(channel: &mut OutputChannel, index: usize, value: &str) -> bool {
+    let value = value.trim();
+
     match index {
-        0 => copy_str_to_bytes(&mut config.var, value),
-        1 => copy_str_to_bytes(&mut config.title, value),
-        2 => copy_str_to_bytes(&mut config.units, value),
-        3 => config.lo = parse_f32(value),
-        4 => config.hi = parse_f32(value),
-        5 => config.lo_danger = parse_f32(value),
-        6 => config.lo_warning = parse_f32(value),
-        7 => config.hi_warning = parse_f32(value),
-        8 => config.hi_danger = parse_f32(value),
-        9 => config.value_decimals = parse_u8(value),
-        10 => config.label_decimals = parse_u8(value),
-        _ => {}
+        0 => value == "scalar",
+        1 => match DataType::from_str(value) {
+            Some(data_type) => {
+                channel.data_type = data_type;
+                true
+            }
+            None => false,
+        },
+        2 => {
+            channel.offset = parse_u16(value);
+            true
+        }
+        3 => {
+            copy_str_to_bytes(&mut channel.units, value);
+            true
+        }
+        4 => {
+            channel.scale = parse_f32(value);
+            true
+        }
+        5 => {
+            channel.translate = parse_f32(value);
+            true
+        }
+        _ => true,
+    }
+}
+
+/// Parse a simple u16 from string
+fn parse_u16(s: &str) -> u16 {
+    let s = s.trim();
+    let mut result: u16 = 0;
+
+    for c in s.chars() {
+        if let Some(digit) = c.to_digit(10) {
+            result = result.wrapping_mul(10).wrapping_add(digit as u16);
+        } else {
+            break;
+        }
+    }
+
+    result
+}
+
+/// Collection of output channel definitions
+pub struct OutputChannels {
+    channels: [Option<OutputChannel>; MAX_OUTPUT_CHANNELS],
+    count: usize,
+}
+
+impl OutputChannels {
+    pub const fn new() -> Self {
+        OutputChannels {
+            channels: [None; MAX_OUTPUT_CHANNELS],
+            count: 0,
+        }
+    }
+
+    /// Add an output channel definition
+    pub fn add(&mut self, channel: OutputChannel) -> bool {
+        if self.count < MAX_OUTPUT_CHANNELS {
+            self.channels[self.count] = Some(channel);
+            self.count += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Get output channel by name
+    pub fn get_by_name(&self, name: &str) -> Option<&OutputChannel> {
+        for i in 0..self.count {
+            if let Some(ref channel) = self.channels[i] {
+                if channel.name_str() == name {
+                    return Some(channel);
+                }
+            }
+        }
+        None
+    }
+
+    /// Get output channel by index
+    pub fn get(&self, index: usize) -> Option<&OutputChannel> {
+        if index < self.count {
+            self.channels[index].as_ref()
+        } else {
+            None
+        }
+    }
+
+    /// Get number of channels
+    pub fn len(&self) -> usize {
+        self.count
+    }
+
+    /// Check if empty
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+}
+
+impl Default for OutputChannels {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
@@ -253,7 +629,7 @@ pub struct GaugeConfigurations {
 }
 
 impl GaugeConfigurations {
-    pub fn new() -> Self {
+    pub const fn new() -> Self {
         GaugeConfigurations {
             configs: [None; MAX_GAUGE_CONFIGS],
             count: 0,
@@ -308,3 +684,470 @@ impl Default for GaugeConfigurations {
         Self::new()
     }
 }
+
+/// Gauge placement from a config.ini [Layout] section
+/// Format: gaugeName = x, y, width, height, style
+#[derive(Copy, Clone, Debug)]
+pub struct LayoutEntry {
+    /// Gauge name as in [GaugeConfigurations]
+    pub name: [u8; 64],
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+    /// Style keyword ("circular", "hbar", "vbar", "digital", ...)
+    pub style: [u8; 16],
+    /// Redraw every Nth frame (optional sixth field, default 1)
+    pub refresh_divider: u32,
+}
+
+impl LayoutEntry {
+    pub fn name_str(&self) -> &str {
+        str_from_bytes(&self.name)
+    }
+
+    pub fn style_str(&self) -> &str {
+        str_from_bytes(&self.style)
+    }
+}
+
+/// Parse a [Layout] line: `name = x, y, width, height, style[, refresh_divider]`
+/// Returns None unless all four numbers and the style are present
+pub fn parse_layout_line(line: &str) -> Option<LayoutEntry> {
+    let line = strip_comment(line);
+    let eq_pos = line.find('=')?;
+    let name = line[..eq_pos].trim();
+    if name.is_empty() {
+        return None;
+    }
+
+    let mut entry = LayoutEntry {
+        name: [0; 64],
+        x: 0,
+        y: 0,
+        width: 0,
+        height: 0,
+        style: [0; 16],
+        refresh_divider: 1,
+    };
+    copy_str_to_bytes(&mut entry.name, name);
+
+    let mut field_count = 0;
+    for (index, field) in line[eq_pos + 1..].split(',').enumerate() {
+        let field = field.trim();
+        match index {
+            0 => entry.x = parse_u16(field) as u32,
+            1 => entry.y = parse_u16(field) as u32,
+            2 => entry.width = parse_u16(field) as u32,
+            3 => entry.height = parse_u16(field) as u32,
+            4 => copy_str_to_bytes(&mut entry.style, field.trim_matches('"')),
+            5 => entry.refresh_divider = (parse_u16(field) as u32).max(1),
+            _ => break,
+        }
+        field_count += 1;
+    }
+
+    if field_count < 5 || entry.width == 0 || entry.height == 0 {
+        return None;
+    }
+    Some(entry)
+}
+
+/// Collection of [Layout] entries
+#[derive(Copy, Clone)]
+pub struct GaugeLayout {
+    entries: [Option<LayoutEntry>; MAX_LAYOUT_ENTRIES],
+    count: usize,
+}
+
+impl GaugeLayout {
+    pub const fn new() -> Self {
+        GaugeLayout {
+            entries: [None; MAX_LAYOUT_ENTRIES],
+            count: 0,
+        }
+    }
+
+    /// Add an entry; a later entry for the same gauge replaces the earlier one
+    pub fn add(&mut self, entry: LayoutEntry) -> bool {
+        for i in 0..self.count {
+            if let Some(ref mut existing) = self.entries[i] {
+                if existing.name_str() == entry.name_str() {
+                    *existing = entry;
+                    return true;
+                }
+            }
+        }
+        if self.count < MAX_LAYOUT_ENTRIES {
+            self.entries[self.count] = Some(entry);
+            self.count += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Get the placement for a gauge by name
+    pub fn get_by_name(&self, name: &str) -> Option<&LayoutEntry> {
+        for i in 0..self.count {
+            if let Some(ref entry) = self.entries[i] {
+                if entry.name_str() == name {
+                    return Some(entry);
+                }
+            }
+        }
+        None
+    }
+
+    pub fn get(&self, index: usize) -> Option<&LayoutEntry> {
+        if index < self.count {
+            self.entries[index].as_ref()
+        } else {
+            None
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.count
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+}
+
+impl Default for GaugeLayout {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// INI sections understood by `IniParser`
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum IniSection {
+    /// Lines before the first section header
+    None,
+    GaugeConfigurations,
+    OutputChannels,
+    Constants,
+    /// LibreDash runtime options (config.ini)
+    General,
+    /// LibreDash gauge placement (config.ini)
+    Layout,
+    /// LibreDash derived channels, `name = expression` (config.ini)
+    Expressions,
+    /// Any section we don't handle; its lines are ignored
+    Unknown,
+}
+
+impl IniSection {
+    fn from_header(name: &str) -> Self {
+        match name.trim() {
+            "GaugeConfigurations" => IniSection::GaugeConfigurations,
+            "OutputChannels" => IniSection::OutputChannels,
+            "Constants" => IniSection::Constants,
+            "General" => IniSection::General,
+            "Layout" => IniSection::Layout,
+            "Expressions" => IniSection::Expressions,
+            _ => IniSection::Unknown,
+        }
+    }
+}
+
+/// Plain `key = value` setting (e.g. `ochBlockSize = 112`, `endianness = big`)
+#[derive(Copy, Clone, Debug)]
+pub struct IniSetting {
+    pub section: IniSection,
+    pub key: [u8; 32],
+    pub value: [u8; 64],
+}
+
+impl IniSetting {
+    pub fn key_str(&self) -> &str {
+        str_from_bytes(&self.key)
+    }
+
+    pub fn value_str(&self) -> &str {
+        str_from_bytes(&self.value)
+    }
+}
+
+/// Line-at-a-time parser for a full TunerStudio INI file
+/// Tracks the current `[Section]` and dispatches each line to that
+/// section's handler
+pub struct IniParser {
+    section: IniSection,
+    gauges: GaugeConfigurations,
+    channels: OutputChannels,
+    layout: GaugeLayout,
+    settings: [Option<IniSetting>; MAX_INI_SETTINGS],
+    setting_count: usize,
+    /// Malformed [GaugeConfigurations] lines that were skipped
+    rejected_gauges: u32,
+}
+
+impl IniParser {
+    pub const fn new() -> Self {
+        IniParser {
+            section: IniSection::None,
+            gauges: GaugeConfigurations::new(),
+            channels: OutputChannels::new(),
+            layout: GaugeLayout::new(),
+            settings: [None; MAX_INI_SETTINGS],
+            setting_count: 0,
+            rejected_gauges: 0,
+        }
+    }
+
+    /// Forget everything fed so far, reusing the storage in place
+    pub fn reset(&mut self) {
+        self.section = IniSection::None;
+        self.gauges.count = 0;
+        self.channels.count = 0;
+        self.layout.count = 0;
+        self.setting_count = 0;
+        self.rejected_gauges = 0;
+    }
+
+    /// Feed the next line of the file
+    pub fn feed_line(&mut self, line: &str) {
+        let line = strip_comment(line);
+
+        // Skip comments and empty lines
+        if line.is_empty() {
+            return;
+        }
+
+        // Section header
+        if line.starts_with('[') {
+            if let Some(end) = line.find(']') {
+                self.section = IniSection::from_header(&line[1..end]);
+            }
+            return;
+        }
+
+        match self.section {
+            IniSection::GaugeConfigurations => {
+                match parse_gauge_line(line) {
+                    Some(config) => {
+                        self.gauges.add(config);
+                    }
+                    None => self.rejected_gauges += 1,
+                }
+            }
+            IniSection::OutputChannels => {
+                if let Some(channel) = parse_output_channel_line(line) {
+                    self.channels.add(channel);
+                } else {
+                    self.add_setting(line);
+                }
+            }
+            IniSection::Layout => {
+                if let Some(entry) = parse_layout_line(line) {
+                    self.layout.add(entry);
+                }
+            }
+            IniSection::Constants | IniSection::General | IniSection::Expressions => {
+                self.add_setting(line);
+            }
+            IniSection::None | IniSection::Unknown => {}
+        }
+    }
+
+    /// Feed a whole file, splitting on line endings
+    /// LF, CRLF (Windows-edited files) and lone CR all end a line; the empty
+    /// pieces between CR and LF are skipped like blank lines
+    pub fn feed(&mut self, text: &str) {
+        for line in text.split(['\n', '\r']) {
+            self.feed_line(line);
+        }
+    }
+
+    /// Record a simple `key = value` line; constant/channel definitions
+    /// (`scalar`, `bits`, `array`) and expressions are skipped
+    fn add_setting(&mut self, line: &str) {
+        let eq_pos = match line.find('=') {
+            Some(pos) => pos,
+            None => return,
+        };
+        let key = line[..eq_pos].trim();
+        let value = line[eq_pos + 1..].trim();
+        if key.is_empty()
+            || value.starts_with("scalar")
+            || value.starts_with("bits")
+            || value.starts_with("array")
+            || value.starts_with('{')
+        {
+            return;
+        }
+        if self.setting_count >= MAX_INI_SETTINGS {
+            return;
+        }
+
+        let mut setting = IniSetting {
+            section: self.section,
+            key: [0; 32],
+            value: [0; 64],
+        };
+        copy_str_to_bytes(&mut setting.key, key);
+        copy_str_to_bytes(&mut setting.value, value.trim_matches('"'));
+        self.settings[self.setting_count] = Some(setting);
+        self.setting_count += 1;
+    }
+
+    /// Section the next line will be dispatched to
+    pub fn current_section(&self) -> IniSection {
+        self.section
+    }
+
+    /// Accumulated [GaugeConfigurations]
+    pub fn gauges(&self) -> &GaugeConfigurations {
+        &self.gauges
+    }
+
+    /// Gauge lines skipped because they were malformed
+    pub fn rejected_gauges(&self) -> u32 {
+        self.rejected_gauges
+    }
+
+    /// Accumulated [OutputChannels]
+    pub fn output_channels(&self) -> &OutputChannels {
+        &self.channels
+    }
+
+    /// Accumulated [Layout]
+    pub fn layout(&self) -> &GaugeLayout {
+        &self.layout
+    }
+
+    /// Look up a plain setting by section and key
+    pub fn setting(&self, section: IniSection, key: &str) -> Option<&str> {
+        for i in 0..self.setting_count {
+            if let Some(ref setting) = self.settings[i] {
+                if setting.section == section && setting.key_str() == key {
+                    return Some(setting.value_str());
+                }
+            }
+        }
+        None
+    }
+
+    /// Every plain setting in a section, in file order
+    pub fn settings_in(&self, section: IniSection) -> impl Iterator<Item = &IniSetting> {
+        self.settings[..self.setting_count]
+            .iter()
+            .flatten()
+            .filter(move |setting| setting.section == section)
+    }
+}
+
+impl Default for IniParser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_valid_gauge_line() {
+        let config = parse_gauge_line(
+            "rpmGauge = rpm, \"Engine Speed\", \"RPM\", 0, 8000, 300, 600, 6500, 7200, 0, 0",
+        )
+        .unwrap();
+        assert_eq!(config.name_str(), "rpmGauge");
+        assert_eq!(config.var_str(), "rpm");
+        assert_eq!(config.title_str(), "Engine Speed");
+        assert_eq!(config.units_str(), "RPM");
+        assert_eq!((config.lo, config.hi), (0.0, 8000.0));
+        assert_eq!((config.lo_danger, config.lo_warning), (300.0, 600.0));
+        assert_eq!((config.hi_warning, config.hi_danger), (6500.0, 7200.0));
+    }
+
+    #[test]
+    fn accepts_trailing_comma_and_whitespace() {
+        let config = parse_gauge_line("  clt =  coolant , \"CLT\" , \"C\" , -40 , 120 , -40 , 10 , 100 , 110 ,  ").unwrap();
+        assert_eq!(config.var_str(), "coolant");
+        assert_eq!((config.lo, config.hi), (-40.0, 120.0));
+        assert_eq!(config.hi_danger, 110.0);
+    }
+
+    #[test]
+    fn rejects_truncated_line() {
+        assert!(parse_gauge_line("rpmGauge = rpm, \"RPM\", \"RPM\", 0, 8000, 300").is_none());
+        assert!(parse_gauge_line("rpmGauge = rpm, \"RPM").is_none());
+        assert!(parse_gauge_line("rpmGauge").is_none());
+    }
+
+    #[test]
+    fn rejects_non_numeric_lo() {
+        assert!(parse_gauge_line("rpmGauge = rpm, \"RPM\", \"RPM\", low, 8000, 300, 600, 6500, 7200").is_none());
+    }
+
+    #[test]
+    fn parse_f32_edge_cases() {
+        assert_eq!(parse_f32("-.5"), -0.5);
+        assert_eq!(parse_f32("+12.0"), 12.0);
+        assert_eq!(parse_f32("3."), 3.0);
+        assert_eq!(parse_f32(".25"), 0.25);
+        assert_eq!(parse_f32("12.3.4"), 12.3);
+        assert_eq!(parse_f32("  -7 "), -7.0);
+        assert_eq!(parse_f32("5-3"), 5.0);
+        assert_eq!(parse_f32("--5"), 0.0);
+    }
+
+    #[test]
+    fn parse_number_requires_a_digit() {
+        assert_eq!(parse_number("-.5"), Some(-0.5));
+        assert_eq!(parse_number("+12.0"), Some(12.0));
+        assert_eq!(parse_number("."), None);
+        assert_eq!(parse_number("-"), None);
+        assert_eq!(parse_number("abc"), None);
+    }
+
+    #[test]
+    fn strip_comment_cuts_inline_comments() {
+        assert_eq!(strip_comment("hi = 8000 ; redline"), "hi = 8000");
+        assert_eq!(strip_comment("  ; whole line"), "");
+        assert_eq!(strip_comment("# preprocessor"), "");
+        assert_eq!(strip_comment("units = \"a;b\" ; note"), "units = \"a;b\"");
+        assert_eq!(strip_comment("color = #FF0000"), "color = #FF0000");
+        assert_eq!(strip_comment("plain = 1\r"), "plain = 1");
+    }
+
+    #[test]
+    fn inline_comment_does_not_reach_numbers() {
+        assert!(parse_gauge_line("rpm = rpm, \"RPM\", \"\", 0, 8000 ; redline").is_none());
+
+        let config = parse_gauge_line("rpm = rpm, \"RPM\", \"\", 0, 8000, 0, 0, 6500, 7200 ; redline").unwrap();
+        assert_eq!(config.hi_danger, 7200.0);
+
+        let mut parser = IniParser::new();
+        parser.feed("[General]\nhi = 8000 ; redline\n");
+        assert_eq!(parser.setting(IniSection::General, "hi"), Some("8000"));
+    }
+
+    #[test]
+    fn feed_accepts_crlf_and_lone_cr() {
+        for text in [
+            "[General]\r\nfps = 30\r\ntheme = dark\r\n",
+            "[General]\rfps = 30\rtheme = dark\r",
+            "[General]\nfps = 30\ntheme = dark",
+        ] {
+            let mut parser = IniParser::new();
+            parser.feed(text);
+            assert_eq!(parser.setting(IniSection::General, "fps"), Some("30"));
+            assert_eq!(parser.setting(IniSection::General, "theme"), Some("dark"));
+        }
+    }
+
+    #[test]
+    fn parser_counts_rejected_gauges() {
+        let mut parser = IniParser::new();
+        parser.feed("[GaugeConfigurations]\nok = rpm, \"R\", \"\", 0, 8000, 0, 0, 6500, 7200\nbad = rpm, \"R\"\n");
+        assert_eq!(parser.gauges().len(), 1);
+        assert_eq!(parser.rejected_gauges(), 1);
+    }
+}
@@ -1,4 +1,5 @@
-﻿use core::ptr;
+﻿use core::ptr::{self, addr_of, addr_of_mut};
+use crate::timer;
 
 const UART0_BASE: u32 = 0x3F201000;
 const UART0_DR: u32 = UART0_BASE + 0x00;
@@ -9,13 +10,57 @@ const UART0_LCRH: u32 = UART0_BASE + 0x2C;
 const UART0_CR: u32 = UART0_BASE + 0x30;
 const UART0_ICR: u32 = UART0_BASE + 0x44;
 
+/// Mini-UART (UART1) lives in the auxiliary peripheral block
+const AUX_BASE: u32 = 0x3F215000;
+const AUX_ENABLES: u32 = AUX_BASE + 0x04;
+const AUX_MU_IO: u32 = AUX_BASE + 0x40;
+const AUX_MU_IER: u32 = AUX_BASE + 0x44;
+const AUX_MU_IIR: u32 = AUX_BASE + 0x48;
+const AUX_MU_LCR: u32 = AUX_BASE + 0x4C;
+const AUX_MU_MCR: u32 = AUX_BASE + 0x50;
+const AUX_MU_LSR: u32 = AUX_BASE + 0x54;
+const AUX_MU_CNTL: u32 = AUX_BASE + 0x60;
+const AUX_MU_BAUD: u32 = AUX_BASE + 0x68;
+
 const GPIO_BASE: u32 = 0x3F200000;
-const GPFSEL1: u32 = GPIO_BASE + 0x04;
+const GPFSEL0: u32 = GPIO_BASE;
 const GPPUD: u32 = GPIO_BASE + 0x94;
 const GPPUDCLK0: u32 = GPIO_BASE + 0x98;
 
+const UART_FR_BUSY: u32 = 1 << 3;
+const UART_FR_RXFE: u32 = 1 << 4;
 const UART_FR_TXFF: u32 = 1 << 5;
 
+const AUX_ENABLE_MINI_UART: u32 = 1 << 0;
+const MU_LSR_DATA_READY: u32 = 1 << 0;
+const MU_LSR_TX_EMPTY: u32 = 1 << 5;
+const MU_LSR_TX_IDLE: u32 = 1 << 6;
+/// LCR value for 8 data bits
+const MU_LCR_8BIT: u32 = 3;
+/// IIR write that clears both FIFOs
+const MU_IIR_CLEAR_FIFOS: u32 = 0xC6;
+/// CNTL value enabling the receiver and transmitter
+const MU_CNTL_RX_TX: u32 = 3;
+
+/// PL011 reference clock as configured by the firmware
+const UART_CLOCK_HZ: u32 = 3_000_000;
+
+/// Core (VPU) clock the mini-UART divides; fixed at 250 MHz by enable_uart=1
+const MINI_UART_CLOCK_HZ: u32 = 250_000_000;
+
+/// GPFSEL function codes for the alternate functions
+const GPIO_ALT0: u32 = 4;
+const GPIO_ALT5: u32 = 2;
+
+/// Default baud rate for the debug console
+const DEFAULT_BAUD: u32 = 115_200;
+
+/// Software RX ring size (one slot is kept free to tell full from empty)
+const RX_BUFFER_SIZE: usize = 512;
+
+/// Where `uart_putc` sends the debug console; None silences it
+static mut CONSOLE_PORT: Option<UartPort> = Some(UartPort::PL011);
+
 fn delay(count: u32) {
     for _ in 0..count {
         unsafe { 
@@ -25,32 +70,213 @@ fn delay(count: u32) {
     }
 }
 
+/// UART peripheral behind a `UartPort`
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum UartPeripheral {
+    /// Full PL011 (UART0); muxed to the wireless chip by default on Pi 3
+    Pl011,
+    /// Mini-UART (UART1); its baud rate follows the core clock
+    Mini,
+}
+
+/// Which UART to drive and which GPIO pins it is routed to
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct UartPort {
+    pub peripheral: UartPeripheral,
+    pub tx_pin: u32,
+    pub rx_pin: u32,
+    /// GPFSEL function code that connects the pins to `peripheral`
+    pub alt_function: u32,
+}
+
+impl UartPort {
+    /// PL011 on GPIO 14/15 (header pins 8/10)
+    pub const PL011: UartPort = UartPort {
+        peripheral: UartPeripheral::Pl011,
+        tx_pin: 14,
+        rx_pin: 15,
+        alt_function: GPIO_ALT0,
+    };
+
+    /// Mini-UART on GPIO 14/15, for Pi 3 builds where UART0 serves Bluetooth
+    pub const MINI: UartPort = UartPort {
+        peripheral: UartPeripheral::Mini,
+        tx_pin: 14,
+        rx_pin: 15,
+        alt_function: GPIO_ALT5,
+    };
+
+    /// Parse a port name from config ("uart0"/"pl011", "uart1"/"mini")
+    pub fn from_name(name: &str) -> Option<Self> {
+        let name = name.trim();
+        if name.eq_ignore_ascii_case("uart0") || name.eq_ignore_ascii_case("pl011") {
+            Some(UartPort::PL011)
+        } else if name.eq_ignore_ascii_case("uart1") || name.eq_ignore_ascii_case("mini") {
+            Some(UartPort::MINI)
+        } else {
+            None
+        }
+    }
+
+    /// True if both ports can't run at once: same peripheral or a shared pin
+    pub fn conflicts_with(&self, other: &UartPort) -> bool {
+        self.peripheral == other.peripheral
+            || [self.tx_pin, self.rx_pin].iter().any(|pin| *pin == other.tx_pin || *pin == other.rx_pin)
+    }
+
+    /// Route the pins and program the line settings
+    fn configure(&self, baud: u32) {
+        match self.peripheral {
+            UartPeripheral::Pl011 => {
+                unsafe { ptr::write_volatile(UART0_CR as *mut u32, 0) };
+                self.route_pins();
+                pl011_program(baud, true);
+            }
+            UartPeripheral::Mini => {
+                unsafe {
+                    let enables = ptr::read_volatile(AUX_ENABLES as *const u32);
+                    ptr::write_volatile(AUX_ENABLES as *mut u32, enables | AUX_ENABLE_MINI_UART);
+                    ptr::write_volatile(AUX_MU_CNTL as *mut u32, 0);
+                    ptr::write_volatile(AUX_MU_IER as *mut u32, 0);
+                    ptr::write_volatile(AUX_MU_LCR as *mut u32, MU_LCR_8BIT);
+                    ptr::write_volatile(AUX_MU_MCR as *mut u32, 0);
+                    ptr::write_volatile(AUX_MU_IIR as *mut u32, MU_IIR_CLEAR_FIFOS);
+                    ptr::write_volatile(AUX_MU_BAUD as *mut u32, mini_baud_register(baud));
+                }
+                self.route_pins();
+                unsafe { ptr::write_volatile(AUX_MU_CNTL as *mut u32, MU_CNTL_RX_TX) };
+            }
+        }
+    }
+
+    /// Reprogram only the baud rate, leaving GPIO routing alone
+    fn reconfigure_baud(&self, baud: u32) {
+        match self.peripheral {
+            UartPeripheral::Pl011 => {
+                // Let any in-flight byte finish, then disable TX/RX
+                unsafe {
+                    while (ptr::read_volatile(UART0_FR as *const u32) & UART_FR_BUSY) != 0 {}
+                    ptr::write_volatile(UART0_CR as *mut u32, 0);
+                }
+                pl011_program(baud, false);
+            }
+            UartPeripheral::Mini => unsafe {
+                while (ptr::read_volatile(AUX_MU_LSR as *const u32) & MU_LSR_TX_IDLE) == 0 {}
+                ptr::write_volatile(AUX_MU_CNTL as *mut u32, 0);
+                ptr::write_volatile(AUX_MU_BAUD as *mut u32, mini_baud_register(baud));
+                ptr::write_volatile(AUX_MU_CNTL as *mut u32, MU_CNTL_RX_TX);
+            },
+        }
+    }
+
+    /// Select the alternate function on both pins and disable their pulls
+    fn route_pins(&self) {
+        for &pin in &[self.tx_pin, self.rx_pin] {
+            let register = GPFSEL0 + (pin / 10) * 4;
+            let shift = (pin % 10) * 3;
+            unsafe {
+                let mut sel = ptr::read_volatile(register as *const u32);
+                sel &= !(7 << shift);
+                sel |= self.alt_function << shift;
+                ptr::write_volatile(register as *mut u32, sel);
+            }
+        }
+
+        // GPPUDCLK0 covers pins 0-31, GPPUDCLK1 the rest
+        let clock_register = |pin: u32| GPPUDCLK0 + (pin / 32) * 4;
+        unsafe {
+            ptr::write_volatile(GPPUD as *mut u32, 0);
+            delay(150);
+            for &pin in &[self.tx_pin, self.rx_pin] {
+                ptr::write_volatile(clock_register(pin) as *mut u32, 1 << (pin % 32));
+            }
+            delay(150);
+            for &pin in &[self.tx_pin, self.rx_pin] {
+                ptr::write_volatile(clock_register(pin) as *mut u32, 0);
+            }
+        }
+    }
+
+    fn put(&self, byte: u8) {
+        match self.peripheral {
+            UartPeripheral::Pl011 => pl011_putc(byte),
+            UartPeripheral::Mini => unsafe {
+                while (ptr::read_volatile(AUX_MU_LSR as *const u32) & MU_LSR_TX_EMPTY) == 0 {}
+                ptr::write_volatile(AUX_MU_IO as *mut u32, byte as u32);
+            },
+        }
+    }
+
+    /// Check whether the hardware RX FIFO holds at least one byte
+    fn rx_ready(&self) -> bool {
+        unsafe {
+            match self.peripheral {
+                UartPeripheral::Pl011 => (ptr::read_volatile(UART0_FR as *const u32) & UART_FR_RXFE) == 0,
+                UartPeripheral::Mini => (ptr::read_volatile(AUX_MU_LSR as *const u32) & MU_LSR_DATA_READY) != 0,
+            }
+        }
+    }
+
+    fn read(&self) -> u8 {
+        let register = match self.peripheral {
+            UartPeripheral::Pl011 => UART0_DR,
+            UartPeripheral::Mini => AUX_MU_IO,
+        };
+        unsafe { ptr::read_volatile(register as *const u32) as u8 }
+    }
+}
+
+/// Start the debug console on the PL011 on GPIO 14/15, until
+/// `set_console_port` moves or silences it
 pub fn uart_init() {
+    UartPort::PL011.configure(DEFAULT_BAUD);
+}
+
+/// Move the debug console to another port, or silence it with None (e.g.
+/// when the ECU needs its pins). A new port is configured at 115200 baud
+pub fn set_console_port(port: Option<UartPort>) {
+    if let Some(port) = port {
+        port.configure(DEFAULT_BAUD);
+    }
+    // Safety: only the boot core runs, and the console is not written while
+    // the port is being switched
+    unsafe { *addr_of_mut!(CONSOLE_PORT) = port };
+}
+
+/// Compute PL011 integer/fractional baud divisors (fraction in 1/64ths)
+fn baud_divisors(baud: u32) -> (u32, u32) {
+    let divisor = (UART_CLOCK_HZ * 4 + baud / 2) / baud;
+    (divisor >> 6, divisor & 0x3F)
+}
+
+/// Mini-UART baud register: baud = clock / (8 * (register + 1))
+fn mini_baud_register(baud: u32) -> u32 {
+    ((MINI_UART_CLOCK_HZ + baud * 4) / (baud * 8)).saturating_sub(1)
+}
+
+/// Program the PL011 divisors and enable it; `clear_interrupts` on first setup
+/// The PL011 latches IBRD/FBRD on the following LCRH write
+fn pl011_program(baud: u32, clear_interrupts: bool) {
+    let (ibrd, fbrd) = baud_divisors(baud);
     unsafe {
-        ptr::write_volatile(UART0_CR as *mut u32, 0);
-        
-        let mut ra = ptr::read_volatile(GPFSEL1 as *const u32);
-        ra &= !(7 << 12);
-        ra |= 4 << 12;
-        ra &= !(7 << 15);
-        ra |= 4 << 15;
-        ptr::write_volatile(GPFSEL1 as *mut u32, ra);
-        
-        ptr::write_volatile(GPPUD as *mut u32, 0);
-        delay(150);
-        ptr::write_volatile(GPPUDCLK0 as *mut u32, (1 << 14) | (1 << 15));
-        delay(150);
-        ptr::write_volatile(GPPUDCLK0 as *mut u32, 0);
-        
-        ptr::write_volatile(UART0_ICR as *mut u32, 0x7FF);
-        ptr::write_volatile(UART0_IBRD as *mut u32, 1);
-        ptr::write_volatile(UART0_FBRD as *mut u32, 40);
+        if clear_interrupts {
+            ptr::write_volatile(UART0_ICR as *mut u32, 0x7FF);
+        }
+        ptr::write_volatile(UART0_IBRD as *mut u32, ibrd);
+        ptr::write_volatile(UART0_FBRD as *mut u32, fbrd);
         ptr::write_volatile(UART0_LCRH as *mut u32, 0x70);
         ptr::write_volatile(UART0_CR as *mut u32, 0x301);
     }
 }
 
 pub fn uart_putc(byte: u8) {
+    // Safety: only the boot core runs, so nothing changes the port mid-read
+    if let Some(port) = unsafe { *addr_of!(CONSOLE_PORT) } {
+        port.put(byte);
+    }
+}
+
+fn pl011_putc(byte: u8) {
     unsafe {
         while (ptr::read_volatile(UART0_FR as *const u32) & UART_FR_TXFF) != 0 {}
         ptr::write_volatile(UART0_DR as *mut u32, byte as u32);
@@ -66,6 +292,12 @@ pub fn uart_puts(s: &str) {
     }
 }
 
+/// Print an unsigned number in decimal
+pub fn uart_put_dec(value: u32) {
+    let mut digits = [0u8; 10];
+    uart_puts(crate::digit_renderer::format_u32(value, &mut digits));
+}
+
 fn hex_digit(val: u8) -> u8 {
     if val < 10 { b'0' + val } else { b'a' + (val - 10) }
 }
@@ -92,3 +324,161 @@ pub fn uart_hex_dump(addr: *const u8, len: usize) {
         uart_putc(b'\n');
     }
 }
+
+/// UART handle for ECU communication
+/// Received bytes are drained from the hardware FIFO (16 bytes on the PL011, 8 on the mini-UART) into a
+/// software ring by `pump_rx`, so calling it often (e.g. between gauge
+/// renders) keeps the FIFO from overflowing while the CPU is busy.
+pub struct Uart {
+    port: UartPort,
+    baud_rate: u32,
+    initialized: bool,
+    rx_buffer: [u8; RX_BUFFER_SIZE],
+    rx_head: usize,
+    rx_tail: usize,
+    rx_overflows: u32,
+}
+
+impl Uart {
+    pub const fn new() -> Self {
+        Self::with_port(UartPort::PL011)
+    }
+
+    /// Handle for a specific peripheral and pin pair; nothing is touched until `init`
+    pub const fn with_port(port: UartPort) -> Self {
+        Uart {
+            port,
+            baud_rate: DEFAULT_BAUD,
+            initialized: false,
+            rx_buffer: [0; RX_BUFFER_SIZE],
+            rx_head: 0,
+            rx_tail: 0,
+            rx_overflows: 0,
+        }
+    }
+
+    /// Configure pins and line settings for the given baud rate
+    pub fn init(&mut self, baud_rate: u32) {
+        self.baud_rate = baud_rate;
+        self.port.configure(baud_rate);
+        self.initialized = true;
+    }
+
+    /// Change baud rate without redoing GPIO setup
+    /// Falls back to a full `init` if the port was never initialized
+    pub fn set_baud(&mut self, baud_rate: u32) {
+        if !self.initialized {
+            self.init(baud_rate);
+            return;
+        }
+        self.baud_rate = baud_rate;
+        self.port.reconfigure_baud(baud_rate);
+    }
+
+    pub fn is_initialized(&self) -> bool {
+        self.initialized
+    }
+
+    pub fn baud_rate(&self) -> u32 {
+        self.baud_rate
+    }
+
+    pub fn port(&self) -> UartPort {
+        self.port
+    }
+
+    pub fn send_byte(&mut self, byte: u8) {
+        self.port.put(byte);
+    }
+
+    pub fn send_bytes(&mut self, data: &[u8]) {
+        for &byte in data {
+            self.port.put(byte);
+        }
+    }
+
+    /// Move everything waiting in the hardware FIFO into the ring
+    /// Bytes arriving while the ring is full are dropped and counted
+    pub fn pump_rx(&mut self) {
+        while self.port.rx_ready() {
+            let byte = self.port.read();
+            let next = (self.rx_head + 1) % RX_BUFFER_SIZE;
+            if next == self.rx_tail {
+                self.rx_overflows = self.rx_overflows.wrapping_add(1);
+                continue;
+            }
+            self.rx_buffer[self.rx_head] = byte;
+            self.rx_head = next;
+        }
+    }
+
+    /// Number of bytes waiting in the ring
+    pub fn rx_available(&self) -> usize {
+        (self.rx_head + RX_BUFFER_SIZE - self.rx_tail) % RX_BUFFER_SIZE
+    }
+
+    /// Bytes dropped because the ring was full
+    pub fn rx_overflows(&self) -> u32 {
+        self.rx_overflows
+    }
+
+    /// Check whether at least one byte is available to read
+    pub fn has_data(&mut self) -> bool {
+        self.pump_rx();
+        self.rx_head != self.rx_tail
+    }
+
+    /// Take the oldest byte from the ring
+    fn pop_rx(&mut self) -> Option<u8> {
+        if self.rx_head == self.rx_tail {
+            return None;
+        }
+        let byte = self.rx_buffer[self.rx_tail];
+        self.rx_tail = (self.rx_tail + 1) % RX_BUFFER_SIZE;
+        Some(byte)
+    }
+
+    /// Receive one byte, giving up after `timeout_us` microseconds
+    /// A timeout of 0 checks once without waiting
+    pub fn recv_byte(&mut self, timeout_us: u32) -> Option<u8> {
+        let start = timer::now_us();
+        loop {
+            self.pump_rx();
+            if let Some(byte) = self.pop_rx() {
+                return Some(byte);
+            }
+            if timer::elapsed_us(start) >= timeout_us as u64 {
+                return None;
+            }
+        }
+    }
+
+    /// Fill `buf` until full or a byte times out; returns bytes received
+    /// The timeout applies to each byte, not the whole transfer
+    pub fn recv_bytes(&mut self, buf: &mut [u8], timeout_us: u32) -> usize {
+        let mut count = 0;
+        while count < buf.len() {
+            match self.recv_byte(timeout_us) {
+                Some(byte) => {
+                    buf[count] = byte;
+                    count += 1;
+                }
+                None => break,
+            }
+        }
+        count
+    }
+
+    /// Discard anything waiting in the ring and the RX FIFO
+    pub fn flush_rx(&mut self) {
+        self.pump_rx();
+        self.rx_head = 0;
+        self.rx_tail = 0;
+    }
+}
+
+impl Default for Uart {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,319 @@
+/// Unit conversions applied at the gauge layer
+/// ECU values arrive in the units the firmware reports (°F for MS2 temps);
+/// gauges convert them, together with their scale and thresholds, for display
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TempUnit {
+    Fahrenheit,
+    Celsius,
+}
+
+impl TempUnit {
+    /// Recognize a temperature units label ("F", "°C", "degF", ...)
+    pub fn from_str(s: &str) -> Option<Self> {
+        let s = s.trim();
+        let s = s.strip_prefix('°').unwrap_or(s);
+        let s = match s.get(..3) {
+            Some(prefix) if prefix.eq_ignore_ascii_case("deg") => s[3..].trim_start(),
+            _ => s,
+        };
+        if s.eq_ignore_ascii_case("f") {
+            Some(TempUnit::Fahrenheit)
+        } else if s.eq_ignore_ascii_case("c") {
+            Some(TempUnit::Celsius)
+        } else {
+            None
+        }
+    }
+
+    /// Short label suitable for a gauge's units field
+    pub fn label(&self) -> &'static str {
+        match self {
+            TempUnit::Fahrenheit => "F",
+            TempUnit::Celsius => "C",
+        }
+    }
+}
+
+/// Convert a temperature between units
+pub fn convert_temp(value: f32, from: TempUnit, to: TempUnit) -> f32 {
+    match (from, to) {
+        (TempUnit::Fahrenheit, TempUnit::Celsius) => (value - 32.0) * 5.0 / 9.0,
+        (TempUnit::Celsius, TempUnit::Fahrenheit) => value * 9.0 / 5.0 + 32.0,
+        _ => value,
+    }
+}
+
+/// Standard sea-level atmospheric pressure
+pub const STANDARD_ATMOSPHERE_KPA: f32 = 101.325;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PressureUnit {
+    Kpa,
+    Psi,
+    Bar,
+    InHg,
+}
+
+impl PressureUnit {
+    /// Parse a unit name from config ("kpa", "psi", "bar", "inhg")
+    pub fn from_str(s: &str) -> Option<Self> {
+        let s = s.trim();
+        if s.eq_ignore_ascii_case("kpa") {
+            Some(PressureUnit::Kpa)
+        } else if s.eq_ignore_ascii_case("psi") {
+            Some(PressureUnit::Psi)
+        } else if s.eq_ignore_ascii_case("bar") {
+            Some(PressureUnit::Bar)
+        } else if s.eq_ignore_ascii_case("inhg") {
+            Some(PressureUnit::InHg)
+        } else {
+            None
+        }
+    }
+
+    /// Short label suitable for a gauge's units field
+    pub fn label(&self) -> &'static str {
+        match self {
+            PressureUnit::Kpa => "kPa",
+            PressureUnit::Psi => "PSI",
+            PressureUnit::Bar => "bar",
+            PressureUnit::InHg => "inHg",
+        }
+    }
+
+    /// Kilopascals per one of this unit
+    fn kpa_per_unit(&self) -> f32 {
+        match self {
+            PressureUnit::Kpa => 1.0,
+            PressureUnit::Psi => 6.894757,
+            PressureUnit::Bar => 100.0,
+            PressureUnit::InHg => 3.386389,
+        }
+    }
+}
+
+/// Convert a pressure between units
+pub fn convert_pressure(value: f32, from: PressureUnit, to: PressureUnit) -> f32 {
+    if from == to {
+        return value;
+    }
+    value * from.kpa_per_unit() / to.kpa_per_unit()
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DistanceUnit {
+    Kilometers,
+    Miles,
+}
+
+impl DistanceUnit {
+    /// Parse a unit name from config ("km", "mi")
+    pub fn from_str(s: &str) -> Option<Self> {
+        let s = s.trim();
+        if s.eq_ignore_ascii_case("km") || s.eq_ignore_ascii_case("kph") {
+            Some(DistanceUnit::Kilometers)
+        } else if s.eq_ignore_ascii_case("mi") || s.eq_ignore_ascii_case("mph") {
+            Some(DistanceUnit::Miles)
+        } else {
+            None
+        }
+    }
+
+    /// Short label suitable for a readout
+    pub fn label(&self) -> &'static str {
+        match self {
+            DistanceUnit::Kilometers => "KM",
+            DistanceUnit::Miles => "MI",
+        }
+    }
+
+    /// Kilometers per one of this unit
+    fn km_per_unit(&self) -> f32 {
+        match self {
+            DistanceUnit::Kilometers => 1.0,
+            DistanceUnit::Miles => 1.609344,
+        }
+    }
+}
+
+/// Convert a distance (or a speed per hour) between units
+pub fn convert_distance(value: f32, from: DistanceUnit, to: DistanceUnit) -> f32 {
+    if from == to {
+        return value;
+    }
+    value * from.km_per_unit() / to.km_per_unit()
+}
+
+/// Stoichiometric air/fuel ratios by mass
+pub const STOICH_GASOLINE: f32 = 14.7;
+pub const STOICH_E85: f32 = 9.0;
+pub const STOICH_DIESEL: f32 = 14.5;
+
+/// Stoich for a fuel name from config ("gasoline"/"petrol", "e85", "diesel")
+pub fn stoich_for_fuel(name: &str) -> Option<f32> {
+    let name = name.trim();
+    if name.eq_ignore_ascii_case("gasoline") || name.eq_ignore_ascii_case("petrol") {
+        Some(STOICH_GASOLINE)
+    } else if name.eq_ignore_ascii_case("e85") {
+        Some(STOICH_E85)
+    } else if name.eq_ignore_ascii_case("diesel") {
+        Some(STOICH_DIESEL)
+    } else {
+        None
+    }
+}
+
+pub fn lambda_to_afr(lambda: f32, stoich: f32) -> f32 {
+    lambda * stoich
+}
+
+pub fn afr_to_lambda(afr: f32, stoich: f32) -> f32 {
+    if stoich <= 0.0 {
+        return 0.0;
+    }
+    afr / stoich
+}
+
+/// How a mixture reading is expressed
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum MixtureUnit {
+    /// Air/fuel ratio by mass (fuel-specific, 14.7 = stoich on gasoline)
+    Afr,
+    /// Ratio to stoich (1.0 = stoich on any fuel)
+    Lambda,
+}
+
+impl MixtureUnit {
+    /// Parse a unit name from config ("afr", "lambda")
+    pub fn from_str(s: &str) -> Option<Self> {
+        let s = s.trim();
+        if s.eq_ignore_ascii_case("afr") {
+            Some(MixtureUnit::Afr)
+        } else if s.eq_ignore_ascii_case("lambda") {
+            Some(MixtureUnit::Lambda)
+        } else {
+            None
+        }
+    }
+
+    /// Short label suitable for a gauge's units field
+    pub fn label(&self) -> &'static str {
+        match self {
+            MixtureUnit::Afr => "AFR",
+            MixtureUnit::Lambda => "LAMBDA",
+        }
+    }
+}
+
+/// Convert a mixture reading between AFR and lambda for a fuel's stoich
+pub fn convert_mixture(value: f32, from: MixtureUnit, to: MixtureUnit, stoich: f32) -> f32 {
+    match (from, to) {
+        (MixtureUnit::Lambda, MixtureUnit::Afr) => lambda_to_afr(value, stoich),
+        (MixtureUnit::Afr, MixtureUnit::Lambda) => afr_to_lambda(value, stoich),
+        _ => value,
+    }
+}
+
+/// Conversion from the unit a gauge's values are supplied in to the unit it displays
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum UnitConversion {
+    Temperature { from: TempUnit, to: TempUnit },
+    Pressure { from: PressureUnit, to: PressureUnit },
+    /// Thresholds are given in the source representation, so lambda-based
+    /// limits come out right in AFR for whichever fuel `stoich` describes
+    Mixture { from: MixtureUnit, to: MixtureUnit, stoich: f32 },
+}
+
+impl UnitConversion {
+    /// Convert a value from the source unit to the display unit
+    pub fn apply(&self, value: f32) -> f32 {
+        match *self {
+            UnitConversion::Temperature { from, to } => convert_temp(value, from, to),
+            UnitConversion::Pressure { from, to } => convert_pressure(value, from, to),
+            UnitConversion::Mixture { from, to, stoich } => convert_mixture(value, from, to, stoich),
+        }
+    }
+
+    /// The conversion back from the display unit to the source unit
+    pub fn inverse(&self) -> Self {
+        match *self {
+            UnitConversion::Temperature { from, to } => UnitConversion::Temperature { from: to, to: from },
+            UnitConversion::Pressure { from, to } => UnitConversion::Pressure { from: to, to: from },
+            UnitConversion::Mixture { from, to, stoich } => UnitConversion::Mixture { from: to, to: from, stoich },
+        }
+    }
+
+    /// Label of the display unit
+    pub fn label(&self) -> &'static str {
+        match self {
+            UnitConversion::Temperature { to, .. } => to.label(),
+            UnitConversion::Pressure { to, .. } => to.label(),
+            UnitConversion::Mixture { to, .. } => to.label(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(actual: f32, expected: f32, tolerance: f32) {
+        assert!((actual - expected).abs() <= tolerance, "{} != {}", actual, expected);
+    }
+
+    #[test]
+    fn temperature_known_values() {
+        assert_close(convert_temp(0.0, TempUnit::Celsius, TempUnit::Fahrenheit), 32.0, 1e-4);
+        assert_close(convert_temp(100.0, TempUnit::Celsius, TempUnit::Fahrenheit), 212.0, 1e-4);
+        assert_close(convert_temp(-40.0, TempUnit::Fahrenheit, TempUnit::Celsius), -40.0, 1e-4);
+        assert_close(convert_temp(195.0, TempUnit::Fahrenheit, TempUnit::Fahrenheit), 195.0, 0.0);
+    }
+
+    #[test]
+    fn pressure_known_values() {
+        assert_close(convert_pressure(100.0, PressureUnit::Kpa, PressureUnit::Psi), 14.5038, 1e-3);
+        assert_close(convert_pressure(1.0, PressureUnit::Bar, PressureUnit::Kpa), 100.0, 1e-4);
+        assert_close(convert_pressure(29.92, PressureUnit::InHg, PressureUnit::Kpa), STANDARD_ATMOSPHERE_KPA, 0.01);
+        assert_close(convert_pressure(2.0, PressureUnit::Bar, PressureUnit::Psi), 29.0075, 1e-3);
+    }
+
+    #[test]
+    fn distance_and_mixture_known_values() {
+        assert_close(convert_distance(1.0, DistanceUnit::Miles, DistanceUnit::Kilometers), 1.609344, 1e-6);
+        assert_close(convert_distance(100.0, DistanceUnit::Kilometers, DistanceUnit::Miles), 62.1371, 1e-3);
+
+        assert_close(convert_mixture(1.0, MixtureUnit::Lambda, MixtureUnit::Afr, STOICH_GASOLINE), 14.7, 1e-5);
+        assert_close(convert_mixture(9.0, MixtureUnit::Afr, MixtureUnit::Lambda, STOICH_E85), 1.0, 1e-6);
+        assert_eq!(afr_to_lambda(14.7, 0.0), 0.0);
+    }
+
+    #[test]
+    fn conversions_round_trip_through_inverse() {
+        let conversions = [
+            UnitConversion::Temperature { from: TempUnit::Fahrenheit, to: TempUnit::Celsius },
+            UnitConversion::Pressure { from: PressureUnit::Kpa, to: PressureUnit::Psi },
+            UnitConversion::Pressure { from: PressureUnit::InHg, to: PressureUnit::Bar },
+            UnitConversion::Mixture { from: MixtureUnit::Afr, to: MixtureUnit::Lambda, stoich: STOICH_DIESEL },
+        ];
+        for conversion in conversions {
+            for value in [-20.0, 0.0, 1.0, 14.7, 250.0] {
+                let back = conversion.inverse().apply(conversion.apply(value));
+                assert_close(back, value, 1e-3);
+            }
+            assert_eq!(conversion.inverse().inverse(), conversion);
+        }
+    }
+
+    #[test]
+    fn labels_follow_the_display_unit() {
+        let conversion = UnitConversion::Pressure { from: PressureUnit::Kpa, to: PressureUnit::Bar };
+        assert_eq!(conversion.label(), "bar");
+        assert_eq!(conversion.inverse().label(), "kPa");
+        assert_eq!(TempUnit::from_str("degC"), Some(TempUnit::Celsius));
+        assert_eq!(TempUnit::from_str("°F"), Some(TempUnit::Fahrenheit));
+        assert_eq!(PressureUnit::from_str(" PSI "), Some(PressureUnit::Psi));
+        assert_eq!(DistanceUnit::from_str("kph"), Some(DistanceUnit::Kilometers));
+        assert_eq!(MixtureUnit::from_str("kpa"), None);
+    }
+}
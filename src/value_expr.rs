@@ -0,0 +1,257 @@
+/// Derived values computed from ECU channels, e.g. `boost = map - baro` or
+/// `pressure_ratio = map / baro`
+/// Deliberately tiny: one operand, or two joined by `+ - * /`, so evaluation
+/// is bounded and needs no allocation. Defined in config.ini [Expressions]
+/// as `name = expression`; results are stored back into the channel map so
+/// gauges read them through their `var` like any other channel
+
+use crate::megasquirt::ChannelMap;
+use crate::ts_ini_parser::parse_number;
+
+/// Maximum number of [Expressions] entries
+pub const MAX_EXPRESSIONS: usize = 8;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Operand {
+    /// Output channel name
+    Channel([u8; 32]),
+    Constant(f32),
+}
+
+impl Operand {
+    /// A decimal constant, or otherwise a channel name (letters, digits, `_`)
+    pub fn parse(s: &str) -> Option<Self> {
+        let s = s.trim();
+        if let Some(value) = parse_number(s) {
+            return Some(Operand::Constant(value));
+        }
+        let bytes = s.as_bytes();
+        let valid_name = !bytes.is_empty()
+            && bytes.len() <= 32
+            && !bytes[0].is_ascii_digit()
+            && bytes.iter().all(|&b| b.is_ascii_alphanumeric() || b == b'_');
+        if !valid_name {
+            return None;
+        }
+        let mut name = [0u8; 32];
+        name[..bytes.len()].copy_from_slice(bytes);
+        Some(Operand::Channel(name))
+    }
+
+    /// Channel value, or 0 for a channel the ECU hasn't reported
+    pub fn value(&self, channels: &ChannelMap) -> f32 {
+        match self {
+            Operand::Constant(value) => *value,
+            Operand::Channel(name) => {
+                let len = name.iter().position(|&b| b == 0).unwrap_or(name.len());
+                core::str::from_utf8(&name[..len])
+                    .ok()
+                    .and_then(|name| channels.get(name))
+                    .unwrap_or(0.0)
+            }
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Operator {
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+}
+
+impl Operator {
+    fn from_byte(b: u8) -> Option<Self> {
+        match b {
+            b'+' => Some(Operator::Add),
+            b'-' => Some(Operator::Subtract),
+            b'*' => Some(Operator::Multiply),
+            b'/' => Some(Operator::Divide),
+            _ => None,
+        }
+    }
+
+    /// Division by zero gives 0 rather than infinity
+    pub fn apply(&self, left: f32, right: f32) -> f32 {
+        match self {
+            Operator::Add => left + right,
+            Operator::Subtract => left - right,
+            Operator::Multiply => left * right,
+            Operator::Divide => {
+                if right == 0.0 {
+                    0.0
+                } else {
+                    left / right
+                }
+            }
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ValueExpr {
+    pub left: Operand,
+    /// None for a plain operand (a channel alias or a constant)
+    pub right: Option<(Operator, Operand)>,
+}
+
+impl ValueExpr {
+    /// Parse `operand` or `operand op operand` (e.g. "map - 101.3")
+    /// A sign at the start of an operand belongs to the number ("map - -5")
+    pub fn parse(s: &str) -> Option<Self> {
+        let s = s.trim();
+        let mut seen_operand = false;
+        for (i, &b) in s.as_bytes().iter().enumerate() {
+            if seen_operand {
+                if let Some(op) = Operator::from_byte(b) {
+                    let left = Operand::parse(&s[..i])?;
+                    let right = Operand::parse(&s[i + 1..])?;
+                    return Some(ValueExpr { left, right: Some((op, right)) });
+                }
+            }
+            if !b.is_ascii_whitespace() {
+                seen_operand = true;
+            }
+        }
+        Some(ValueExpr { left: Operand::parse(s)?, right: None })
+    }
+
+    pub fn evaluate(&self, channels: &ChannelMap) -> f32 {
+        let left = self.left.value(channels);
+        match self.right {
+            Some((op, right)) => op.apply(left, right.value(channels)),
+            None => left,
+        }
+    }
+}
+
+/// Named expressions, evaluated in order so later ones can use earlier results
+pub struct ExpressionSet {
+    names: [[u8; 32]; MAX_EXPRESSIONS],
+    exprs: [Option<ValueExpr>; MAX_EXPRESSIONS],
+    count: usize,
+}
+
+impl ExpressionSet {
+    pub const fn new() -> Self {
+        ExpressionSet {
+            names: [[0; 32]; MAX_EXPRESSIONS],
+            exprs: [None; MAX_EXPRESSIONS],
+            count: 0,
+        }
+    }
+
+    /// Add a derived channel; false when full or the name doesn't fit
+    pub fn add(&mut self, name: &str, expr: ValueExpr) -> bool {
+        let bytes = name.as_bytes();
+        if bytes.is_empty() || bytes.len() > 32 || self.count >= MAX_EXPRESSIONS {
+            return false;
+        }
+        self.names[self.count] = [0; 32];
+        self.names[self.count][..bytes.len()].copy_from_slice(bytes);
+        self.exprs[self.count] = Some(expr);
+        self.count += 1;
+        true
+    }
+
+    /// Whether one of the expressions produces channel `name`
+    pub fn defines(&self, name: &str) -> bool {
+        self.names[..self.count].iter().any(|stored| {
+            let len = stored.iter().position(|&b| b == 0).unwrap_or(stored.len());
+            &stored[..len] == name.as_bytes()
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.count
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    /// Evaluate every expression and store the results in the channel map
+    /// Call after each channel update
+    pub fn apply(&self, channels: &mut ChannelMap) {
+        for i in 0..self.count {
+            let name = &self.names[i];
+            let len = name.iter().position(|&b| b == 0).unwrap_or(name.len());
+            let name = match core::str::from_utf8(&name[..len]) {
+                Ok(name) => name,
+                Err(_) => continue,
+            };
+            if let Some(ref expr) = self.exprs[i] {
+                let value = expr.evaluate(channels);
+                channels.set(name, value);
+            }
+        }
+    }
+}
+
+impl Default for ExpressionSet {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn channels() -> ChannelMap {
+        let mut channels = ChannelMap::new();
+        channels.set("map", 150.0);
+        channels.set("baro", 100.0);
+        channels
+    }
+
+    fn eval(s: &str) -> f32 {
+        ValueExpr::parse(s).unwrap().evaluate(&channels())
+    }
+
+    #[test]
+    fn each_operator() {
+        assert_eq!(eval("map + baro"), 250.0);
+        assert_eq!(eval("map - 101.3"), 150.0 - 101.3);
+        assert_eq!(eval("map*2"), 300.0);
+        assert_eq!(eval("map / baro"), 1.5);
+        assert_eq!(eval("map / 0"), 0.0);
+    }
+
+    #[test]
+    fn single_operands_and_signed_constants() {
+        assert_eq!(eval("baro"), 100.0);
+        assert_eq!(eval(" 42.5 "), 42.5);
+        assert_eq!(eval("-5"), -5.0);
+        assert_eq!(eval("map - -5"), 155.0);
+    }
+
+    #[test]
+    fn unknown_channel_reads_zero() {
+        assert_eq!(eval("egt"), 0.0);
+        assert_eq!(eval("egt + 10"), 10.0);
+        assert_eq!(eval("map - egt"), 150.0);
+    }
+
+    #[test]
+    fn malformed_expressions_are_rejected() {
+        assert_eq!(ValueExpr::parse(""), None);
+        assert_eq!(ValueExpr::parse("map -"), None);
+        assert_eq!(ValueExpr::parse("map + 2x"), None);
+        assert_eq!(ValueExpr::parse("map + baro + 1"), None);
+    }
+
+    #[test]
+    fn expression_set_chains_results() {
+        let mut set = ExpressionSet::new();
+        assert!(set.add("boost", ValueExpr::parse("map - baro").unwrap()));
+        assert!(set.add("boost_psi", ValueExpr::parse("boost * 0.145").unwrap()));
+        let mut channels = channels();
+        set.apply(&mut channels);
+        assert_eq!(channels.get("boost"), Some(50.0));
+        assert_eq!(channels.get("boost_psi"), Some(50.0 * 0.145));
+        assert!(set.defines("boost_psi"));
+        assert!(!set.defines("boost_ps"));
+    }
+}
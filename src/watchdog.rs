@@ -0,0 +1,50 @@
+/// BCM2835 power-management watchdog
+/// Once started, the board resets unless `pet` is called before the timeout
+/// runs out, so a hung ECU read or render routine reboots the dashboard
+/// instead of freezing it. Enabled with the `watchdog` feature.
+
+use crate::mmio::{mmio_read, mmio_write};
+
+const PM_BASE: u32 = 0x3F100000;
+const PM_RSTC: u32 = PM_BASE + 0x1C;
+const PM_WDOG: u32 = PM_BASE + 0x24;
+
+/// Every PM register write must carry this in the top byte
+const PM_PASSWORD: u32 = 0x5A000000;
+const PM_RSTC_WRCFG_CLR: u32 = 0xFFFFFFCF;
+const PM_RSTC_WRCFG_FULL_RESET: u32 = 0x00000020;
+const PM_RSTC_RESET: u32 = 0x00000102;
+const PM_WDOG_TIME_MASK: u32 = 0x000FFFFF;
+
+/// The watchdog counts down at 65536 ticks per second
+const TICKS_PER_SECOND: u64 = 65_536;
+
+/// Longest timeout the 20-bit counter holds (about 16 seconds)
+pub const MAX_TIMEOUT_MS: u32 = (PM_WDOG_TIME_MASK as u64 * 1000 / TICKS_PER_SECOND) as u32;
+
+pub struct Watchdog {
+    ticks: u32,
+}
+
+impl Watchdog {
+    /// Arm the watchdog; the timeout is clamped to 1 ms..MAX_TIMEOUT_MS
+    pub fn start(timeout_ms: u32) -> Self {
+        let timeout_ms = timeout_ms.clamp(1, MAX_TIMEOUT_MS);
+        let ticks = ((timeout_ms as u64 * TICKS_PER_SECOND / 1000) as u32).max(1);
+        let watchdog = Watchdog { ticks };
+        watchdog.pet();
+        watchdog
+    }
+
+    /// Restart the countdown; call once per main-loop iteration
+    pub fn pet(&self) {
+        mmio_write(PM_WDOG, PM_PASSWORD | (self.ticks & PM_WDOG_TIME_MASK));
+        let rstc = mmio_read(PM_RSTC) & PM_RSTC_WRCFG_CLR;
+        mmio_write(PM_RSTC, PM_PASSWORD | rstc | PM_RSTC_WRCFG_FULL_RESET);
+    }
+
+    /// Disarm, e.g. before a long blocking operation such as an SD card write
+    pub fn stop(self) {
+        mmio_write(PM_RSTC, PM_PASSWORD | PM_RSTC_RESET);
+    }
+}
@@ -136,16 +136,181 @@ impl XMLGaugeDefinition {
 pub struct XMLGaugeParser;
 
 impl XMLGaugeParser {
-    /// Parse a simple XML gauge element
-    /// Very basic parsing - just enough for TunerStudio gauge format
-    pub fn parse_gauge_element(_xml_data: &[u8]) -> Option<XMLGaugeDefinition> {
-        // Placeholder for actual XML parsing
-        // In a real implementation, would:
-        // 1. Find <gauge> tags
-        // 2. Extract attributes (name, type, etc.)
-        // 3. Parse nested elements (min, max, warn, danger, variable, units, etc.)
-        // 4. Convert string values to numbers
-        None
+    /// Parse the first `<gauge>` element found in `xml_data`
+    /// Reads `name`/`type` (and optionally `variable`/`title`/`units`)
+    /// attributes, then nested `<min>`, `<max>`, `<warn>`, `<danger>`,
+    /// `<variable>`, `<units>` and `<title>` elements. Values may be given
+    /// as text content or as a `value` attribute; `<warn>`/`<danger>` also
+    /// accept `min`/`max` attributes or a "lo,hi" text pair.
+    pub fn parse_gauge_element(xml_data: &[u8]) -> Option<XMLGaugeDefinition> {
+        let mut pos = 0;
+
+        // Find the opening <gauge> tag
+        let (gauge_tag, kind) = loop {
+            let (element, kind, next) = read_tag(xml_data, pos)?;
+            pos = next;
+            if kind != TagKind::Close && element.tag_str() == "gauge" {
+                break (element, kind);
+            }
+        };
+
+        let mut gauge = XMLGaugeDefinition::new();
+        if let Some(v) = gauge_tag.get_attr("name") {
+            gauge.name_len = copy_bytes(&mut gauge.name, v);
+        }
+        if let Some(v) = gauge_tag.get_attr("type") {
+            gauge.gauge_type_len = copy_bytes(&mut gauge.gauge_type, v);
+        }
+        for attr in ["variable", "title", "units"] {
+            if let Some(v) = gauge_tag.get_attr(attr) {
+                Self::apply_gauge_field(&mut gauge, attr, v, None, None);
+            }
+        }
+
+        if kind == TagKind::SelfClosing {
+            return Some(gauge);
+        }
+
+        // Walk children until </gauge>
+        loop {
+            let (mut child, kind, next) = read_tag(xml_data, pos)?;
+            pos = next;
+
+            match kind {
+                TagKind::Close => {
+                    if child.tag_str() == "gauge" {
+                        return Some(gauge);
+                    }
+                }
+                TagKind::Open => {
+                    pos = read_text(xml_data, pos, &mut child);
+                }
+                TagKind::SelfClosing => {}
+            }
+
+            if kind != TagKind::Close {
+                let value = child.get_attr("value").unwrap_or(child.text_str());
+                Self::apply_gauge_field(
+                    &mut gauge,
+                    child.tag_str(),
+                    value,
+                    child.get_attr("min"),
+                    child.get_attr("max"),
+                );
+            }
+        }
+    }
+
+    /// Parse a `<dashboard>` element and its `<gauge>` placement references
+    /// Reads `name`/`width`/`height` from the dashboard tag and
+    /// `name`/`x`/`y`/`width`/`height` from each gauge reference. References
+    /// beyond the 16-slot limit are ignored.
+    pub fn parse_dashboard(xml: &[u8]) -> Option<XMLDashboardLayout> {
+        let mut pos = 0;
+
+        // Find the opening <dashboard> tag
+        let (dash_tag, kind) = loop {
+            let (element, kind, next) = read_tag(xml, pos)?;
+            pos = next;
+            if kind != TagKind::Close && element.tag_str() == "dashboard" {
+                break (element, kind);
+            }
+        };
+
+        let mut layout = XMLDashboardLayout::new();
+        if let Some(v) = dash_tag.get_attr("name") {
+            layout.name_len = copy_bytes(&mut layout.name, v);
+        }
+        if let Some(v) = dash_tag.get_attr("width") {
+            layout.width = Self::parse_int(v);
+        }
+        if let Some(v) = dash_tag.get_attr("height") {
+            layout.height = Self::parse_int(v);
+        }
+
+        if kind == TagKind::SelfClosing {
+            return Some(layout);
+        }
+
+        // Collect gauge references until </dashboard>
+        loop {
+            let (child, kind, next) = read_tag(xml, pos)?;
+            pos = next;
+
+            if kind == TagKind::Close {
+                if child.tag_str() == "dashboard" {
+                    return Some(layout);
+                }
+                continue;
+            }
+
+            if child.tag_str() != "gauge" || layout.gauge_count >= layout.gauge_refs.len() {
+                continue;
+            }
+
+            let gauge_ref = &mut layout.gauge_refs[layout.gauge_count];
+            gauge_ref.gauge_name_len = copy_bytes(&mut gauge_ref.gauge_name, child.get_attr("name").unwrap_or(""));
+            gauge_ref.x = child.get_attr("x").map(Self::parse_int).unwrap_or(0);
+            gauge_ref.y = child.get_attr("y").map(Self::parse_int).unwrap_or(0);
+            gauge_ref.width = child.get_attr("width").map(Self::parse_int).unwrap_or(0);
+            gauge_ref.height = child.get_attr("height").map(Self::parse_int).unwrap_or(0);
+            layout.gauge_count += 1;
+        }
+    }
+
+    /// Store one child element's value into the gauge definition
+    fn apply_gauge_field(
+        gauge: &mut XMLGaugeDefinition,
+        tag: &str,
+        value: &str,
+        attr_min: Option<&str>,
+        attr_max: Option<&str>,
+    ) {
+        let value = value.trim();
+        match tag {
+            "min" => gauge.min_value = Self::parse_float(value),
+            "max" => gauge.max_value = Self::parse_float(value),
+            "variable" => gauge.variable_name_len = copy_bytes(&mut gauge.variable_name, value),
+            "title" => gauge.title_len = copy_bytes(&mut gauge.title, value),
+            "units" => gauge.units_len = copy_bytes(&mut gauge.units, value),
+            "warn" => {
+                let (lo, hi) = Self::parse_range(value, attr_min, attr_max, gauge.warn_min, gauge.warn_max);
+                gauge.warn_min = lo;
+                gauge.warn_max = hi;
+            }
+            "danger" => {
+                let (lo, hi) = Self::parse_range(value, attr_min, attr_max, gauge.danger_min, gauge.danger_max);
+                gauge.danger_min = lo;
+                gauge.danger_max = hi;
+            }
+            _ => {}
+        }
+    }
+
+    /// Parse a threshold pair from `min`/`max` attributes or "lo,hi" text
+    /// A single value sets only the upper threshold
+    fn parse_range(
+        text: &str,
+        attr_min: Option<&str>,
+        attr_max: Option<&str>,
+        default_lo: f32,
+        default_hi: f32,
+    ) -> (f32, f32) {
+        if attr_min.is_some() || attr_max.is_some() {
+            return (
+                attr_min.map(Self::parse_float).unwrap_or(default_lo),
+                attr_max.map(Self::parse_float).unwrap_or(default_hi),
+            );
+        }
+
+        match text.find(',') {
+            Some(comma) => (
+                Self::parse_float(text[..comma].trim()),
+                Self::parse_float(text[comma + 1..].trim()),
+            ),
+            None if !text.is_empty() => (default_lo, Self::parse_float(text)),
+            None => (default_lo, default_hi),
+        }
     }
 
     /// Parse string to f32
@@ -196,6 +361,134 @@ impl XMLGaugeParser {
     }
 }
 
+/// Kind of tag returned by `read_tag`
+#[derive(Clone, Copy, PartialEq)]
+enum TagKind {
+    Open,
+    Close,
+    SelfClosing,
+}
+
+fn is_space(b: u8) -> bool {
+    b == b' ' || b == b'\t' || b == b'\r' || b == b'\n'
+}
+
+fn skip_spaces(data: &[u8], mut pos: usize) -> usize {
+    while pos < data.len() && is_space(data[pos]) {
+        pos += 1;
+    }
+    pos
+}
+
+/// Find `needle` in `data` at or after `from`
+fn find_bytes(data: &[u8], from: usize, needle: &[u8]) -> Option<usize> {
+    if needle.is_empty() || data.len() < needle.len() {
+        return None;
+    }
+    (from..=data.len() - needle.len()).find(|&i| &data[i..i + needle.len()] == needle)
+}
+
+/// Copy a string into a fixed buffer, truncating; returns bytes copied
+fn copy_bytes(dest: &mut [u8], src: &str) -> usize {
+    let len = src.len().min(dest.len());
+    dest[..len].copy_from_slice(&src.as_bytes()[..len]);
+    len
+}
+
+/// Read the next element tag at or after `pos`, skipping text, comments,
+/// `<?...?>` declarations and `<!...>` directives
+/// Returns the tag (name and attributes), its kind, and the position just
+/// after its closing `>`
+fn read_tag(data: &[u8], mut pos: usize) -> Option<(XMLElement, TagKind, usize)> {
+    loop {
+        pos = find_bytes(data, pos, b"<")?;
+        let rest = &data[pos..];
+        if rest.starts_with(b"<!--") {
+            pos = find_bytes(data, pos + 4, b"-->")? + 3;
+        } else if rest.starts_with(b"<?") || rest.starts_with(b"<!") {
+            pos = find_bytes(data, pos, b">")? + 1;
+        } else {
+            break;
+        }
+    }
+
+    let mut element = XMLElement::new();
+    let mut kind = TagKind::Open;
+    pos += 1;
+    if pos < data.len() && data[pos] == b'/' {
+        kind = TagKind::Close;
+        pos += 1;
+    }
+
+    // Tag name
+    let name_start = pos;
+    while pos < data.len() && !is_space(data[pos]) && data[pos] != b'>' && data[pos] != b'/' {
+        pos += 1;
+    }
+    let name = str::from_utf8(&data[name_start..pos]).ok()?;
+    element.tag_name_len = copy_bytes(&mut element.tag_name, name);
+
+    // Attributes
+    loop {
+        pos = skip_spaces(data, pos);
+        if pos >= data.len() {
+            return None;
+        }
+        match data[pos] {
+            b'>' => return Some((element, kind, pos + 1)),
+            b'/' => {
+                pos = skip_spaces(data, pos + 1);
+                if pos < data.len() && data[pos] == b'>' {
+                    return Some((element, TagKind::SelfClosing, pos + 1));
+                }
+                return None;
+            }
+            _ => {}
+        }
+
+        let attr_start = pos;
+        while pos < data.len() && data[pos] != b'=' && !is_space(data[pos]) && data[pos] != b'>' {
+            pos += 1;
+        }
+        let attr_name = str::from_utf8(&data[attr_start..pos]).ok()?;
+        pos = skip_spaces(data, pos);
+        if pos >= data.len() || data[pos] != b'=' {
+            // Attribute without a value - ignore it
+            continue;
+        }
+        pos = skip_spaces(data, pos + 1);
+        if pos >= data.len() || (data[pos] != b'"' && data[pos] != b'\'') {
+            return None;
+        }
+        let quote = data[pos];
+        let value_start = pos + 1;
+        let value_end = (value_start..data.len()).find(|&i| data[i] == quote)?;
+        let attr_value = str::from_utf8(&data[value_start..value_end]).ok()?;
+        pos = value_end + 1;
+
+        if element.attr_count < element.attributes.len() {
+            let attr = &mut element.attributes[element.attr_count];
+            attr.name_len = copy_bytes(&mut attr.name, attr_name);
+            attr.value_len = copy_bytes(&mut attr.value, attr_value);
+            element.attr_count += 1;
+        }
+    }
+}
+
+/// Read trimmed text content up to the element's closing tag
+/// Returns the position just after `</tag>`, or just after the text if a
+/// nested element starts first
+fn read_text(data: &[u8], pos: usize, element: &mut XMLElement) -> usize {
+    let end = find_bytes(data, pos, b"<").unwrap_or(data.len());
+    let text = str::from_utf8(&data[pos..end]).unwrap_or("").trim();
+    element.text_len = copy_bytes(&mut element.text_content, text);
+
+    match read_tag(data, end) {
+        Some((close, TagKind::Close, next)) if close.tag_str() == element.tag_str() => next,
+        _ => end,
+    }
+}
+
 /// Dashboard layout from XML
 #[derive(Clone, Copy)]
 pub struct XMLDashboardLayout {
@@ -240,3 +533,9 @@ impl XMLDashboardLayout {
         str::from_utf8(&self.name[..self.name_len]).unwrap_or("")
     }
 }
+
+impl GaugeReference {
+    pub fn name_str(&self) -> &str {
+        str::from_utf8(&self.gauge_name[..self.gauge_name_len]).unwrap_or("")
+    }
+}